@@ -0,0 +1,143 @@
+// font-kit/examples/cache-timing.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Times the lookups that the loader caches, each against a run that can't use the cache:
+//!
+//! * Small font tables, against reading them in full with `font_table_range()`.
+//!
+//! * Mapping repeated ASCII text to glyphs, against alternating between two characters that share
+//!   a slot in the character-to-glyph cache.
+//!
+//! * Extracting the outlines of the first glyphs a second time, against the first pass that
+//!   decodes them. There are fewer of them than the outline cache holds.
+
+extern crate font_kit;
+
+use font_kit::font::Font;
+use font_kit::hinting::HintingOptions;
+use font_kit::outline::OutlineBuilder;
+use std::env;
+use std::time::{Duration, Instant};
+
+static DEFAULT_FONT_PATH: &str = "resources/tests/eb-garamond/EBGaramond12-Regular.otf";
+
+static TABLE_TAGS: [&[u8; 4]; 5] = [b"head", b"hhea", b"OS/2", b"post", b"name"];
+
+static TEXT: &str = "The quick brown fox jumps over the lazy dog. 0123456789 ";
+
+const REPETITIONS: usize = 10000;
+
+const OUTLINE_GLYPH_COUNT: u32 = 512;
+
+fn time<F>(mut f: F) -> Duration
+where
+    F: FnMut(),
+{
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn report(what: &str, count: usize, uncached_label: &str, uncached: Duration, cached: Duration) {
+    println!("{}: {}", what, count);
+    println!("  {:<22} {:?}", uncached_label, uncached);
+    println!("  {:<22} {:?}", "cached:", cached);
+    println!(
+        "  speedup: {:.1}x",
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
+
+fn time_tables(font: &Font) {
+    let tags: Vec<u32> = TABLE_TAGS
+        .iter()
+        .map(|&&tag| u32::from_be_bytes(tag))
+        .filter(|&tag| font.table_len(tag).is_some())
+        .collect();
+    let load_tables = |load: &dyn Fn(u32) -> Option<Box<[u8]>>| {
+        time(|| {
+            for _ in 0..REPETITIONS {
+                for &tag in &tags {
+                    load(tag);
+                }
+            }
+        })
+    };
+    let uncached = load_tables(&|tag| font.font_table_range(tag, 0..font.table_len(tag)?));
+    let cached = load_tables(&|tag| font.load_font_table(tag));
+    report(
+        "table loads",
+        tags.len() * REPETITIONS,
+        "read from the font:",
+        uncached,
+        cached,
+    );
+}
+
+fn time_glyphs(font: &Font) {
+    let text: Vec<char> = TEXT.chars().collect();
+    let colliding: Vec<char> = ['A', '\u{141}']
+        .iter()
+        .cycle()
+        .take(text.len())
+        .cloned()
+        .collect();
+    let map_characters = |characters: &[char]| {
+        time(|| {
+            for _ in 0..REPETITIONS {
+                for &character in characters {
+                    font.glyph_for_char(character);
+                }
+            }
+        })
+    };
+    let misses = map_characters(&colliding);
+    let hits = map_characters(&text);
+    report(
+        "character lookups",
+        text.len() * REPETITIONS,
+        "colliding characters:",
+        misses,
+        hits,
+    );
+}
+
+fn time_outlines(font: &Font) {
+    let glyph_count = font.glyph_count().min(OUTLINE_GLYPH_COUNT);
+    let extract_outlines = || {
+        time(|| {
+            for glyph_id in 0..glyph_count {
+                let mut outline_builder = OutlineBuilder::new();
+                font.outline(glyph_id, HintingOptions::None, &mut outline_builder)
+                    .unwrap();
+            }
+        })
+    };
+    let first = extract_outlines();
+    let second = extract_outlines();
+    report(
+        "outlines",
+        glyph_count as usize,
+        "first pass:",
+        first,
+        second,
+    );
+}
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_FONT_PATH.to_owned());
+    let font = Font::from_path(&path, 0).unwrap();
+
+    time_tables(&font);
+    time_glyphs(&font);
+    time_outlines(&font);
+}
@@ -0,0 +1,160 @@
+# Generates FontKitTestAAT.ttf for the font-kit test suite.
+#
+# Usage: python3 generate.py FontKitTestAAT.ttf
+
+import struct, sys
+
+def u16(v): return struct.pack('>H', v & 0xffff)
+def i16(v): return struct.pack('>h', v)
+def u32(v): return struct.pack('>I', v & 0xffffffff)
+def fixed(v): return struct.pack('>i', int(round(v * 65536)))
+
+UPEM = 1000
+FAMILY = "Font Kit Test AAT"
+PS_NAME = "FontKitTestAAT-Regular"
+# Glyphs: .notdef, f, i, f_i, a, a.sc
+GLYPH_NAMES = ['.notdef', 'f', 'i', 'f_i', 'a', 'a.sc']
+NUM_GLYPHS = len(GLYPH_NAMES)
+CMAP = {ord('f'): 1, ord('i'): 2, ord('a'): 4}
+
+glyf = b''
+loca = b''.join(u16(0) for _ in range(NUM_GLYPHS + 1))
+head = (fixed(1.0) + fixed(1.0) + u32(0) + u32(0x5F0F3CF5) + u16(0x000B) + u16(UPEM)
+        + b'\0' * 16 + i16(0) + i16(0) + i16(0) + i16(0) + u16(0) + u16(8) + i16(2)
+        + i16(0) + i16(0))
+hhea = (fixed(1.0) + i16(800) + i16(-200) + i16(0) + u16(500) + i16(0) + i16(0) + i16(0)
+        + i16(1) + i16(0) + i16(0) + b'\0' * 8 + i16(0) + u16(NUM_GLYPHS))
+maxp = fixed(1.0) + u16(NUM_GLYPHS) + u16(0) + u16(0) + u16(0) + u16(0) + u16(2) + b'\0' * 16
+os2 = (u16(4) + i16(500) + u16(400) + u16(5) + u16(0) + b'\0' * 20 + i16(0) + b'\0' * 10
+       + b'\0' * 16 + b'NONE' + u16(0x40) + u16(0x61) + u16(0x69) + i16(800) + i16(-200)
+       + i16(0) + u16(800) + u16(200) + u32(1) + u32(0) + i16(500) + i16(700) + u16(0)
+       + u16(32) + u16(0))
+hmtx = b''.join(u16(500) + i16(0) for _ in range(NUM_GLYPHS))
+
+def post_table():
+    t = fixed(2.0) + fixed(0) + i16(-100) + i16(50) + u32(0) + b'\0' * 16
+    t += u16(NUM_GLYPHS)
+    strings = b''
+    n = 0
+    std = {'.notdef': 0, 'f': 73, 'i': 76, 'a': 68}
+    for name in GLYPH_NAMES:
+        if name in std:
+            t += u16(std[name])
+        else:
+            t += u16(258 + n); n += 1
+            strings += bytes([len(name)]) + name.encode()
+    return t + strings
+
+def cmap_table():
+    seg = [(c, c, (g - c) & 0xffff) for c, g in sorted(CMAP.items())] + [(0xffff, 0xffff, 1)]
+    n = len(seg)
+    sub = (u16(4) + u16(16 + 8 * n) + u16(0) + u16(2 * n) + u16(4) + u16(1) + u16(0))
+    sub += b''.join(u16(e) for s, e, d in seg) + u16(0)
+    sub += b''.join(u16(s) for s, e, d in seg)
+    sub += b''.join(u16(d) for s, e, d in seg)
+    sub += b''.join(u16(0) for _ in seg)
+    return u16(0) + u16(1) + u16(3) + u16(1) + u32(12) + sub
+
+names = {1: FAMILY, 2: "Regular", 4: FAMILY + " Regular", 6: PS_NAME}
+
+def name_table():
+    records = b''
+    strings = b''
+    for name_id, s in sorted(names.items()):
+        data = s.encode('utf-16-be')
+        records += u16(3) + u16(1) + u16(0x409) + u16(name_id) + u16(len(data)) + u16(len(strings))
+        strings += data
+    return u16(0) + u16(len(names)) + u16(6 + len(records)) + records + strings
+
+LIGATURE_FLAG = 0x1
+SMALL_CAPS_FLAG = 0x2
+
+def ligature_subtable():
+    # Classes: 0-3 are predefined, 4 is 'f' and 5 is 'i'.
+    n_classes = 6
+    # Class lookup, format 2 (segment single).
+    segments = [(1, 1, 4), (2, 2, 5)]
+    class_table = u16(2) + u16(6) + u16(len(segments)) + u16(12) + u16(1) + u16(0)
+    class_table += b''.join(u16(l) + u16(f) + u16(v) for f, l, v in segments)
+    while len(class_table) % 4: class_table += b'\0'
+    states = [
+        [0, 0, 0, 0, 1, 0],  # start of text
+        [0, 0, 0, 0, 1, 0],  # start of line
+        [0, 0, 0, 0, 1, 2],  # saw 'f'
+    ]
+    state_array = b''.join(u16(e) for row in states for e in row)
+    while len(state_array) % 4: state_array += b'\0'
+    entries = [
+        (0, 0x0000, 0),  # no-op
+        (2, 0x8000, 0),  # 'f': mark the component
+        (0, 0xA000, 0),  # 'i' after 'f': mark the component and form the ligature
+    ]
+    entry_table = b''.join(u16(s) + u16(f) + u16(a) for s, f, a in entries)
+    while len(entry_table) % 4: entry_table += b'\0'
+    # Actions pop 'i' and then 'f' (the last action); components index by glyph ID.
+    lig_actions = u32(0) + u32(0x80000000)
+    components = b''.join(u16(v) for v in [0, 0, 1, 0, 0, 0])
+    while len(components) % 4: components += b'\0'
+    ligatures = u16(0) + u16(3)
+    header_size = 28
+    class_off = header_size
+    state_off = class_off + len(class_table)
+    entry_off = state_off + len(state_array)
+    action_off = entry_off + len(entry_table)
+    comp_off = action_off + len(lig_actions)
+    lig_off = comp_off + len(components)
+    body = (u32(n_classes) + u32(class_off) + u32(state_off) + u32(entry_off) + u32(action_off)
+            + u32(comp_off) + u32(lig_off))
+    body += class_table + state_array + entry_table + lig_actions + components + ligatures
+    return u32(12 + len(body)) + u32(0x00000002) + u32(LIGATURE_FLAG) + body
+
+def noncontextual_subtable():
+    # Lookup format 8 (trimmed array): 'a' -> 'a.sc'.
+    body = u16(8) + u16(4) + u16(1) + u16(5)
+    return u32(12 + len(body)) + u32(0x00000004) + u32(SMALL_CAPS_FLAG) + body
+
+def morx_table():
+    features = [
+        (1, 2, LIGATURE_FLAG, 0xffffffff),                 # common ligatures on
+        (1, 3, 0, ~LIGATURE_FLAG & 0xffffffff),            # common ligatures off
+        (37, 1, SMALL_CAPS_FLAG, 0xffffffff),              # lower case small caps
+        (37, 0, 0, ~SMALL_CAPS_FLAG & 0xffffffff),         # default lower case
+    ]
+    feature_table = b''.join(u16(t) + u16(s) + u32(e) + u32(d) for t, s, e, d in features)
+    subtables = ligature_subtable() + noncontextual_subtable()
+    chain_length = 16 + len(feature_table) + len(subtables)
+    chain = u32(LIGATURE_FLAG) + u32(chain_length) + u32(len(features)) + u32(2)
+    chain += feature_table + subtables
+    return u16(2) + u16(0) + u32(1) + chain
+
+tables = {
+    b'OS/2': os2, b'cmap': cmap_table(), b'glyf': glyf, b'head': head, b'hhea': hhea,
+    b'hmtx': hmtx, b'loca': loca, b'maxp': maxp, b'morx': morx_table(), b'name': name_table(),
+    b'post': post_table(),
+}
+
+def checksum(data):
+    data += b'\0' * (-len(data) % 4)
+    return sum(struct.unpack('>%dI' % (len(data) // 4), data)) & 0xffffffff
+
+def build():
+    tags = sorted(tables)
+    n = len(tags)
+    entry_selector = n.bit_length() - 1
+    search_range = (1 << entry_selector) * 16
+    out = fixed(1.0) + u16(n) + u16(search_range) + u16(entry_selector) + u16(n * 16 - search_range)
+    offset = 12 + 16 * n
+    directory = b''
+    body = b''
+    for tag in tags:
+        data = tables[tag]
+        directory += tag + u32(checksum(data)) + u32(offset + len(body)) + u32(len(data))
+        body += data + b'\0' * (-len(data) % 4)
+    font = bytearray(out + directory + body)
+    head_offset = 12 + 16 * n + sum(len(tables[t]) + (-len(tables[t]) % 4)
+                                     for t in tags[:tags.index(b'head')])
+    adjustment = (0xB1B0AFBA - checksum(bytes(font))) & 0xffffffff
+    font[head_offset + 8:head_offset + 12] = u32(adjustment)
+    return bytes(font)
+
+open(sys.argv[1], 'wb').write(build())
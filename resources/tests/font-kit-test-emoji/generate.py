@@ -0,0 +1,191 @@
+# Generates FontKitTestEmojiCBDT.ttf and FontKitTestEmojiSbix.ttf for the font-kit test suite.
+#
+# Usage: python3 generate.py FontKitTestEmojiCBDT.ttf cbdt
+#        python3 generate.py FontKitTestEmojiSbix.ttf sbix
+
+import struct, sys, zlib
+
+def u16(v): return struct.pack('>H', v & 0xffff)
+def i16(v): return struct.pack('>h', v)
+def u32(v): return struct.pack('>I', v & 0xffffffff)
+def fixed(v): return struct.pack('>i', int(round(v * 65536)))
+
+UPEM = 1000
+FAMILY = "Font Kit Test Emoji"
+PS_NAME = "FontKitTestEmoji-Regular"
+
+# Glyph 1 (the emoji smiley) and glyph 2 (the text smiley): a rectangle, clockwise in y-up space.
+points = [(100, 0), (100, 700), (300, 700), (300, 0)]
+ADVANCES = [500, 400, 400]
+
+def glyf_table():
+    g = b''
+    g += i16(1) + i16(100) + i16(0) + i16(300) + i16(700)
+    g += u16(3)           # endPtsOfContours
+    g += u16(0)           # instructionLength
+    g += bytes([1, 1, 1, 1])
+    px = py = 0
+    xs = ys = b''
+    for x, y in points:
+        xs += i16(x - px); ys += i16(y - py); px, py = x, y
+    g += xs + ys
+    while len(g) % 4: g += b'\0'
+    return g
+
+glyf = glyf_table() * 2
+loca = u16(0) + u16(0) + u16(len(glyf) // 4) + u16(len(glyf) // 2)
+
+head = (fixed(1.0) + fixed(1.0) + u32(0) + u32(0x5F0F3CF5) + u16(0x000B) + u16(UPEM)
+        + b'\0' * 16 + i16(100) + i16(0) + i16(300) + i16(700) + u16(0) + u16(8) + i16(2)
+        + i16(0) + i16(0))
+assert len(head) == 54
+
+hhea = (fixed(1.0) + i16(800) + i16(-200) + i16(0) + u16(500) + i16(0) + i16(0) + i16(300)
+        + i16(1) + i16(0) + i16(0) + b'\0' * 8 + i16(0) + u16(3))
+assert len(hhea) == 36
+
+maxp = fixed(1.0) + u16(3) + u16(4) + u16(1) + u16(0) + u16(0) + u16(2) + b'\0' * 16
+assert len(maxp) == 32
+
+os2 = (u16(4) + i16(450) + u16(400) + u16(5) + u16(0) + b'\0' * 20 + i16(0) + b'\0' * 10
+       + b'\0' * 16 + b'NONE' + u16(0x40) + u16(0x231A) + u16(0x263A) + i16(800) + i16(-200)
+       + i16(0) + u16(800) + u16(200) + u32(1 << 17) + u32(0) + i16(500) + i16(700) + u16(0)
+       + u16(32) + u16(0))
+assert len(os2) == 96, len(os2)
+
+hmtx = u16(ADVANCES[0]) + i16(0) + u16(ADVANCES[1]) + i16(100) + u16(ADVANCES[2]) + i16(100)
+
+post = fixed(3.0) + fixed(0) + i16(-100) + i16(50) + u32(0) + b'\0' * 16
+assert len(post) == 32
+
+def format_4(seg):
+    n = len(seg)
+    entry_selector = n.bit_length() - 1
+    search_range = 2 << entry_selector
+    sub = (u16(4) + u16(16 + 8 * n) + u16(0) + u16(2 * n) + u16(search_range)
+           + u16(entry_selector) + u16(2 * n - search_range))
+    sub += b''.join(u16(e) for s, e, d in seg) + u16(0)
+    sub += b''.join(u16(s) for s, e, d in seg)
+    sub += b''.join(u16(d) for s, e, d in seg)
+    sub += b''.join(u16(0) for _ in seg)
+    assert len(sub) == 16 + 8 * n
+    return sub
+
+def format_12(groups):
+    sub = u16(12) + u16(0) + u32(16 + 12 * len(groups)) + u32(0) + u32(len(groups))
+    return sub + b''.join(u32(s) + u32(e) + u32(g) for s, e, g in groups)
+
+def format_14(mappings):
+    # One variation selector, U+FE0F, with non-default mappings only.
+    uvs = u32(len(mappings)) + b''.join(u32(c)[1:] + u16(g) for c, g in mappings)
+    return u16(14) + u32(10 + 11 + len(uvs)) + u32(1) + u32(0xFE0F)[1:] + u32(0) + u32(21) + uvs
+
+def cmap_table():
+    # U+231A, which defaults to emoji presentation, and U+263A, which defaults to text
+    # presentation, both map to the text glyph by default and to the emoji glyph with U+FE0F.
+    # U+1F600 has only the emoji glyph.
+    variations = format_14([(0x231A, 1), (0x263A, 1)])
+    bmp = format_4([(0x231A, 0x231A, (2 - 0x231A) & 0xffff),
+                    (0x263A, 0x263A, (2 - 0x263A) & 0xffff), (0xffff, 0xffff, 1)])
+    full = format_12([(0x231A, 0x231A, 2), (0x263A, 0x263A, 2), (0x1F600, 0x1F600, 1)])
+    offset = 4 + 8 * 3
+    t = u16(0) + u16(3)
+    t += u16(0) + u16(5) + u32(offset)
+    t += u16(3) + u16(1) + u32(offset + len(variations))
+    t += u16(3) + u16(10) + u32(offset + len(variations) + len(bmp))
+    return t + variations + bmp + full
+
+def png(size, rgba):
+    def chunk(kind, data):
+        return u32(len(data)) + kind + data + u32(zlib.crc32(kind + data))
+    rows = b''.join(b'\0' + bytes(rgba) * size for _ in range(size))
+    return (b'\x89PNG\r\n\x1a\n' + chunk(b'IHDR', u32(size) + u32(size) + bytes([8, 6, 0, 0, 0]))
+            + chunk(b'IDAT', zlib.compress(rows)) + chunk(b'IEND', b''))
+
+# (ppem, image size, color)
+STRIKES = [(16, 4, (255, 200, 0, 255)), (64, 8, (255, 0, 0, 255))]
+
+def sbit_line_metrics():
+    return bytes([96, 224 & 0xff, 8, 1, 0, 0, 0, 0, 0, 0, 0, 0])
+
+def cbdt_tables():
+    cbdt = u16(3) + u16(0)
+    sizes = b''
+    subtables = []
+    for ppem, size, color in STRIKES:
+        image = png(size, color)
+        offset = len(cbdt)
+        # Format 17: small metrics (height, width, bearingX, bearingY, advance), then the PNG.
+        cbdt += bytes([size, size, 0, size - 1, size]) + u32(len(image)) + image
+        array = u16(1) + u16(1) + u32(8)
+        header = u16(1) + u16(17) + u32(offset) + u32(0) + u32(len(cbdt) - offset)
+        subtables.append(array + header)
+    offset = 8 + 48 * len(STRIKES)
+    for (ppem, size, color), subtable in zip(STRIKES, subtables):
+        sizes += (u32(offset) + u32(len(subtable)) + u32(1) + u32(0) + sbit_line_metrics()
+                  + sbit_line_metrics() + u16(1) + u16(1) + bytes([ppem, ppem, 32, 1]))
+        offset += len(subtable)
+    cblc = u16(3) + u16(0) + u32(len(STRIKES)) + sizes + b''.join(subtables)
+    return {b'CBDT': cbdt, b'CBLC': cblc}
+
+def sbix_tables():
+    strikes = []
+    for ppem, size, color in STRIKES:
+        image = u16(0) + i16(-1) + b'png ' + png(size, color)
+        header_size = 4 + 4 * 4
+        strikes.append(u16(ppem) + u16(72) + u32(header_size) + u32(header_size)
+                       + u32(header_size + len(image)) + u32(header_size + len(image)) + image)
+    sbix = u16(1) + u16(1) + u32(len(strikes))
+    offset = len(sbix) + 4 * len(strikes)
+    for strike in strikes:
+        sbix += u32(offset)
+        offset += len(strike)
+    return {b'sbix': sbix + b''.join(strikes)}
+
+names = {
+    1: FAMILY,
+    2: "Regular",
+    4: FAMILY + " Regular",
+    6: PS_NAME,
+}
+
+def name_table():
+    records = b''
+    strings = b''
+    for name_id, s in sorted(names.items()):
+        data = s.encode('utf-16-be')
+        records += u16(3) + u16(1) + u16(0x409) + u16(name_id) + u16(len(data)) + u16(len(strings))
+        strings += data
+    return u16(0) + u16(len(names)) + u16(6 + len(records)) + records + strings
+
+tables = {
+    b'OS/2': os2, b'cmap': cmap_table(), b'glyf': glyf, b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'loca': loca,
+    b'maxp': maxp, b'name': name_table(), b'post': post,
+}
+
+def checksum(data):
+    data += b'\0' * (-len(data) % 4)
+    return sum(struct.unpack('>%dI' % (len(data) // 4), data)) & 0xffffffff
+
+def build():
+    tags = sorted(tables)
+    n = len(tags)
+    entry_selector = n.bit_length() - 1
+    search_range = (1 << entry_selector) * 16
+    out = fixed(1.0) + u16(n) + u16(search_range) + u16(entry_selector) + u16(n * 16 - search_range)
+    offset = 12 + 16 * n
+    directory = b''
+    body = b''
+    for tag in tags:
+        data = tables[tag]
+        directory += tag + u32(checksum(data)) + u32(offset + len(body)) + u32(len(data))
+        body += data + b'\0' * (-len(data) % 4)
+    font = bytearray(out + directory + body)
+    head_offset = 12 + 16 * n + sum(len(tables[t]) + (-len(tables[t]) % 4)
+                                     for t in tags[:tags.index(b'head')])
+    adjustment = (0xB1B0AFBA - checksum(bytes(font))) & 0xffffffff
+    font[head_offset + 8:head_offset + 12] = u32(adjustment)
+    return bytes(font)
+
+tables.update(cbdt_tables() if sys.argv[2] == 'cbdt' else sbix_tables())
+open(sys.argv[1], 'wb').write(build())
@@ -0,0 +1,19 @@
+# Generates FontKitTestHidden-Regular.pfa for the font-kit test suite from the Type 1 test font.
+#
+# Usage: python3 generate.py ../font-kit-test-type1/FontKitTest-Regular.pfa FontKitTestHidden-Regular.pfa
+
+import sys
+
+REPLACEMENTS = [
+    ("FontKitTest-Regular", "FontKitTestHidden-Regular"),
+    ("(Font Kit Test Regular)", "(.Font Kit Test Hidden Regular)"),
+    ("(Font Kit Test)", "(.Font Kit Test Hidden)"),
+    ('A minimal Type 1 font for the font-kit test suite, containing only "A".',
+     'A hidden copy of FontKitTest-Regular for the font-kit test suite.'),
+]
+
+font = open(sys.argv[1]).read()
+for old, new in REPLACEMENTS:
+    assert old in font
+    font = font.replace(old, new)
+open(sys.argv[2], 'w').write(font)
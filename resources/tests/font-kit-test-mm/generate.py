@@ -0,0 +1,93 @@
+# Generates FontKitTestMM.pfa for the font-kit test suite.
+#
+# Usage: python3 generate.py FontKitTestMM.pfa
+
+import sys
+
+def num(v):
+    if -107 <= v <= 107: return bytes([v + 139])
+    if 108 <= v <= 1131: v -= 108; return bytes([v // 256 + 247, v % 256])
+    if -1131 <= v <= -108: v = -v - 108; return bytes([v // 256 + 251, v % 256])
+    return bytes([255]) + v.to_bytes(4, 'big', signed=True)
+
+def cs(*ops):
+    out = bytearray()
+    for op in ops:
+        out += num(op) if isinstance(op, int) else op
+    return bytes(out)
+
+HSBW, RMOVETO, RLINETO, CLOSEPATH, ENDCHAR = b'\x0d', b'\x15', b'\x05', b'\x09', b'\x0e'
+CALLOTHERSUBR, POP = b'\x0c\x10', b'\x0c\x11'
+
+def encrypt(data, r):
+    out = bytearray()
+    for p in data:
+        c = p ^ (r >> 8); out.append(c); r = ((c + r) * 52845 + 22719) & 0xffff
+    return bytes(out)
+
+# Othersubr 14 blends one value from its value in the first master and its delta in the second.
+glyphs = {
+    b'.notdef': cs(0, 700, HSBW, ENDCHAR),
+    b'A': cs(0, 700, HSBW, 50, 0, RMOVETO,
+             500, 100, 2, 14, CALLOTHERSUBR, POP, 0, RLINETO,
+             -250, -50, 2, 14, CALLOTHERSUBR, POP, 700, RLINETO,
+             CLOSEPATH, ENDCHAR),
+}
+
+clear = b'''%!PS-AdobeFont-1.0: FontKitTestMM 001.000
+%%Title: FontKitTestMM
+%%Comment: A minimal Multiple Master Type 1 font for the font-kit test suite, containing only "A".
+15 dict begin
+/FontInfo 9 dict dup begin
+/version (001.000) readonly def
+/Notice (Public domain.) readonly def
+/FullName (Font Kit Test MM) readonly def
+/FamilyName (Font Kit Test MM) readonly def
+/Weight (All) readonly def
+/ItalicAngle 0 def
+/isFixedPitch false def
+/UnderlinePosition -100 def
+/UnderlineThickness 50 def
+/BlendDesignPositions [[0] [1]] def
+/BlendDesignMap [[[200 0] [900 1]]] def
+/BlendAxisTypes [/Weight] def
+end readonly def
+/FontName /FontKitTestMM def
+/Encoding 256 array
+0 1 255 {1 index exch /.notdef put} for
+dup 65 /A put
+readonly def
+/PaintType 0 def
+/FontType 1 def
+/FontMatrix [0.001 0 0 0.001 0 0] readonly def
+/FontBBox {0 0 700 700} readonly def
+/WeightVector [0.714286 0.285714] def
+currentdict end
+currentfile eexec
+'''
+
+private = bytearray(b'''dup /Private 8 dict dup begin
+/RD {string currentfile exch readstring pop} executeonly def
+/ND {noaccess def} executeonly def
+/NP {noaccess put} executeonly def
+/MinFeature {16 16} def
+/password 5839 def
+/BlueValues [0 0 700 700] def
+/Subrs 0 array
+ND
+2 index /CharStrings 2 dict dup begin
+''')
+for name, charstring in glyphs.items():
+    data = encrypt(b'\0\0\0\0' + charstring, 4330)
+    private += b'/' + name + b' ' + str(len(data)).encode() + b' RD ' + data + b' ND\n'
+private += b'''end
+end
+readonly put
+noaccess put
+dup /FontName get exch definefont pop
+mark currentfile closefile
+'''
+hexed = encrypt(b'\0\0\0\0' + bytes(private), 55665).hex()
+lines = [hexed[i:i + 64] for i in range(0, len(hexed), 64)]
+trailer = ('0' * 64 + '\n') * 8 + 'cleartomark\n'
+open(sys.argv[1], 'wb').write(clear + '\n'.join(lines).encode() + b'\n' + trailer.encode())
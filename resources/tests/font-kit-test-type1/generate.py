@@ -0,0 +1,87 @@
+# Generates FontKitTest-Regular.pfa and FontKitTest-Regular.pfb for the font-kit test suite.
+#
+# Usage: python3 generate.py FontKitTest-Regular.pfa FontKitTest-Regular.pfb
+
+import struct, sys
+
+def num(v):
+    if -107 <= v <= 107: return bytes([v + 139])
+    if 108 <= v <= 1131: v -= 108; return bytes([(v >> 8) + 247, v & 0xff])
+    if -1131 <= v <= -108: v = -v - 108; return bytes([(v >> 8) + 251, v & 0xff])
+    return bytes([255]) + struct.pack('>i', v)
+
+OPERATORS = {'hsbw': 13, 'rmoveto': 21, 'rlineto': 5, 'closepath': 9, 'endchar': 14}
+
+def cs(*ops):
+    out = b''
+    for op in ops:
+        out += num(op) if isinstance(op, int) else bytes([OPERATORS[op]])
+    return out
+
+def encrypt(data, r):
+    out = bytearray()
+    for b in data:
+        c = b ^ (r >> 8); r = ((c + r) * 52845 + 22719) & 0xffff; out.append(c)
+    return bytes(out)
+
+# Charstrings are encrypted with four leading random bytes, here zeros.
+notdef = encrypt(b'\0' * 4 + cs(0, 500, 'hsbw', 'endchar'), 4330)
+# A triangle with its base along the baseline.
+A = encrypt(b'\0' * 4 + cs(0, 600, 'hsbw', 50, 0, 'rmoveto', 500, 0, 'rlineto', -250, 700, 'rlineto',
+                          'closepath', 'endchar'), 4330)
+
+clear = b'''%!PS-AdobeFont-1.0: FontKitTest-Regular 001.000
+%%Title: FontKitTest-Regular
+%%Comment: A minimal Type 1 font for the font-kit test suite, containing only "A".
+11 dict begin
+/FontInfo 8 dict dup begin
+/version (001.000) readonly def
+/Notice (Public domain.) readonly def
+/FullName (Font Kit Test Regular) readonly def
+/FamilyName (Font Kit Test) readonly def
+/Weight (Regular) readonly def
+/ItalicAngle 0 def
+/isFixedPitch false def
+/UnderlinePosition -100 def
+/UnderlineThickness 50 def
+end readonly def
+/FontName /FontKitTest-Regular def
+/Encoding 256 array
+0 1 255 {1 index exch /.notdef put} for
+dup 65 /A put
+readonly def
+/PaintType 0 def
+/FontType 1 def
+/FontMatrix [0.001 0 0 0.001 0 0] readonly def
+/FontBBox {0 0 600 700} readonly def
+currentdict end
+currentfile eexec
+'''
+private = (b'''dup /Private 8 dict dup begin
+/RD {string currentfile exch readstring pop} executeonly def
+/ND {noaccess def} executeonly def
+/NP {noaccess put} executeonly def
+/MinFeature {16 16} def
+/password 5839 def
+/BlueValues [0 0 700 700] def
+/Subrs 0 array
+ND
+2 index /CharStrings 2 dict dup begin
+/.notdef ''' + str(len(notdef)).encode() + b' RD ' + notdef + b''' ND
+/A ''' + str(len(A)).encode() + b' RD ' + A + b''' ND
+end
+end
+readonly put
+noaccess put
+dup /FontName get exch definefont pop
+mark currentfile closefile
+''')
+binary = encrypt(b'\0' * 4 + private, 55665)
+trailer = (b'0' * 64 + b'\n') * 8 + b'cleartomark\n'
+h = binary.hex().encode()
+hexlines = b'\n'.join(h[i:i + 64] for i in range(0, len(h), 64)) + b'\n'
+open(sys.argv[1], 'wb').write(clear + hexlines + trailer)
+
+# PFB segments: ASCII, binary, ASCII, then end of file.
+seg = lambda t, d: bytes([0x80, t]) + struct.pack('<I', len(d)) + d
+open(sys.argv[2], 'wb').write(seg(1, clear) + seg(2, binary) + seg(1, trailer) + bytes([0x80, 3]))
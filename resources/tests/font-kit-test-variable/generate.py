@@ -0,0 +1,164 @@
+# Generates FontKitTestVariable.ttf for the font-kit test suite.
+#
+# Usage: python3 generate.py FontKitTestVariable.ttf
+
+import struct, sys
+
+def u16(v): return struct.pack('>H', v & 0xffff)
+def i16(v): return struct.pack('>h', v)
+def u32(v): return struct.pack('>I', v & 0xffffffff)
+def fixed(v): return struct.pack('>i', int(round(v * 65536)))
+def f2dot14(v): return struct.pack('>h', int(round(v * 16384)))
+
+UPEM = 1000
+FAMILY = "Font Kit Test Variable"
+PS_NAME = "FontKitTestVariable-Regular"
+
+# Glyph 1 ('I'): a rectangle, clockwise in y-up space.
+points = [(100, 0), (100, 700), (300, 700), (300, 0)]
+ADVANCES = [500, 400]
+
+def glyf_table():
+    g = b''
+    g += i16(1) + i16(100) + i16(0) + i16(300) + i16(700)
+    g += u16(3)           # endPtsOfContours
+    g += u16(0)           # instructionLength
+    g += bytes([1, 1, 1, 1])
+    px = py = 0
+    xs = ys = b''
+    for x, y in points:
+        xs += i16(x - px); ys += i16(y - py); px, py = x, y
+    g += xs + ys
+    while len(g) % 4: g += b'\0'
+    return g
+
+glyf = glyf_table()
+loca = u16(0) + u16(0) + u16(len(glyf) // 2)
+
+head = (fixed(1.0) + fixed(1.0) + u32(0) + u32(0x5F0F3CF5) + u16(0x000B) + u16(UPEM)
+        + b'\0' * 16 + i16(100) + i16(0) + i16(300) + i16(700) + u16(0) + u16(8) + i16(2)
+        + i16(0) + i16(0))
+assert len(head) == 54
+
+hhea = (fixed(1.0) + i16(800) + i16(-200) + i16(0) + u16(500) + i16(0) + i16(0) + i16(300)
+        + i16(1) + i16(0) + i16(0) + b'\0' * 8 + i16(0) + u16(2))
+assert len(hhea) == 36
+
+maxp = fixed(1.0) + u16(2) + u16(4) + u16(1) + u16(0) + u16(0) + u16(2) + b'\0' * 16
+assert len(maxp) == 32
+
+os2 = (u16(4) + i16(450) + u16(400) + u16(5) + u16(0) + b'\0' * 20 + i16(0) + b'\0' * 10
+       + b'\0' * 16 + b'NONE' + u16(0x40) + u16(0x49) + u16(0x49) + i16(800) + i16(-200)
+       + i16(0) + u16(800) + u16(200) + u32(1) + u32(0) + i16(500) + i16(700) + u16(0)
+       + u16(32) + u16(0))
+assert len(os2) == 96, len(os2)
+
+hmtx = u16(ADVANCES[0]) + i16(0) + u16(ADVANCES[1]) + i16(100)
+
+post = fixed(3.0) + fixed(0) + i16(-100) + i16(50) + u32(0) + b'\0' * 16
+assert len(post) == 32
+
+def cmap_table():
+    seg = [(0x49, 0x49, (1 - 0x49) & 0xffff), (0xffff, 0xffff, 1)]
+    n = len(seg)
+    sub = (u16(4) + u16(16 + 8 * n) + u16(0) + u16(2 * n) + u16(4) + u16(1) + u16(0))
+    sub += b''.join(u16(e) for s, e, d in seg) + u16(0)
+    sub += b''.join(u16(s) for s, e, d in seg)
+    sub += b''.join(u16(d) for s, e, d in seg)
+    sub += b''.join(u16(0) for _ in seg)
+    assert len(sub) == 16 + 8 * n
+    return u16(0) + u16(1) + u16(3) + u16(1) + u32(12) + sub
+
+names = {
+    1: FAMILY,
+    2: "Regular",
+    4: FAMILY + " Regular",
+    6: PS_NAME,
+    256: "Weight",
+    257: "Width",
+    258: "Thin",
+    259: "Bold",
+}
+
+def name_table():
+    records = b''
+    strings = b''
+    for name_id, s in sorted(names.items()):
+        data = s.encode('utf-16-be')
+        records += u16(3) + u16(1) + u16(0x409) + u16(name_id) + u16(len(data)) + u16(len(strings))
+        strings += data
+    return u16(0) + u16(len(names)) + u16(6 + len(records)) + records + strings
+
+AXES = [(b'wght', 100.0, 400.0, 900.0, 256), (b'wdth', 75.0, 100.0, 125.0, 257)]
+INSTANCES = [(258, [100.0, 100.0]), (259, [700.0, 100.0])]
+
+def fvar_table():
+    t = u16(1) + u16(0) + u16(16) + u16(2) + u16(len(AXES)) + u16(20) + u16(len(INSTANCES))
+    t += u16(4 + 4 * len(AXES))
+    for tag, lo, de, hi, nid in AXES:
+        t += tag + fixed(lo) + fixed(de) + fixed(hi) + u16(0) + u16(nid)
+    for nid, coords in INSTANCES:
+        t += u16(nid) + u16(0) + b''.join(fixed(c) for c in coords)
+    return t
+
+def gvar_table():
+    # One tuple at wght=max: the right edge and the advance move right by 200 units.
+    x_deltas = [0, 0, 200, 200, 0, 200, 0, 0]
+    data = b'\0'                                    # private point numbers: all points
+    data += bytes([0x40 | (len(x_deltas) - 1)]) + b''.join(i16(d) for d in x_deltas)
+    data += bytes([0x80 | (len(x_deltas) - 1)])     # y deltas: all zero
+    tuple_header = u16(len(data)) + u16(0x8000 | 0x2000) + f2dot14(1.0) + f2dot14(0.0)
+    glyph_data = u16(1) + u16(4 + len(tuple_header)) + tuple_header + data
+    while len(glyph_data) % 2: glyph_data += b'\0'
+    header_size = 20 + 3 * 2
+    t = (u16(1) + u16(0) + u16(len(AXES)) + u16(0) + u32(header_size) + u16(2) + u16(0)
+         + u32(header_size))
+    t += u16(0) + u16(0) + u16(len(glyph_data) // 2)
+    assert len(t) == header_size
+    return t + glyph_data
+
+def mvar_table():
+    # One value record: the x-height grows by 100 units at wght=max.
+    region_list = u16(len(AXES)) + u16(1)
+    region_list += f2dot14(0.0) + f2dot14(1.0) + f2dot14(1.0)
+    region_list += f2dot14(0.0) + f2dot14(0.0) + f2dot14(0.0)
+    item_data = u16(1) + u16(1) + u16(1) + u16(0) + i16(100)
+    store_header_size = 2 + 4 + 2 + 4
+    store = (u16(1) + u32(store_header_size) + u16(1)
+             + u32(store_header_size + len(region_list)))
+    store += region_list + item_data
+    header_size = 12 + 8
+    return (u16(1) + u16(0) + u16(0) + u16(8) + u16(1) + u16(header_size)
+            + b'xhgt' + u16(0) + u16(0) + store)
+
+tables = {
+    b'OS/2': os2, b'cmap': cmap_table(), b'fvar': fvar_table(), b'glyf': glyf,
+    b'gvar': gvar_table(), b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'loca': loca,
+    b'MVAR': mvar_table(), b'maxp': maxp, b'name': name_table(), b'post': post,
+}
+
+def checksum(data):
+    data += b'\0' * (-len(data) % 4)
+    return sum(struct.unpack('>%dI' % (len(data) // 4), data)) & 0xffffffff
+
+def build():
+    tags = sorted(tables)
+    n = len(tags)
+    entry_selector = n.bit_length() - 1
+    search_range = (1 << entry_selector) * 16
+    out = fixed(1.0) + u16(n) + u16(search_range) + u16(entry_selector) + u16(n * 16 - search_range)
+    offset = 12 + 16 * n
+    directory = b''
+    body = b''
+    for tag in tags:
+        data = tables[tag]
+        directory += tag + u32(checksum(data)) + u32(offset + len(body)) + u32(len(data))
+        body += data + b'\0' * (-len(data) % 4)
+    font = bytearray(out + directory + body)
+    head_offset = 12 + 16 * n + sum(len(tables[t]) + (-len(tables[t]) % 4)
+                                     for t in tags[:tags.index(b'head')])
+    adjustment = (0xB1B0AFBA - checksum(bytes(font))) & 0xffffffff
+    font[head_offset + 8:head_offset + 12] = u32(adjustment)
+    return bytes(font)
+
+open(sys.argv[1], 'wb').write(build())
@@ -0,0 +1,54 @@
+// font-kit/src/aat/mod.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Apple Advanced Typography (AAT), the layout tables that fonts from macOS use in place of
+//! OpenType's `GSUB` and `GPOS`.
+//!
+//! Only the glyph substitutions of the `morx` table are supported, via
+//! `Loader::apply_aat_features()`: noncontextual substitutions and ligatures.
+
+pub(crate) mod morx;
+
+/// A feature setting, as listed in the `feat` table and the `morx` feature table.
+///
+/// AAT features are identified by a feature type, such as ligatures, and a selector within that
+/// type, such as turning common ligatures on or off. See Apple's font feature registry for the
+/// full list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Feature {
+    /// The feature type.
+    pub feature_type: u16,
+    /// The setting of the feature type to select.
+    pub selector: u16,
+}
+
+impl Feature {
+    /// Turns on common ligatures, such as "fi".
+    pub const COMMON_LIGATURES_ON: Feature = Feature::new(1, 2);
+    /// Turns off common ligatures.
+    pub const COMMON_LIGATURES_OFF: Feature = Feature::new(1, 3);
+    /// Turns on rare ligatures.
+    pub const RARE_LIGATURES_ON: Feature = Feature::new(1, 4);
+    /// Turns off rare ligatures.
+    pub const RARE_LIGATURES_OFF: Feature = Feature::new(1, 5);
+    /// Displays lowercase letters normally.
+    pub const DEFAULT_LOWER_CASE: Feature = Feature::new(37, 0);
+    /// Displays lowercase letters as small capitals.
+    pub const LOWER_CASE_SMALL_CAPS: Feature = Feature::new(37, 1);
+
+    /// Creates a feature setting from a feature type and selector.
+    #[inline]
+    pub const fn new(feature_type: u16, selector: u16) -> Feature {
+        Feature {
+            feature_type,
+            selector,
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// font-kit/src/aat/morx.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Glyph substitution with the extended metamorphosis (`morx`) table.
+//!
+//! A `morx` table holds chains of subtables. Each chain starts from a set of default feature
+//! flags, which the requested features turn on and off; a subtable runs if any of its flags are
+//! on. Noncontextual subtables map glyphs one-to-one, and ligature subtables run a finite state
+//! machine over the glyphs, pushing components onto a stack and replacing them with a ligature.
+//!
+//! Rearrangement, contextual, and insertion subtables are skipped, as are vertical-only
+//! subtables. Malformed subtables leave the glyphs they have processed so far in place.
+
+use std::convert::TryFrom;
+
+use crate::aat::Feature;
+use crate::tables::{read_u16, read_u32};
+
+/// The placeholder that ligature subtables leave for components merged into a ligature.
+const DELETED_GLYPH: u32 = 0xffff;
+
+const COVERAGE_VERTICAL: u32 = 0x8000_0000;
+const COVERAGE_DESCENDING: u32 = 0x4000_0000;
+const COVERAGE_ALL_ORIENTATIONS: u32 = 0x2000_0000;
+
+const SUBTABLE_TYPE_LIGATURE: u32 = 2;
+const SUBTABLE_TYPE_NONCONTEXTUAL: u32 = 4;
+
+// The classes that every state table predefines.
+const CLASS_END_OF_TEXT: u16 = 0;
+const CLASS_OUT_OF_BOUNDS: u16 = 1;
+const CLASS_DELETED_GLYPH: u16 = 2;
+
+const LIGATURE_SET_COMPONENT: u16 = 0x8000;
+const LIGATURE_DONT_ADVANCE: u16 = 0x4000;
+const LIGATURE_PERFORM_ACTION: u16 = 0x2000;
+
+const LIGATURE_ACTION_LAST: u32 = 0x8000_0000;
+const LIGATURE_ACTION_STORE: u32 = 0x4000_0000;
+const LIGATURE_ACTION_OFFSET: u32 = 0x3fff_ffff;
+
+/// The number of component positions a ligature subtable can remember.
+const MAX_COMPONENTS: usize = 64;
+
+/// Applies the substitutions of every `morx` chain to `glyphs`, with `features` turned on or off
+/// relative to each chain's defaults.
+pub(crate) fn apply(morx: &[u8], glyphs: &[u32], features: &[Feature]) -> Vec<u32> {
+    let mut glyphs = glyphs.to_vec();
+    let chain_count = read_u32(morx, 4).unwrap_or(0);
+    let mut chain_offset = 8;
+    for _ in 0..chain_count {
+        let chain = match morx.get(chain_offset..) {
+            Some(chain) => chain,
+            None => break,
+        };
+        match read_u32(chain, 4) {
+            Some(chain_length) if chain_length > 0 => {
+                apply_chain(chain, &mut glyphs, features);
+                chain_offset += chain_length as usize;
+            }
+            _ => break,
+        }
+    }
+    glyphs.retain(|&glyph| glyph != DELETED_GLYPH);
+    glyphs
+}
+
+fn apply_chain(chain: &[u8], glyphs: &mut [u32], features: &[Feature]) -> Option<()> {
+    let mut flags = read_u32(chain, 0)?;
+    let feature_count = read_u32(chain, 8)? as usize;
+    let subtable_count = read_u32(chain, 12)?;
+
+    // Feature entries: type, setting, enable flags, and disable flags.
+    for feature in features {
+        for index in 0..feature_count {
+            let entry = 16 + index * 12;
+            if read_u16(chain, entry)? == feature.feature_type
+                && read_u16(chain, entry + 2)? == feature.selector
+            {
+                flags = (flags & read_u32(chain, entry + 8)?) | read_u32(chain, entry + 4)?;
+            }
+        }
+    }
+
+    let mut subtable_offset = 16 + feature_count * 12;
+    for _ in 0..subtable_count {
+        let subtable = chain.get(subtable_offset..)?;
+        let length = read_u32(subtable, 0)? as usize;
+        let coverage = read_u32(subtable, 4)?;
+        let subtable_flags = read_u32(subtable, 8)?;
+        let body = subtable.get(12..length)?;
+        subtable_offset += length;
+
+        if subtable_flags & flags == 0
+            || (coverage & COVERAGE_VERTICAL != 0 && coverage & COVERAGE_ALL_ORIENTATIONS == 0)
+        {
+            continue;
+        }
+
+        // Descending subtables process the glyphs from the end of the run.
+        let descending = coverage & COVERAGE_DESCENDING != 0;
+        if descending {
+            glyphs.reverse();
+        }
+        match coverage & 0xff {
+            SUBTABLE_TYPE_NONCONTEXTUAL => apply_noncontextual(body, glyphs),
+            SUBTABLE_TYPE_LIGATURE => {
+                apply_ligatures(body, glyphs);
+            }
+            _ => {}
+        }
+        if descending {
+            glyphs.reverse();
+        }
+    }
+    Some(())
+}
+
+fn apply_noncontextual(lookup_table: &[u8], glyphs: &mut [u32]) {
+    for glyph in glyphs.iter_mut().filter(|glyph| **glyph != DELETED_GLYPH) {
+        if let Some(substitute) = lookup(lookup_table, *glyph) {
+            *glyph = u32::from(substitute);
+        }
+    }
+}
+
+fn apply_ligatures(subtable: &[u8], glyphs: &mut [u32]) -> Option<()> {
+    // The extended state table header, followed by the ligature-specific offsets.
+    let class_count = read_u32(subtable, 0)? as usize;
+    let class_table = subtable.get(read_u32(subtable, 4)? as usize..)?;
+    let state_array = subtable.get(read_u32(subtable, 8)? as usize..)?;
+    let entry_table = subtable.get(read_u32(subtable, 12)? as usize..)?;
+    let actions = subtable.get(read_u32(subtable, 16)? as usize..)?;
+    let components = subtable.get(read_u32(subtable, 20)? as usize..)?;
+    let ligatures = subtable.get(read_u32(subtable, 24)? as usize..)?;
+
+    let mut stack: Vec<usize> = vec![];
+    let mut state = 0;
+    let mut index = 0;
+    // `DontAdvance` entries can loop forever in a broken font, so cap the total work.
+    let mut steps_left = glyphs.len() * 4 + 16;
+    loop {
+        let class = match glyphs.get(index) {
+            None => CLASS_END_OF_TEXT,
+            Some(&DELETED_GLYPH) => CLASS_DELETED_GLYPH,
+            Some(&glyph) => lookup(class_table, glyph).unwrap_or(CLASS_OUT_OF_BOUNDS),
+        };
+        let entry_index = read_u16(state_array, (state * class_count + class as usize) * 2)?;
+        let entry = entry_table.get(entry_index as usize * 6..)?;
+        let (next_state, entry_flags) = (read_u16(entry, 0)?, read_u16(entry, 2)?);
+
+        if entry_flags & LIGATURE_SET_COMPONENT != 0
+            && index < glyphs.len()
+            && stack.last() != Some(&index)
+        {
+            if stack.len() == MAX_COMPONENTS {
+                stack.remove(0);
+            }
+            stack.push(index);
+        }
+
+        if entry_flags & LIGATURE_PERFORM_ACTION != 0 {
+            let mut action_index = read_u16(entry, 4)? as usize;
+            let mut ligature_index = 0usize;
+            let mut cursor = stack.len();
+            loop {
+                if cursor == 0 {
+                    stack.clear();
+                    break;
+                }
+                cursor -= 1;
+                let position = stack[cursor];
+                let action = read_u32(actions, action_index * 4)?;
+                action_index += 1;
+
+                // The offset is a signed 30-bit number added to the component's glyph ID.
+                let offset = ((action & LIGATURE_ACTION_OFFSET) << 2) as i32 >> 2;
+                let component_index =
+                    usize::try_from(glyphs[position] as i64 + offset as i64).ok()?;
+                ligature_index += read_u16(components, component_index * 2)? as usize;
+
+                if action & (LIGATURE_ACTION_STORE | LIGATURE_ACTION_LAST) != 0 {
+                    glyphs[position] = u32::from(read_u16(ligatures, ligature_index * 2)?);
+                    for &merged_position in &stack[(cursor + 1)..] {
+                        glyphs[merged_position] = DELETED_GLYPH;
+                    }
+                    stack.truncate(cursor + 1);
+                }
+                if action & LIGATURE_ACTION_LAST != 0 {
+                    break;
+                }
+            }
+        }
+
+        state = next_state as usize;
+        if index == glyphs.len() || steps_left == 0 {
+            break;
+        }
+        steps_left -= 1;
+        if entry_flags & LIGATURE_DONT_ADVANCE == 0 {
+            index += 1;
+        }
+    }
+    Some(())
+}
+
+/// Looks up the 16-bit value for a glyph in an AAT lookup table.
+fn lookup(table: &[u8], glyph: u32) -> Option<u16> {
+    let glyph = u16::try_from(glyph).ok()?;
+    match read_u16(table, 0)? {
+        // Simple array, indexed by glyph ID.
+        0 => read_u16(table, 2 + glyph as usize * 2),
+        // Segment single and segment array, after a binary search header.
+        format @ 2 | format @ 4 => {
+            let unit_size = read_u16(table, 2)? as usize;
+            for index in 0..(read_u16(table, 4)? as usize) {
+                let segment = 12 + index * unit_size;
+                let (last, first) = (read_u16(table, segment)?, read_u16(table, segment + 2)?);
+                if first == 0xffff || glyph < first || glyph > last {
+                    continue;
+                }
+                let value = read_u16(table, segment + 4)?;
+                if format == 2 {
+                    return Some(value);
+                }
+                return read_u16(table, value as usize + (glyph - first) as usize * 2);
+            }
+            None
+        }
+        // Single table: glyph and value pairs.
+        6 => {
+            let unit_size = read_u16(table, 2)? as usize;
+            (0..(read_u16(table, 4)? as usize))
+                .map(|index| 12 + index * unit_size)
+                .find(|&unit| read_u16(table, unit) == Some(glyph))
+                .and_then(|unit| read_u16(table, unit + 2))
+        }
+        // Trimmed array, covering a range of glyph IDs.
+        8 => {
+            let first = read_u16(table, 2)?;
+            let index = glyph.checked_sub(first)?;
+            if index >= read_u16(table, 4)? {
+                return None;
+            }
+            read_u16(table, 6 + index as usize * 2)
+        }
+        // Extended trimmed array, with a variable value size.
+        10 => {
+            let unit_size = read_u16(table, 2)? as usize;
+            let index = glyph.checked_sub(read_u16(table, 4)?)?;
+            if index >= read_u16(table, 6)? {
+                return None;
+            }
+            let value = 8 + index as usize * unit_size;
+            match unit_size {
+                1 => table.get(value).map(|&value| u16::from(value)),
+                2 => read_u16(table, value),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
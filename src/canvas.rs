@@ -15,7 +15,11 @@ use pathfinder_geometry::rect::RectI;
 use pathfinder_geometry::vector::Vector2I;
 use std::cmp;
 use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
 
+use crate::hinting::HintingOptions;
 use crate::utils;
 
 lazy_static! {
@@ -74,7 +78,443 @@ impl Canvas {
         }
     }
 
-    #[allow(dead_code)]
+    /// Creates a canvas that takes ownership of an existing pixel buffer, without copying it.
+    ///
+    /// `stride` is the number of bytes between successive rows. Returns `None` if `stride` is
+    /// smaller than a row of pixels in `format`, or if `pixels` is shorter than `stride` times the
+    /// height. The buffer can be taken back from the `pixels` field once drawing is done. To draw
+    /// into memory that isn't a `Vec<u8>`, use `BorrowedCanvas`.
+    pub fn from_pixels(
+        pixels: Vec<u8>,
+        size: Vector2I,
+        stride: usize,
+        format: Format,
+    ) -> Option<Canvas> {
+        if !buffer_fits(pixels.len(), size, stride, format) {
+            return None;
+        }
+        Some(Canvas {
+            pixels,
+            size,
+            stride,
+            format,
+        })
+    }
+
+    /// Packs the canvas into a 1-bit-per-pixel bitmap, as used by XBM and other monochrome
+    /// formats.
+    ///
+    /// A pixel is set if its coverage, as a fraction from 0.0 to 1.0, is at least `threshold`.
+    /// Coverage is the value itself for `A8`, the green channel for `Rgb24` (matching the
+    /// conversion used when blitting to `A8`), and alpha for `Rgba32`. Bits are packed most
+    /// significant bit first, and each row is padded to a whole number of bytes.
+    pub fn to_packed_1bpp(&self, threshold: f32) -> Vec<u8> {
+        let width = self.size.x() as usize;
+        let packed_stride = utils::div_round_up(width, 8);
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        let coverage_offset = coverage_offset(self.format);
+
+        let mut packed = vec![0; packed_stride * self.size.y() as usize];
+        if packed.is_empty() {
+            return packed;
+        }
+        for (src_row, dest_row) in self
+            .pixels
+            .chunks(self.stride)
+            .zip(packed.chunks_mut(packed_stride))
+        {
+            for x in 0..width {
+                let coverage = src_row[x * bytes_per_pixel + coverage_offset];
+                if coverage as f32 / 255.0 >= threshold {
+                    dest_row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        packed
+    }
+
+    /// Unpacks a 1-bit-per-pixel bitmap, as produced by `to_packed_1bpp()`, into a new canvas in
+    /// `format`, with set bits at full coverage and clear bits at none.
+    ///
+    /// Bits are read most significant bit first, and each row is padded to a whole number of
+    /// bytes. Returns `None` if `size` is negative or `packed` is too short to hold it.
+    pub fn from_packed_1bpp(packed: &[u8], size: Vector2I, format: Format) -> Option<Canvas> {
+        if size.x() < 0 || size.y() < 0 {
+            return None;
+        }
+        let packed_stride = utils::div_round_up(size.x() as usize, 8);
+        if packed.len() < packed_stride.checked_mul(size.y() as usize)? {
+            return None;
+        }
+        let mut coverage = Canvas::new(size, Format::A8);
+        coverage.blit_from_bitmap_1bpp(Vector2I::default(), packed, size, packed_stride);
+        Some(convert(&coverage, format))
+    }
+
+    /// Expands the coverage in this canvas into a new `Rgba32` canvas filled with `color`, a
+    /// non-premultiplied RGBA value, whose alpha is scaled by the coverage of each pixel.
+    ///
+    /// This turns a rasterized glyph into, for example, white text on a transparent background.
+    /// Like every `Rgba32` canvas this crate produces, including those from `convert()` and from
+    /// rasterization with default settings, the result has premultiplied alpha. Coverage is read as
+    /// in `to_packed_1bpp()`.
+    pub fn to_rgba(&self, color: [u8; 4]) -> Canvas {
+        // Multiplies two fractions expressed as 0..=255.
+        fn mul(a: u8, b: u8) -> u8 {
+            ((a as u32 * b as u32 + 127) / 255) as u8
+        }
+
+        let mut rgba = Canvas::new(self.size, Format::Rgba32);
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        let coverage_offset = coverage_offset(self.format);
+        for (src_row, dest_row) in self
+            .pixels
+            .chunks(self.stride.max(1))
+            .zip(rgba.pixels.chunks_mut(rgba.stride.max(1)))
+        {
+            for (x, dest) in dest_row.chunks_exact_mut(4).enumerate() {
+                let alpha = mul(color[3], src_row[x * bytes_per_pixel + coverage_offset]);
+                dest.copy_from_slice(&[
+                    mul(color[0], alpha),
+                    mul(color[1], alpha),
+                    mul(color[2], alpha),
+                    alpha,
+                ]);
+            }
+        }
+        rgba
+    }
+
+    /// Returns a hash of the format, size, and pixel values of the canvas, for comparing
+    /// rasterizations against golden values in tests.
+    ///
+    /// Padding bytes past the end of each row are ignored, so canvases that differ only in stride
+    /// hash equally. The hash is 64-bit FNV-1a, which doesn't change between platforms or
+    /// releases.
+    pub fn content_hash(&self) -> u64 {
+        let format_index: u8 = match self.format {
+            Format::Rgba32 => 0,
+            Format::Rgb24 => 1,
+            Format::A8 => 2,
+        };
+        let mut hash = fnv1a(FNV_OFFSET_BASIS, &[format_index]);
+        hash = fnv1a(hash, &self.size.x().to_le_bytes());
+        hash = fnv1a(hash, &self.size.y().to_le_bytes());
+
+        let row_length = self.size.x() as usize * self.format.bytes_per_pixel() as usize;
+        for y in 0..self.size.y() as usize {
+            let row_start = y * self.stride;
+            hash = fnv1a(hash, &self.pixels[row_start..(row_start + row_length)]);
+        }
+        hash
+    }
+
+    /// Copies the pixels within `rect` into a new, tightly-strided canvas of the same format.
+    ///
+    /// `rect` is clamped to the bounds of this canvas; if they don't overlap, the result is empty.
+    pub fn crop(&self, rect: RectI) -> Canvas {
+        let rect = rect
+            .intersection(RectI::new(Vector2I::default(), self.size))
+            .unwrap_or_default();
+        let mut cropped = Canvas::new(rect.size(), self.format);
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        for y in 0..rect.height() as usize {
+            let src_start =
+                (rect.min_y() as usize + y) * self.stride + rect.min_x() as usize * bytes_per_pixel;
+            let dest_start = y * cropped.stride;
+            cropped.pixels[dest_start..(dest_start + cropped.stride)]
+                .copy_from_slice(&self.pixels[src_start..(src_start + cropped.stride)]);
+        }
+        cropped
+    }
+
+    /// Reverses the order of the rows in place, turning a top-down bitmap into a bottom-up one
+    /// (as OpenGL expects for textures) or vice versa.
+    pub fn flip_vertical(&mut self) {
+        let height = self.size.y() as usize;
+        for y in 0..(height / 2) {
+            let (top, bottom) = self.pixels.split_at_mut((height - 1 - y) * self.stride);
+            top[(y * self.stride)..((y + 1) * self.stride)]
+                .swap_with_slice(&mut bottom[0..self.stride]);
+        }
+    }
+
+    /// Returns a copy of this canvas with the order of the rows reversed.
+    ///
+    /// See `flip_vertical()`.
+    pub fn flipped(&self) -> Canvas {
+        let mut flipped = Canvas::with_stride(self.size, self.stride, self.format);
+        // Buffers from `from_pixels()` can run past the last row.
+        for (src_row, dest_row) in self
+            .pixels
+            .chunks_exact(self.stride.max(1))
+            .take(self.size.y() as usize)
+            .rev()
+            .zip(flipped.pixels.chunks_exact_mut(self.stride.max(1)))
+        {
+            dest_row.copy_from_slice(src_row);
+        }
+        flipped
+    }
+
+    /// Writes the canvas to `path` as an uncompressed BMP file, for inspecting rasterized glyphs
+    /// while debugging.
+    ///
+    /// `A8` canvases are written as 24-bit grayscale, `Rgb24` canvases as 24-bit color, and
+    /// `Rgba32` canvases as 32-bit color with alpha. Pixel values are written unchanged.
+    pub fn write_bmp<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        const FILE_HEADER_LEN: u32 = 14;
+        const INFO_HEADER_LEN: u32 = 40;
+
+        let bits_per_pixel: u16 = match self.format {
+            Format::Rgba32 => 32,
+            Format::Rgb24 | Format::A8 => 24,
+        };
+        let width = self.size.x().max(0) as usize;
+        let height = self.size.y().max(0) as usize;
+        let row_len = width * bits_per_pixel as usize / 8;
+        let padded_row_len = (row_len + 3) & !3;
+        let image_len = (padded_row_len * height) as u32;
+        let pixel_offset = FILE_HEADER_LEN + INFO_HEADER_LEN;
+
+        let mut bmp = Vec::with_capacity((pixel_offset + image_len) as usize);
+        // BITMAPFILEHEADER
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&(pixel_offset + image_len).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&pixel_offset.to_le_bytes());
+        // BITMAPINFOHEADER, uncompressed, 72 DPI
+        bmp.extend_from_slice(&INFO_HEADER_LEN.to_le_bytes());
+        bmp.extend_from_slice(&(width as i32).to_le_bytes());
+        bmp.extend_from_slice(&(height as i32).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&bits_per_pixel.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&image_len.to_le_bytes());
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&[0; 8]);
+
+        // BMP rows run bottom-up, with each pixel stored as BGR(A).
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        for y in (0..height).rev() {
+            let row_start = y * self.stride;
+            let row = &self.pixels[row_start..(row_start + width * bytes_per_pixel)];
+            for pixel in row.chunks_exact(bytes_per_pixel) {
+                match *pixel {
+                    [value] => bmp.extend_from_slice(&[value, value, value]),
+                    [r, g, b] => bmp.extend_from_slice(&[b, g, r]),
+                    [r, g, b, a] => bmp.extend_from_slice(&[b, g, r, a]),
+                    _ => unreachable!(),
+                }
+            }
+            bmp.resize(bmp.len() + padded_row_len - row_len, 0);
+        }
+
+        fs::write(path, bmp)
+    }
+
+    /// Raises every component within `rect` to the power `1 / gamma`, treating it as a fraction
+    /// from 0.0 to 1.0.
+    pub(crate) fn apply_gamma(&mut self, rect: RectI, gamma: f32) {
+        let rect = match rect.intersection(RectI::new(Vector2I::default(), self.size)) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        let mut lut = [0; 256];
+        for (value, entry) in lut.iter_mut().enumerate() {
+            *entry = ((value as f32 / 255.0).powf(1.0 / gamma) * 255.0).round() as u8;
+        }
+
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        for y in rect.min_y()..rect.max_y() {
+            let row_start = y as usize * self.stride;
+            let start = row_start + rect.min_x() as usize * bytes_per_pixel;
+            let end = row_start + rect.max_x() as usize * bytes_per_pixel;
+            for component in &mut self.pixels[start..end] {
+                *component = lut[*component as usize];
+            }
+        }
+    }
+
+    /// Turns every component within `rect` fully on or off by comparing it against a 4×4 Bayer
+    /// matrix tiled from the canvas origin.
+    pub(crate) fn apply_ordered_dither(&mut self, rect: RectI) {
+        // Thresholds at the centers of 16 evenly spaced intervals, so a coverage of n/16 sets n
+        // of every 16 pixels.
+        const BAYER_MATRIX: [[u8; 4]; 4] =
+            [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+        let rect = match rect.intersection(RectI::new(Vector2I::default(), self.size)) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        for y in rect.min_y()..rect.max_y() {
+            let row = &BAYER_MATRIX[y as usize % 4];
+            let row_start = y as usize * self.stride;
+            for x in rect.min_x()..rect.max_x() {
+                let threshold = (row[x as usize % 4] as u32 * 2 + 1) * 255 / 32;
+                let start = row_start + x as usize * bytes_per_pixel;
+                for component in &mut self.pixels[start..(start + bytes_per_pixel)] {
+                    *component = if *component as u32 > threshold {
+                        0xff
+                    } else {
+                        0
+                    };
+                }
+            }
+        }
+    }
+
+    pub(crate) fn apply_threshold(&mut self, rect: RectI, threshold: f32) {
+        let rect = match rect.intersection(RectI::new(Vector2I::default(), self.size)) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        let bytes_per_pixel = self.format.bytes_per_pixel() as usize;
+        for y in rect.min_y()..rect.max_y() {
+            let row_start = y as usize * self.stride;
+            let (start, end) = (
+                row_start + rect.min_x() as usize * bytes_per_pixel,
+                row_start + rect.max_x() as usize * bytes_per_pixel,
+            );
+            for component in &mut self.pixels[start..end] {
+                *component = if *component as f32 / 255.0 >= threshold {
+                    0xff
+                } else {
+                    0
+                };
+            }
+        }
+    }
+
+    // Divides the color channels of the pixels in `rect` of this `Rgba32` canvas by their alpha.
+    pub(crate) fn unpremultiply(&mut self, rect: RectI) {
+        debug_assert_eq!(self.format, Format::Rgba32);
+        let rect = match rect.intersection(RectI::new(Vector2I::default(), self.size)) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        for y in rect.min_y()..rect.max_y() {
+            let row_start = y as usize * self.stride;
+            let (start, end) = (
+                row_start + rect.min_x() as usize * 4,
+                row_start + rect.max_x() as usize * 4,
+            );
+            for pixel in self.pixels[start..end].chunks_mut(4) {
+                let alpha = pixel[3] as u32;
+                if alpha == 0 || alpha == 255 {
+                    continue;
+                }
+                for component in &mut pixel[0..3] {
+                    *component = ((*component as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+                }
+            }
+        }
+    }
+
+    /// Composites `color`, a non-premultiplied RGBA value, over this `Rgba32` canvas through the
+    /// `A8` or `Rgb24` coverage mask `coverage` placed at `origin`.
+    ///
+    /// The result is premultiplied. `Rgb24` masks give per-channel coverage, with the green
+    /// channel used for alpha.
+    pub(crate) fn composite_coverage(
+        &mut self,
+        origin: Vector2I,
+        coverage: &Canvas,
+        color: [u8; 4],
+    ) {
+        debug_assert_eq!(self.format, Format::Rgba32);
+        let rect = RectI::new(origin, coverage.size);
+        let rect = match rect.intersection(RectI::new(Vector2I::default(), self.size)) {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        // Multiplies two fractions expressed as 0..=255.
+        fn mul(a: u8, b: u8) -> u8 {
+            ((a as u32 * b as u32 + 127) / 255) as u8
+        }
+
+        let alpha = color[3];
+        let premultiplied = [
+            mul(color[0], alpha),
+            mul(color[1], alpha),
+            mul(color[2], alpha),
+        ];
+        let src_bytes_per_pixel = coverage.format.bytes_per_pixel() as usize;
+        for y in rect.min_y()..rect.max_y() {
+            for x in rect.min_x()..rect.max_x() {
+                let src_start = (y - origin.y()) as usize * coverage.stride
+                    + (x - origin.x()) as usize * src_bytes_per_pixel;
+                let mask = match coverage.format {
+                    Format::A8 => [coverage.pixels[src_start]; 4],
+                    Format::Rgb24 | Format::Rgba32 => {
+                        let rgb = &coverage.pixels[src_start..(src_start + 3)];
+                        [rgb[0], rgb[1], rgb[2], rgb[1]]
+                    }
+                };
+
+                let dest_start = y as usize * self.stride + x as usize * 4;
+                let dest = &mut self.pixels[dest_start..(dest_start + 4)];
+                let src_alpha = mul(alpha, mask[3]);
+                for channel in 0..3 {
+                    let src = mul(premultiplied[channel], mask[channel]);
+                    dest[channel] = src.saturating_add(mul(dest[channel], 255 - src_alpha));
+                }
+                dest[3] = src_alpha.saturating_add(mul(dest[3], 255 - src_alpha));
+            }
+        }
+    }
+
+    /// Fills this `Rgb24` or `Rgba32` canvas with `background` mixed with `text` by the `A8` or
+    /// `Rgb24` coverage mask `coverage`, which must be the same size.
+    ///
+    /// Colors are sRGB and are mixed in linear light. `Rgb24` masks mix each channel by its own
+    /// coverage.
+    pub(crate) fn blend_coverage(&mut self, coverage: &Canvas, background: [u8; 3], text: [u8; 3]) {
+        debug_assert_eq!(self.size, coverage.size);
+        let mut to_linear = [0.0; 256];
+        for (value, entry) in to_linear.iter_mut().enumerate() {
+            *entry = srgb_to_linear(value as f32 / 255.0);
+        }
+        let background = background.map(|component| to_linear[component as usize]);
+        let text = text.map(|component| to_linear[component as usize]);
+
+        let (dest_bytes_per_pixel, src_bytes_per_pixel) = (
+            self.format.bytes_per_pixel() as usize,
+            coverage.format.bytes_per_pixel() as usize,
+        );
+        for y in 0..(self.size.y() as usize) {
+            for x in 0..(self.size.x() as usize) {
+                let src_start = y * coverage.stride + x * src_bytes_per_pixel;
+                let mask = match coverage.format {
+                    Format::A8 => [coverage.pixels[src_start]; 3],
+                    Format::Rgb24 | Format::Rgba32 => {
+                        let rgb = &coverage.pixels[src_start..(src_start + 3)];
+                        [rgb[0], rgb[1], rgb[2]]
+                    }
+                };
+
+                let dest_start = y * self.stride + x * dest_bytes_per_pixel;
+                let dest = &mut self.pixels[dest_start..(dest_start + dest_bytes_per_pixel)];
+                for channel in 0..3 {
+                    let alpha = mask[channel] as f32 / 255.0;
+                    let linear = background[channel] * (1.0 - alpha) + text[channel] * alpha;
+                    dest[channel] = (linear_to_srgb(linear) * 255.0).round() as u8;
+                }
+                if let Some(alpha) = dest.get_mut(3) {
+                    *alpha = 255;
+                }
+            }
+        }
+    }
+
     pub(crate) fn blit_from_canvas(&mut self, src: &Canvas) {
         self.blit_from(
             Vector2I::default(),
@@ -85,7 +525,6 @@ impl Canvas {
         )
     }
 
-    #[allow(dead_code)]
     pub(crate) fn blit_from(
         &mut self,
         dst_point: Vector2I,
@@ -94,34 +533,20 @@ impl Canvas {
         src_stride: usize,
         src_format: Format,
     ) {
-        let dst_rect = RectI::new(dst_point, src_size);
-        let dst_rect = dst_rect.intersection(RectI::new(Vector2I::default(), self.size));
-        let dst_rect = match dst_rect {
-            Some(dst_rect) => dst_rect,
-            None => return,
-        };
+        self.as_borrowed()
+            .blit_from(dst_point, src_bytes, src_size, src_stride, src_format)
+    }
 
-        match (self.format, src_format) {
-            (Format::A8, Format::A8)
-            | (Format::Rgb24, Format::Rgb24)
-            | (Format::Rgba32, Format::Rgba32) => {
-                self.blit_from_with::<BlitMemcpy>(dst_rect, src_bytes, src_stride, src_format)
-            }
-            (Format::A8, Format::Rgb24) => {
-                self.blit_from_with::<BlitRgb24ToA8>(dst_rect, src_bytes, src_stride, src_format)
-            }
-            (Format::Rgb24, Format::A8) => {
-                self.blit_from_with::<BlitA8ToRgb24>(dst_rect, src_bytes, src_stride, src_format)
-            }
-            (Format::Rgb24, Format::Rgba32) => self
-                .blit_from_with::<BlitRgba32ToRgb24>(dst_rect, src_bytes, src_stride, src_format),
-            (Format::Rgba32, Format::Rgb24) => self
-                .blit_from_with::<BlitRgb24ToRgba32>(dst_rect, src_bytes, src_stride, src_format),
-            (Format::Rgba32, Format::A8) | (Format::A8, Format::Rgba32) => unimplemented!(),
+    #[inline]
+    pub(crate) fn as_borrowed(&mut self) -> BorrowedCanvas<'_> {
+        BorrowedCanvas {
+            pixels: &mut self.pixels,
+            size: self.size,
+            stride: self.stride,
+            format: self.format,
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) fn blit_from_bitmap_1bpp(
         &mut self,
         dst_point: Vector2I,
@@ -165,6 +590,85 @@ impl Canvas {
             }
         }
     }
+}
+
+/// A bitmap surface that draws into pixels borrowed from the caller.
+///
+/// Unlike `Canvas`, this doesn't own its buffer, so glyphs can be rasterized straight into memory
+/// that belongs to something else, such as a mapped texture, with
+/// `Loader::rasterize_glyph_into()`.
+pub struct BorrowedCanvas<'a> {
+    /// The raw pixel data.
+    pub pixels: &'a mut [u8],
+    /// The size of the buffer, in pixels.
+    pub size: Vector2I,
+    /// The number of *bytes* between successive rows.
+    pub stride: usize,
+    /// The image format of the canvas.
+    pub format: Format,
+}
+
+impl<'a> BorrowedCanvas<'a> {
+    /// Wraps a borrowed pixel buffer with the given pixel size, stride, and format.
+    ///
+    /// Returns `None` if `stride` is smaller than a row of pixels in `format`, or if `pixels` is
+    /// shorter than `stride` times the height.
+    pub fn new(
+        pixels: &'a mut [u8],
+        size: Vector2I,
+        stride: usize,
+        format: Format,
+    ) -> Option<BorrowedCanvas<'a>> {
+        if !buffer_fits(pixels.len(), size, stride, format) {
+            return None;
+        }
+        Some(BorrowedCanvas {
+            pixels,
+            size,
+            stride,
+            format,
+        })
+    }
+
+    pub(crate) fn blit_from(
+        &mut self,
+        dst_point: Vector2I,
+        src_bytes: &[u8],
+        src_size: Vector2I,
+        src_stride: usize,
+        src_format: Format,
+    ) {
+        let dst_rect = RectI::new(dst_point, src_size);
+        let dst_rect = dst_rect.intersection(RectI::new(Vector2I::default(), self.size));
+        let dst_rect = match dst_rect {
+            Some(dst_rect) => dst_rect,
+            None => return,
+        };
+
+        match (self.format, src_format) {
+            (Format::A8, Format::A8)
+            | (Format::Rgb24, Format::Rgb24)
+            | (Format::Rgba32, Format::Rgba32) => {
+                self.blit_from_with::<BlitMemcpy>(dst_rect, src_bytes, src_stride, src_format)
+            }
+            (Format::A8, Format::Rgb24) => {
+                self.blit_from_with::<BlitRgb24ToA8>(dst_rect, src_bytes, src_stride, src_format)
+            }
+            (Format::Rgb24, Format::A8) => {
+                self.blit_from_with::<BlitA8ToRgb24>(dst_rect, src_bytes, src_stride, src_format)
+            }
+            (Format::Rgb24, Format::Rgba32) => self
+                .blit_from_with::<BlitRgba32ToRgb24>(dst_rect, src_bytes, src_stride, src_format),
+            (Format::Rgba32, Format::Rgb24) => self
+                .blit_from_with::<BlitRgb24ToRgba32>(dst_rect, src_bytes, src_stride, src_format),
+            (Format::Rgba32, Format::A8) => {
+                self.blit_from_with::<BlitA8ToRgba32>(dst_rect, src_bytes, src_stride, src_format)
+            }
+            (Format::A8, Format::Rgba32) => {
+                self.blit_from_with::<BlitRgba32ToA8>(dst_rect, src_bytes, src_stride, src_format)
+            }
+        }
+    }
 
     fn blit_from_with<B: Blit>(
         &mut self,
@@ -191,6 +695,26 @@ impl Canvas {
     }
 }
 
+/// Converts a canvas to another format, returning a new canvas of the same size with rows packed
+/// tightly.
+///
+/// Coverage carries across formats as `to_packed_1bpp()` reads it:
+///
+/// * `A8` becomes gray in `Rgb24` and white in `Rgba32`. `Rgba32` output is premultiplied, as
+///   it is from `Canvas::to_rgba()`.
+///
+/// * `Rgb24` becomes opaque in `Rgba32`, and its green channel becomes `A8`.
+///
+/// * `Rgba32` drops its alpha in `Rgb24`, and its alpha becomes `A8`.
+///
+/// Converting to the same format copies the canvas. For bilevel bitmaps, use
+/// `Canvas::to_packed_1bpp()` and `Canvas::from_packed_1bpp()`.
+pub fn convert(src: &Canvas, dst_format: Format) -> Canvas {
+    let mut dst = Canvas::new(src.size, dst_format);
+    dst.blit_from_canvas(src);
+    dst
+}
+
 impl fmt::Debug for Canvas {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Canvas")
@@ -202,6 +726,66 @@ impl fmt::Debug for Canvas {
     }
 }
 
+impl<'a> fmt::Debug for BorrowedCanvas<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BorrowedCanvas")
+            .field("pixels", &self.pixels.len())
+            .field("size", &self.size)
+            .field("stride", &self.stride)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+// Returns true if a buffer of `len` bytes holds an image of the given size, stride, and format.
+fn buffer_fits(len: usize, size: Vector2I, stride: usize, format: Format) -> bool {
+    if size.x() < 0 || size.y() < 0 {
+        return false;
+    }
+    let row_length = size.x() as usize * format.bytes_per_pixel() as usize;
+    match stride.checked_mul(size.y() as usize) {
+        Some(length) => stride >= row_length && len >= length,
+        None => false,
+    }
+}
+
+// Returns the byte offset within a pixel of the component that holds its coverage: the value
+// itself for `A8`, green for `Rgb24`, and alpha for `Rgba32`.
+fn coverage_offset(format: Format) -> usize {
+    match format {
+        Format::A8 => 0,
+        Format::Rgb24 => 1,
+        Format::Rgba32 => 3,
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+// Converts an sRGB component from 0.0 to 1.0 to linear light.
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Converts a linear light component from 0.0 to 1.0 to sRGB.
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 /// The image format for the canvas.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Format {
@@ -258,6 +842,316 @@ pub enum RasterizationOptions {
     SubpixelAa,
 }
 
+/// The filter applied to subpixel antialiased coverage to reduce color fringes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LcdFilter {
+    /// No filtering. Glyphs are sharpest, but fringes are strongest.
+    None,
+    /// A five-tap filter that balances sharpness against fringes.
+    Default,
+    /// A lighter five-tap filter, giving sharper glyphs with a little more fringing.
+    Light,
+    /// The three-tap filter of older FreeType versions.
+    Legacy,
+}
+
+/// A complete description of how to rasterize a glyph, bundling hinting, antialiasing, and
+/// coverage post-processing.
+///
+/// Build one with `RasterizationOptionsBuilder` and pass it to
+/// `Loader::rasterize_glyph_with_settings()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterizationSettings {
+    hinting_options: HintingOptions,
+    rasterization_options: RasterizationOptions,
+    gamma: f32,
+    stem_darkening: Option<bool>,
+    stem_darkening_strength: f32,
+    background_color: Option<[u8; 3]>,
+    text_color: [u8; 3],
+    dithering: bool,
+    threshold: Option<f32>,
+    lcd_filter: Option<LcdFilter>,
+    premultiplied: bool,
+}
+
+impl RasterizationSettings {
+    /// Returns the hinting options.
+    #[inline]
+    pub fn hinting_options(&self) -> HintingOptions {
+        self.hinting_options
+    }
+
+    /// Returns the antialiasing strategy.
+    #[inline]
+    pub fn rasterization_options(&self) -> RasterizationOptions {
+        self.rasterization_options
+    }
+
+    /// Returns the gamma applied to coverage values. 1.0 leaves them linear.
+    #[inline]
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Returns whether stems are darkened, or `None` to use the rasterizer's default.
+    #[inline]
+    pub fn stem_darkening(&self) -> Option<bool> {
+        self.stem_darkening
+    }
+
+    /// Returns the strength of stem darkening, relative to the rasterizer's standard amount.
+    #[inline]
+    pub fn stem_darkening_strength(&self) -> f32 {
+        self.stem_darkening_strength
+    }
+
+    /// Returns the opaque background that coverage is blended against, or `None` to write raw
+    /// coverage.
+    #[inline]
+    pub fn background_color(&self) -> Option<[u8; 3]> {
+        self.background_color
+    }
+
+    /// Returns the color of the text when it's blended against a background.
+    #[inline]
+    pub fn text_color(&self) -> [u8; 3] {
+        self.text_color
+    }
+
+    /// Returns whether bilevel output is dithered rather than thresholded.
+    #[inline]
+    pub fn dithering(&self) -> bool {
+        self.dithering
+    }
+
+    /// Returns the coverage threshold for bilevel output, or `None` to use the rasterizer's own
+    /// bilevel mode.
+    #[inline]
+    pub fn threshold(&self) -> Option<f32> {
+        self.threshold
+    }
+
+    /// Returns the filter applied to subpixel antialiased coverage, or `None` to use the
+    /// rasterizer's default.
+    #[inline]
+    pub fn lcd_filter(&self) -> Option<LcdFilter> {
+        self.lcd_filter
+    }
+
+    /// Returns whether `Rgba32` output has premultiplied alpha.
+    #[inline]
+    pub fn premultiplied(&self) -> bool {
+        self.premultiplied
+    }
+}
+
+impl Default for RasterizationSettings {
+    #[inline]
+    fn default() -> RasterizationSettings {
+        RasterizationOptionsBuilder::new().build()
+    }
+}
+
+impl From<RasterizationOptions> for RasterizationSettings {
+    #[inline]
+    fn from(rasterization_options: RasterizationOptions) -> RasterizationSettings {
+        RasterizationOptionsBuilder::new()
+            .rasterization_options(rasterization_options)
+            .build()
+    }
+}
+
+/// Composes a `RasterizationSettings` value.
+///
+/// The defaults are no hinting, grayscale antialiasing, linear (1.0) gamma, the rasterizer's
+/// default stem darkening and LCD filter, raw coverage output with no background, the rasterizer's
+/// own bilevel mode with no threshold or dithering, and premultiplied alpha.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterizationOptionsBuilder {
+    settings: RasterizationSettings,
+}
+
+impl RasterizationOptionsBuilder {
+    /// Creates a builder with the default settings.
+    #[inline]
+    pub fn new() -> RasterizationOptionsBuilder {
+        RasterizationOptionsBuilder {
+            settings: RasterizationSettings {
+                hinting_options: HintingOptions::None,
+                rasterization_options: RasterizationOptions::GrayscaleAa,
+                gamma: 1.0,
+                stem_darkening: None,
+                stem_darkening_strength: 1.0,
+                background_color: None,
+                text_color: [0, 0, 0],
+                dithering: false,
+                threshold: None,
+                lcd_filter: None,
+                premultiplied: true,
+            },
+        }
+    }
+
+    /// Sets how hinting (grid fitting) is performed.
+    #[inline]
+    pub fn hinting_options(mut self, hinting_options: HintingOptions) -> Self {
+        self.settings.hinting_options = hinting_options;
+        self
+    }
+
+    /// Sets the antialiasing strategy.
+    #[inline]
+    pub fn rasterization_options(mut self, rasterization_options: RasterizationOptions) -> Self {
+        self.settings.rasterization_options = rasterization_options;
+        self
+    }
+
+    /// Switches between subpixel and grayscale antialiasing.
+    #[inline]
+    pub fn subpixel(self, subpixel: bool) -> Self {
+        self.rasterization_options(if subpixel {
+            RasterizationOptions::SubpixelAa
+        } else {
+            RasterizationOptions::GrayscaleAa
+        })
+    }
+
+    /// Sets the gamma applied to coverage values after rasterization.
+    ///
+    /// Each coverage value `c` in 0.0 to 1.0 becomes `c^(1 / gamma)`, so values above 1.0 darken
+    /// (embolden) antialiased edges. Fully covered and empty pixels are unaffected.
+    ///
+    /// Panics if `gamma` is not positive.
+    #[inline]
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        assert!(gamma > 0.0, "gamma must be positive");
+        self.settings.gamma = gamma;
+        self
+    }
+
+    /// Turns stem darkening on or off.
+    ///
+    /// Stem darkening emboldens thin stems at small sizes to improve contrast, similar to macOS
+    /// text rendering. It's supported by the FreeType loader for CFF and Type 1 fonts; FreeType
+    /// leaves it off by default. Other loaders and font formats ignore this setting.
+    #[inline]
+    pub fn stem_darkening(mut self, stem_darkening: bool) -> Self {
+        self.settings.stem_darkening = Some(stem_darkening);
+        self
+    }
+
+    /// Sets the strength of stem darkening, where 1.0 is the rasterizer's standard amount.
+    ///
+    /// This has no effect unless stem darkening is turned on.
+    ///
+    /// Panics if `strength` is negative.
+    #[inline]
+    pub fn stem_darkening_strength(mut self, strength: f32) -> Self {
+        assert!(
+            strength >= 0.0,
+            "stem darkening strength must not be negative"
+        );
+        self.settings.stem_darkening_strength = strength;
+        self
+    }
+
+    /// Blends the glyph against an opaque sRGB background color instead of writing coverage.
+    ///
+    /// Every pixel of the canvas becomes the background color mixed with the text color by the
+    /// glyph's coverage. The mix is done in linear light and converted back to sRGB, so
+    /// antialiased edges come out as the expected intermediate shades on any background, rather
+    /// than the too-dark fringes of blending sRGB values directly. The canvas must be `Rgb24` or
+    /// `Rgba32`, whose pixels come out opaque; rasterizing to an `A8` canvas returns
+    /// `GlyphLoadingError::UnsupportedCanvasFormat`.
+    #[inline]
+    pub fn background_color(mut self, background_color: [u8; 3]) -> Self {
+        self.settings.background_color = Some(background_color);
+        self
+    }
+
+    /// Sets the sRGB color of the text for blending against a background. The default is black.
+    ///
+    /// This has no effect unless a background color is set.
+    #[inline]
+    pub fn text_color(mut self, text_color: [u8; 3]) -> Self {
+        self.settings.text_color = text_color;
+        self
+    }
+
+    /// Turns ordered dithering of bilevel output on or off.
+    ///
+    /// Bilevel rendering normally turns each pixel on or off by whether the glyph covers its
+    /// center, which makes diagonal and curved edges of small text blocky. With dithering, the
+    /// glyph is rasterized with antialiasing and its coverage is compared against a repeating
+    /// 4×4 Bayer matrix of thresholds instead, so partly covered pixels are set in proportion to
+    /// their coverage. Pixels still come out entirely on or off.
+    ///
+    /// This has no effect unless the antialiasing strategy is `RasterizationOptions::Bilevel`.
+    #[inline]
+    pub fn dithering(mut self, dithering: bool) -> Self {
+        self.settings.dithering = dithering;
+        self
+    }
+
+    /// Sets the coverage threshold for bilevel output, as a fraction from 0.0 to 1.0.
+    ///
+    /// The glyph is rasterized with antialiasing, and each pixel is set if its coverage is at
+    /// least `threshold`, as in `Canvas::to_packed_1bpp()`. Lower thresholds make glyphs bolder.
+    /// Without a threshold, the rasterizer's own bilevel mode sets the pixels whose centers the
+    /// glyph covers.
+    ///
+    /// This has no effect unless the antialiasing strategy is `RasterizationOptions::Bilevel`,
+    /// and dithering takes precedence over it.
+    ///
+    /// Panics if `threshold` is outside 0.0 to 1.0.
+    #[inline]
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "threshold must be between 0.0 and 1.0"
+        );
+        self.settings.threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the filter applied to subpixel antialiased coverage to reduce color fringes.
+    ///
+    /// It's supported by the FreeType loader, which uses `LcdFilter::Default` otherwise. Other
+    /// loaders ignore this setting. This has no effect unless the antialiasing strategy is
+    /// `RasterizationOptions::SubpixelAa`.
+    #[inline]
+    pub fn lcd_filter(mut self, lcd_filter: LcdFilter) -> Self {
+        self.settings.lcd_filter = Some(lcd_filter);
+        self
+    }
+
+    /// Chooses whether `Rgba32` output has premultiplied alpha. The default is premultiplied.
+    ///
+    /// Glyphs are rasterized into `Rgba32` canvases with premultiplied alpha, as `convert()`
+    /// produces. Otherwise the color channels of the pixels the glyph covers are divided by their
+    /// alpha afterwards, so grayscale glyphs come out white with varying alpha. Other formats, and
+    /// canvases blended against a background color, whose pixels are opaque, are unaffected.
+    #[inline]
+    pub fn premultiplied(mut self, premultiplied: bool) -> Self {
+        self.settings.premultiplied = premultiplied;
+        self
+    }
+
+    /// Returns the composed settings.
+    #[inline]
+    pub fn build(self) -> RasterizationSettings {
+        self.settings
+    }
+}
+
+impl Default for RasterizationOptionsBuilder {
+    #[inline]
+    fn default() -> RasterizationOptionsBuilder {
+        RasterizationOptionsBuilder::new()
+    }
+}
+
 trait Blit {
     fn blit(dest: &mut [u8], src: &[u8]);
 }
@@ -296,6 +1190,28 @@ impl Blit for BlitA8ToRgb24 {
     }
 }
 
+struct BlitA8ToRgba32;
+
+impl Blit for BlitA8ToRgba32 {
+    #[inline]
+    fn blit(dest: &mut [u8], src: &[u8]) {
+        for (dest, src) in dest.chunks_mut(4).zip(src.iter()) {
+            dest.copy_from_slice(&[*src; 4])
+        }
+    }
+}
+
+struct BlitRgba32ToA8;
+
+impl Blit for BlitRgba32ToA8 {
+    #[inline]
+    fn blit(dest: &mut [u8], src: &[u8]) {
+        for (dest, src) in dest.iter_mut().zip(src.chunks(4)) {
+            *dest = src[3]
+        }
+    }
+}
+
 struct BlitRgba32ToRgb24;
 
 impl Blit for BlitRgba32ToRgb24 {
@@ -0,0 +1,642 @@
+// font-kit/src/coverage.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Coarse script, language, and legacy encoding coverage, as advertised in the `OS/2` table.
+//!
+//! These bits are set by the font vendor and are frequently incomplete or wrong. They are useful
+//! for quick badges in a font picker, but the font's character map (`Loader::glyph_for_char()`)
+//! is authoritative.
+
+/// A Unicode block, or group of related blocks, corresponding to one bit of the `OS/2`
+/// `ulUnicodeRange1`..`ulUnicodeRange4` fields.
+///
+/// The discriminant of each variant is its bit number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum UnicodeBlock {
+    /// Basic Latin.
+    BasicLatin = 0,
+    /// Latin-1 Supplement.
+    Latin1Supplement = 1,
+    /// Latin Extended-A.
+    LatinExtendedA = 2,
+    /// Latin Extended-B.
+    LatinExtendedB = 3,
+    /// IPA Extensions, Phonetic Extensions, and Phonetic Extensions Supplement.
+    IpaExtensions = 4,
+    /// Spacing Modifier Letters and Modifier Tone Letters.
+    SpacingModifierLetters = 5,
+    /// Combining Diacritical Marks and Combining Diacritical Marks Supplement.
+    CombiningDiacriticalMarks = 6,
+    /// Greek and Coptic.
+    GreekAndCoptic = 7,
+    /// Coptic.
+    Coptic = 8,
+    /// Cyrillic, Cyrillic Supplement, and Cyrillic Extended-A and -B.
+    Cyrillic = 9,
+    /// Armenian.
+    Armenian = 10,
+    /// Hebrew.
+    Hebrew = 11,
+    /// Vai.
+    Vai = 12,
+    /// Arabic and Arabic Supplement.
+    Arabic = 13,
+    /// NKo.
+    Nko = 14,
+    /// Devanagari.
+    Devanagari = 15,
+    /// Bengali.
+    Bengali = 16,
+    /// Gurmukhi.
+    Gurmukhi = 17,
+    /// Gujarati.
+    Gujarati = 18,
+    /// Oriya.
+    Oriya = 19,
+    /// Tamil.
+    Tamil = 20,
+    /// Telugu.
+    Telugu = 21,
+    /// Kannada.
+    Kannada = 22,
+    /// Malayalam.
+    Malayalam = 23,
+    /// Thai.
+    Thai = 24,
+    /// Lao.
+    Lao = 25,
+    /// Georgian and Georgian Supplement.
+    Georgian = 26,
+    /// Balinese.
+    Balinese = 27,
+    /// Hangul Jamo.
+    HangulJamo = 28,
+    /// Latin Extended Additional and Latin Extended-C and -D.
+    LatinExtendedAdditional = 29,
+    /// Greek Extended.
+    GreekExtended = 30,
+    /// General Punctuation and Supplemental Punctuation.
+    GeneralPunctuation = 31,
+    /// Superscripts and Subscripts.
+    SuperscriptsAndSubscripts = 32,
+    /// Currency Symbols.
+    CurrencySymbols = 33,
+    /// Combining Diacritical Marks for Symbols.
+    CombiningDiacriticalMarksForSymbols = 34,
+    /// Letterlike Symbols.
+    LetterlikeSymbols = 35,
+    /// Number Forms.
+    NumberForms = 36,
+    /// Arrows, Supplemental Arrows-A and -B, and Miscellaneous Symbols and Arrows.
+    Arrows = 37,
+    /// Mathematical Operators, Supplemental Mathematical Operators, and Miscellaneous Mathematical
+    /// Symbols-A and -B.
+    MathematicalOperators = 38,
+    /// Miscellaneous Technical.
+    MiscellaneousTechnical = 39,
+    /// Control Pictures.
+    ControlPictures = 40,
+    /// Optical Character Recognition.
+    OpticalCharacterRecognition = 41,
+    /// Enclosed Alphanumerics.
+    EnclosedAlphanumerics = 42,
+    /// Box Drawing.
+    BoxDrawing = 43,
+    /// Block Elements.
+    BlockElements = 44,
+    /// Geometric Shapes.
+    GeometricShapes = 45,
+    /// Miscellaneous Symbols.
+    MiscellaneousSymbols = 46,
+    /// Dingbats.
+    Dingbats = 47,
+    /// CJK Symbols and Punctuation.
+    CjkSymbolsAndPunctuation = 48,
+    /// Hiragana.
+    Hiragana = 49,
+    /// Katakana and Katakana Phonetic Extensions.
+    Katakana = 50,
+    /// Bopomofo and Bopomofo Extended.
+    Bopomofo = 51,
+    /// Hangul Compatibility Jamo.
+    HangulCompatibilityJamo = 52,
+    /// Phags-pa.
+    PhagsPa = 53,
+    /// Enclosed CJK Letters and Months.
+    EnclosedCjkLettersAndMonths = 54,
+    /// CJK Compatibility.
+    CjkCompatibility = 55,
+    /// Hangul Syllables.
+    HangulSyllables = 56,
+    /// Any character outside the Basic Multilingual Plane.
+    NonPlane0 = 57,
+    /// Phoenician.
+    Phoenician = 58,
+    /// CJK Unified Ideographs and related radicals, extensions, and Kanbun.
+    CjkUnifiedIdeographs = 59,
+    /// The Private Use Area in the Basic Multilingual Plane.
+    PrivateUseAreaPlane0 = 60,
+    /// CJK Strokes, CJK Compatibility Ideographs, and CJK Compatibility Ideographs Supplement.
+    CjkStrokes = 61,
+    /// Alphabetic Presentation Forms.
+    AlphabeticPresentationForms = 62,
+    /// Arabic Presentation Forms-A.
+    ArabicPresentationFormsA = 63,
+    /// Combining Half Marks.
+    CombiningHalfMarks = 64,
+    /// Vertical Forms and CJK Compatibility Forms.
+    VerticalForms = 65,
+    /// Small Form Variants.
+    SmallFormVariants = 66,
+    /// Arabic Presentation Forms-B.
+    ArabicPresentationFormsB = 67,
+    /// Halfwidth and Fullwidth Forms.
+    HalfwidthAndFullwidthForms = 68,
+    /// Specials.
+    Specials = 69,
+    /// Tibetan.
+    Tibetan = 70,
+    /// Syriac.
+    Syriac = 71,
+    /// Thaana.
+    Thaana = 72,
+    /// Sinhala.
+    Sinhala = 73,
+    /// Myanmar.
+    Myanmar = 74,
+    /// Ethiopic, Ethiopic Supplement, and Ethiopic Extended.
+    Ethiopic = 75,
+    /// Cherokee.
+    Cherokee = 76,
+    /// Unified Canadian Aboriginal Syllabics.
+    UnifiedCanadianAboriginalSyllabics = 77,
+    /// Ogham.
+    Ogham = 78,
+    /// Runic.
+    Runic = 79,
+    /// Khmer and Khmer Symbols.
+    Khmer = 80,
+    /// Mongolian.
+    Mongolian = 81,
+    /// Braille Patterns.
+    BraillePatterns = 82,
+    /// Yi Syllables and Yi Radicals.
+    YiSyllables = 83,
+    /// Tagalog, Hanunoo, Buhid, and Tagbanwa.
+    Tagalog = 84,
+    /// Old Italic.
+    OldItalic = 85,
+    /// Gothic.
+    Gothic = 86,
+    /// Deseret.
+    Deseret = 87,
+    /// Byzantine Musical Symbols, Musical Symbols, and Ancient Greek Musical Notation.
+    MusicalSymbols = 88,
+    /// Mathematical Alphanumeric Symbols.
+    MathematicalAlphanumericSymbols = 89,
+    /// The Private Use planes 15 and 16.
+    PrivateUsePlanes15And16 = 90,
+    /// Variation Selectors and Variation Selectors Supplement.
+    VariationSelectors = 91,
+    /// Tags.
+    Tags = 92,
+    /// Limbu.
+    Limbu = 93,
+    /// Tai Le.
+    TaiLe = 94,
+    /// New Tai Lue.
+    NewTaiLue = 95,
+    /// Buginese.
+    Buginese = 96,
+    /// Glagolitic.
+    Glagolitic = 97,
+    /// Tifinagh.
+    Tifinagh = 98,
+    /// Yijing Hexagram Symbols.
+    YijingHexagramSymbols = 99,
+    /// Syloti Nagri.
+    SylotiNagri = 100,
+    /// Linear B Syllabary, Linear B Ideograms, and Aegean Numbers.
+    LinearB = 101,
+    /// Ancient Greek Numbers.
+    AncientGreekNumbers = 102,
+    /// Ugaritic.
+    Ugaritic = 103,
+    /// Old Persian.
+    OldPersian = 104,
+    /// Shavian.
+    Shavian = 105,
+    /// Osmanya.
+    Osmanya = 106,
+    /// Cypriot Syllabary.
+    CypriotSyllabary = 107,
+    /// Kharoshthi.
+    Kharoshthi = 108,
+    /// Tai Xuan Jing Symbols.
+    TaiXuanJingSymbols = 109,
+    /// Cuneiform and Cuneiform Numbers and Punctuation.
+    Cuneiform = 110,
+    /// Counting Rod Numerals.
+    CountingRodNumerals = 111,
+    /// Sundanese.
+    Sundanese = 112,
+    /// Lepcha.
+    Lepcha = 113,
+    /// Ol Chiki.
+    OlChiki = 114,
+    /// Saurashtra.
+    Saurashtra = 115,
+    /// Kayah Li.
+    KayahLi = 116,
+    /// Rejang.
+    Rejang = 117,
+    /// Cham.
+    Cham = 118,
+    /// Ancient Symbols.
+    AncientSymbols = 119,
+    /// Phaistos Disc.
+    PhaistosDisc = 120,
+    /// Carian, Lycian, and Lydian.
+    Carian = 121,
+    /// Domino Tiles and Mahjong Tiles.
+    DominoTiles = 122,
+}
+
+impl UnicodeBlock {
+    /// Every block, in bit order.
+    pub const ALL: [UnicodeBlock; 123] = [
+        UnicodeBlock::BasicLatin,
+        UnicodeBlock::Latin1Supplement,
+        UnicodeBlock::LatinExtendedA,
+        UnicodeBlock::LatinExtendedB,
+        UnicodeBlock::IpaExtensions,
+        UnicodeBlock::SpacingModifierLetters,
+        UnicodeBlock::CombiningDiacriticalMarks,
+        UnicodeBlock::GreekAndCoptic,
+        UnicodeBlock::Coptic,
+        UnicodeBlock::Cyrillic,
+        UnicodeBlock::Armenian,
+        UnicodeBlock::Hebrew,
+        UnicodeBlock::Vai,
+        UnicodeBlock::Arabic,
+        UnicodeBlock::Nko,
+        UnicodeBlock::Devanagari,
+        UnicodeBlock::Bengali,
+        UnicodeBlock::Gurmukhi,
+        UnicodeBlock::Gujarati,
+        UnicodeBlock::Oriya,
+        UnicodeBlock::Tamil,
+        UnicodeBlock::Telugu,
+        UnicodeBlock::Kannada,
+        UnicodeBlock::Malayalam,
+        UnicodeBlock::Thai,
+        UnicodeBlock::Lao,
+        UnicodeBlock::Georgian,
+        UnicodeBlock::Balinese,
+        UnicodeBlock::HangulJamo,
+        UnicodeBlock::LatinExtendedAdditional,
+        UnicodeBlock::GreekExtended,
+        UnicodeBlock::GeneralPunctuation,
+        UnicodeBlock::SuperscriptsAndSubscripts,
+        UnicodeBlock::CurrencySymbols,
+        UnicodeBlock::CombiningDiacriticalMarksForSymbols,
+        UnicodeBlock::LetterlikeSymbols,
+        UnicodeBlock::NumberForms,
+        UnicodeBlock::Arrows,
+        UnicodeBlock::MathematicalOperators,
+        UnicodeBlock::MiscellaneousTechnical,
+        UnicodeBlock::ControlPictures,
+        UnicodeBlock::OpticalCharacterRecognition,
+        UnicodeBlock::EnclosedAlphanumerics,
+        UnicodeBlock::BoxDrawing,
+        UnicodeBlock::BlockElements,
+        UnicodeBlock::GeometricShapes,
+        UnicodeBlock::MiscellaneousSymbols,
+        UnicodeBlock::Dingbats,
+        UnicodeBlock::CjkSymbolsAndPunctuation,
+        UnicodeBlock::Hiragana,
+        UnicodeBlock::Katakana,
+        UnicodeBlock::Bopomofo,
+        UnicodeBlock::HangulCompatibilityJamo,
+        UnicodeBlock::PhagsPa,
+        UnicodeBlock::EnclosedCjkLettersAndMonths,
+        UnicodeBlock::CjkCompatibility,
+        UnicodeBlock::HangulSyllables,
+        UnicodeBlock::NonPlane0,
+        UnicodeBlock::Phoenician,
+        UnicodeBlock::CjkUnifiedIdeographs,
+        UnicodeBlock::PrivateUseAreaPlane0,
+        UnicodeBlock::CjkStrokes,
+        UnicodeBlock::AlphabeticPresentationForms,
+        UnicodeBlock::ArabicPresentationFormsA,
+        UnicodeBlock::CombiningHalfMarks,
+        UnicodeBlock::VerticalForms,
+        UnicodeBlock::SmallFormVariants,
+        UnicodeBlock::ArabicPresentationFormsB,
+        UnicodeBlock::HalfwidthAndFullwidthForms,
+        UnicodeBlock::Specials,
+        UnicodeBlock::Tibetan,
+        UnicodeBlock::Syriac,
+        UnicodeBlock::Thaana,
+        UnicodeBlock::Sinhala,
+        UnicodeBlock::Myanmar,
+        UnicodeBlock::Ethiopic,
+        UnicodeBlock::Cherokee,
+        UnicodeBlock::UnifiedCanadianAboriginalSyllabics,
+        UnicodeBlock::Ogham,
+        UnicodeBlock::Runic,
+        UnicodeBlock::Khmer,
+        UnicodeBlock::Mongolian,
+        UnicodeBlock::BraillePatterns,
+        UnicodeBlock::YiSyllables,
+        UnicodeBlock::Tagalog,
+        UnicodeBlock::OldItalic,
+        UnicodeBlock::Gothic,
+        UnicodeBlock::Deseret,
+        UnicodeBlock::MusicalSymbols,
+        UnicodeBlock::MathematicalAlphanumericSymbols,
+        UnicodeBlock::PrivateUsePlanes15And16,
+        UnicodeBlock::VariationSelectors,
+        UnicodeBlock::Tags,
+        UnicodeBlock::Limbu,
+        UnicodeBlock::TaiLe,
+        UnicodeBlock::NewTaiLue,
+        UnicodeBlock::Buginese,
+        UnicodeBlock::Glagolitic,
+        UnicodeBlock::Tifinagh,
+        UnicodeBlock::YijingHexagramSymbols,
+        UnicodeBlock::SylotiNagri,
+        UnicodeBlock::LinearB,
+        UnicodeBlock::AncientGreekNumbers,
+        UnicodeBlock::Ugaritic,
+        UnicodeBlock::OldPersian,
+        UnicodeBlock::Shavian,
+        UnicodeBlock::Osmanya,
+        UnicodeBlock::CypriotSyllabary,
+        UnicodeBlock::Kharoshthi,
+        UnicodeBlock::TaiXuanJingSymbols,
+        UnicodeBlock::Cuneiform,
+        UnicodeBlock::CountingRodNumerals,
+        UnicodeBlock::Sundanese,
+        UnicodeBlock::Lepcha,
+        UnicodeBlock::OlChiki,
+        UnicodeBlock::Saurashtra,
+        UnicodeBlock::KayahLi,
+        UnicodeBlock::Rejang,
+        UnicodeBlock::Cham,
+        UnicodeBlock::AncientSymbols,
+        UnicodeBlock::PhaistosDisc,
+        UnicodeBlock::Carian,
+        UnicodeBlock::DominoTiles,
+    ];
+
+    /// Returns the block for the given `OS/2` Unicode range bit, or `None` if the bit is
+    /// reserved.
+    #[inline]
+    pub fn from_bit(bit: u32) -> Option<UnicodeBlock> {
+        UnicodeBlock::ALL.get(bit as usize).cloned()
+    }
+
+    /// Returns the `OS/2` Unicode range bit for this block.
+    #[inline]
+    pub fn bit(self) -> u32 {
+        self as u32
+    }
+}
+
+/// The set of Unicode blocks that a font claims to support, decoded from the `OS/2`
+/// `ulUnicodeRange1`..`ulUnicodeRange4` fields.
+///
+/// Note that fonts often set these bits inaccurately; see the module documentation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UnicodeRanges {
+    bits: [u32; 4],
+}
+
+impl UnicodeRanges {
+    /// Creates a set of Unicode ranges from the raw `ulUnicodeRange1`..`ulUnicodeRange4` values.
+    #[inline]
+    pub fn from_bits(bits: [u32; 4]) -> UnicodeRanges {
+        UnicodeRanges { bits }
+    }
+
+    /// Returns the raw `ulUnicodeRange1`..`ulUnicodeRange4` values.
+    #[inline]
+    pub fn bits(&self) -> [u32; 4] {
+        self.bits
+    }
+
+    /// Returns true if the font claims to support the given block.
+    #[inline]
+    pub fn supports_block(&self, block: UnicodeBlock) -> bool {
+        let bit = block.bit();
+        self.bits[(bit / 32) as usize] & (1 << (bit % 32)) != 0
+    }
+
+    /// Returns every block the font claims to support, in bit order.
+    pub fn blocks(&self) -> Vec<UnicodeBlock> {
+        UnicodeBlock::ALL
+            .iter()
+            .cloned()
+            .filter(|&block| self.supports_block(block))
+            .collect()
+    }
+
+    /// Returns true if no bits are set, which is also the case for fonts without an `OS/2` table.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits == [0; 4]
+    }
+}
+
+/// A legacy code page, corresponding to one bit of the `OS/2` `ulCodePageRange1` and
+/// `ulCodePageRange2` fields.
+///
+/// The discriminant of each variant is its bit number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum CodePage {
+    /// Latin 1 (Windows code page 1252).
+    Latin1 = 0,
+    /// Latin 2: Eastern Europe (Windows code page 1250).
+    Latin2 = 1,
+    /// Cyrillic (Windows code page 1251).
+    Cyrillic = 2,
+    /// Greek (Windows code page 1253).
+    Greek = 3,
+    /// Turkish (Windows code page 1254).
+    Turkish = 4,
+    /// Hebrew (Windows code page 1255).
+    Hebrew = 5,
+    /// Arabic (Windows code page 1256).
+    Arabic = 6,
+    /// Windows Baltic (Windows code page 1257).
+    Baltic = 7,
+    /// Vietnamese (Windows code page 1258).
+    Vietnamese = 8,
+    /// Thai (Windows code page 874).
+    Thai = 16,
+    /// Japanese Shift-JIS (Windows code page 932).
+    ShiftJis = 17,
+    /// Simplified Chinese GBK (Windows code page 936).
+    Gbk = 18,
+    /// Korean Wansung (Windows code page 949).
+    KoreanWansung = 19,
+    /// Traditional Chinese Big5 (Windows code page 950).
+    Big5 = 20,
+    /// Korean Johab (Windows code page 1361).
+    KoreanJohab = 21,
+    /// The Macintosh character set (US Roman).
+    Macintosh = 29,
+    /// The OEM character set.
+    Oem = 30,
+    /// The symbol character set.
+    Symbol = 31,
+    /// IBM Greek (code page 869).
+    IbmGreek = 48,
+    /// MS-DOS Russian (code page 866).
+    DosRussian = 49,
+    /// MS-DOS Nordic (code page 865).
+    DosNordic = 50,
+    /// Arabic (code page 864).
+    DosArabic = 51,
+    /// MS-DOS Canadian French (code page 863).
+    DosCanadianFrench = 52,
+    /// Hebrew (code page 862).
+    DosHebrew = 53,
+    /// MS-DOS Icelandic (code page 861).
+    DosIcelandic = 54,
+    /// MS-DOS Portuguese (code page 860).
+    DosPortuguese = 55,
+    /// IBM Turkish (code page 857).
+    IbmTurkish = 56,
+    /// IBM Cyrillic, primarily Russian (code page 855).
+    IbmCyrillic = 57,
+    /// Latin 2 (code page 852).
+    DosLatin2 = 58,
+    /// MS-DOS Baltic (code page 775).
+    DosBaltic = 59,
+    /// Greek, formerly 437 G (code page 737).
+    DosGreek = 60,
+    /// Arabic ASMO 708 (code page 708).
+    Asmo708 = 61,
+    /// Western European Latin 1 (code page 850).
+    DosLatin1 = 62,
+    /// US (code page 437).
+    DosUs = 63,
+}
+
+impl CodePage {
+    /// Every code page, in bit order.
+    pub const ALL: [CodePage; 34] = [
+        CodePage::Latin1,
+        CodePage::Latin2,
+        CodePage::Cyrillic,
+        CodePage::Greek,
+        CodePage::Turkish,
+        CodePage::Hebrew,
+        CodePage::Arabic,
+        CodePage::Baltic,
+        CodePage::Vietnamese,
+        CodePage::Thai,
+        CodePage::ShiftJis,
+        CodePage::Gbk,
+        CodePage::KoreanWansung,
+        CodePage::Big5,
+        CodePage::KoreanJohab,
+        CodePage::Macintosh,
+        CodePage::Oem,
+        CodePage::Symbol,
+        CodePage::IbmGreek,
+        CodePage::DosRussian,
+        CodePage::DosNordic,
+        CodePage::DosArabic,
+        CodePage::DosCanadianFrench,
+        CodePage::DosHebrew,
+        CodePage::DosIcelandic,
+        CodePage::DosPortuguese,
+        CodePage::IbmTurkish,
+        CodePage::IbmCyrillic,
+        CodePage::DosLatin2,
+        CodePage::DosBaltic,
+        CodePage::DosGreek,
+        CodePage::Asmo708,
+        CodePage::DosLatin1,
+        CodePage::DosUs,
+    ];
+
+    /// Returns the code page for the given `OS/2` code page range bit, or `None` if the bit is
+    /// reserved.
+    #[inline]
+    pub fn from_bit(bit: u32) -> Option<CodePage> {
+        CodePage::ALL
+            .iter()
+            .cloned()
+            .find(|code_page| code_page.bit() == bit)
+    }
+
+    /// Returns the `OS/2` code page range bit for this code page.
+    #[inline]
+    pub fn bit(self) -> u32 {
+        self as u32
+    }
+
+    /// Returns the numeric code page identifier, or `None` for the Macintosh, OEM, and symbol
+    /// character sets, which have none.
+    pub fn number(self) -> Option<u16> {
+        match self {
+            CodePage::Latin1 => Some(1252),
+            CodePage::Latin2 => Some(1250),
+            CodePage::Cyrillic => Some(1251),
+            CodePage::Greek => Some(1253),
+            CodePage::Turkish => Some(1254),
+            CodePage::Hebrew => Some(1255),
+            CodePage::Arabic => Some(1256),
+            CodePage::Baltic => Some(1257),
+            CodePage::Vietnamese => Some(1258),
+            CodePage::Thai => Some(874),
+            CodePage::ShiftJis => Some(932),
+            CodePage::Gbk => Some(936),
+            CodePage::KoreanWansung => Some(949),
+            CodePage::Big5 => Some(950),
+            CodePage::KoreanJohab => Some(1361),
+            CodePage::IbmGreek => Some(869),
+            CodePage::DosRussian => Some(866),
+            CodePage::DosNordic => Some(865),
+            CodePage::DosArabic => Some(864),
+            CodePage::DosCanadianFrench => Some(863),
+            CodePage::DosHebrew => Some(862),
+            CodePage::DosIcelandic => Some(861),
+            CodePage::DosPortuguese => Some(860),
+            CodePage::IbmTurkish => Some(857),
+            CodePage::IbmCyrillic => Some(855),
+            CodePage::DosLatin2 => Some(852),
+            CodePage::DosBaltic => Some(775),
+            CodePage::DosGreek => Some(737),
+            CodePage::Asmo708 => Some(708),
+            CodePage::DosLatin1 => Some(850),
+            CodePage::DosUs => Some(437),
+            CodePage::Macintosh | CodePage::Oem | CodePage::Symbol => None,
+        }
+    }
+
+    /// Decodes the raw `ulCodePageRange1` and `ulCodePageRange2` values, in bit order.
+    pub fn from_bits(bits: [u32; 2]) -> Vec<CodePage> {
+        CodePage::ALL
+            .iter()
+            .cloned()
+            .filter(|code_page| {
+                let bit = code_page.bit();
+                bits[(bit / 32) as usize] & (1 << (bit % 32)) != 0
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,215 @@
+// font-kit/src/embedded_bitmaps.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Embedded glyph images from the `sbix` table and the `CBLC`/`CBDT` tables.
+//!
+//! Color emoji fonts store each glyph as an image per strike (pixel size). The images are passed
+//! through as stored; nothing is decoded or re-encoded. Only PNG data is supported in `CBDT`,
+//! while `sbix` images are returned in whatever format the font tags them with.
+
+use pathfinder_geometry::vector::Vector2I;
+use std::convert::TryFrom;
+
+use crate::loader::{GlyphRasterImage, ImageFormat};
+use crate::tables::{read_i16, read_u16, read_u32};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+const SBIX_GRAPHIC_TYPE_DUPE: u32 = 0x64757065;
+const SBIX_GRAPHIC_TYPE_JPG: u32 = 0x6a706720;
+const SBIX_GRAPHIC_TYPE_PNG: u32 = 0x706e6720;
+const SBIX_GRAPHIC_TYPE_TIFF: u32 = 0x74696666;
+
+// The PNG image formats of `CBDT`: with small metrics, with big metrics, and with the metrics
+// in the `CBLC` index subtable.
+const CBDT_FORMAT_PNG_SMALL_METRICS: u16 = 17;
+const CBDT_FORMAT_PNG_BIG_METRICS: u16 = 18;
+const CBDT_FORMAT_PNG_INDEX_METRICS: u16 = 19;
+
+const CBLC_BITMAP_SIZE_LENGTH: usize = 48;
+
+/// Returns the image for a glyph from the `sbix` strike that best fits `pixels_per_em`.
+pub(crate) fn sbix_image(
+    sbix: &[u8],
+    glyph_count: u32,
+    glyph_id: u32,
+    pixels_per_em: f32,
+) -> Option<GlyphRasterImage> {
+    let strike_count = read_u32(sbix, 4)? as usize;
+    let strikes = (0..strike_count)
+        .filter_map(|index| {
+            let strike = sbix.get(read_u32(sbix, 8 + index * 4)? as usize..)?;
+            Some((read_u16(strike, 0)?, strike))
+        })
+        .collect::<Vec<_>>();
+    let strike = best_strike(&strikes, pixels_per_em)?;
+    let strike_pixels_per_em = read_u16(strike, 0)?;
+
+    // Follow one `dupe` reference at most, which is all that the format allows.
+    let mut glyph_id = glyph_id;
+    for _ in 0..2 {
+        if glyph_id >= glyph_count {
+            return None;
+        }
+        let start = read_u32(strike, 4 + glyph_id as usize * 4)? as usize;
+        let end = read_u32(strike, 8 + glyph_id as usize * 4)? as usize;
+        let glyph = strike.get(start..end)?;
+        let data = glyph.get(8..)?;
+        let format = match read_u32(glyph, 4)? {
+            SBIX_GRAPHIC_TYPE_DUPE => {
+                glyph_id = u32::from(read_u16(data, 0)?);
+                continue;
+            }
+            SBIX_GRAPHIC_TYPE_PNG => ImageFormat::Png,
+            SBIX_GRAPHIC_TYPE_JPG => ImageFormat::Jpeg,
+            SBIX_GRAPHIC_TYPE_TIFF => ImageFormat::Tiff,
+            _ => return None,
+        };
+        let size = match format {
+            ImageFormat::Png => png_size(data).unwrap_or_default(),
+            _ => Vector2I::default(),
+        };
+        return Some(GlyphRasterImage {
+            format,
+            data: data.to_vec(),
+            size,
+            origin: Vector2I::new(read_i16(glyph, 0)? as i32, read_i16(glyph, 2)? as i32),
+            pixels_per_em: strike_pixels_per_em,
+        });
+    }
+    None
+}
+
+/// Returns the PNG image for a glyph from the `CBLC`/`CBDT` strike that best fits
+/// `pixels_per_em`.
+pub(crate) fn cbdt_image(
+    cblc: &[u8],
+    cbdt: &[u8],
+    glyph_id: u32,
+    pixels_per_em: f32,
+) -> Option<GlyphRasterImage> {
+    let glyph_id = u16::try_from(glyph_id).ok()?;
+    let size_count = read_u32(cblc, 4)? as usize;
+    let sizes = (0..size_count)
+        .filter_map(|index| {
+            let size = cblc.get(8 + index * CBLC_BITMAP_SIZE_LENGTH..)?;
+            let (start_glyph, end_glyph) = (read_u16(size, 40)?, read_u16(size, 42)?);
+            if glyph_id < start_glyph || glyph_id > end_glyph {
+                return None;
+            }
+            Some((u16::from(*size.get(45)?), size))
+        })
+        .collect::<Vec<_>>();
+    let size = best_strike(&sizes, pixels_per_em)?;
+    let strike_pixels_per_em = u16::from(*size.get(45)?);
+
+    // Find the index subtable that covers the glyph.
+    let array = cblc.get(read_u32(size, 0)? as usize..)?;
+    let subtable = (0..(read_u32(size, 8)? as usize)).find_map(|index| {
+        let entry = index * 8;
+        let (first_glyph, last_glyph) = (read_u16(array, entry)?, read_u16(array, entry + 2)?);
+        if glyph_id < first_glyph || glyph_id > last_glyph {
+            return None;
+        }
+        let subtable = array.get(read_u32(array, entry + 4)? as usize..)?;
+        Some((first_glyph, subtable))
+    });
+    let (first_glyph, subtable) = subtable?;
+    let (index_format, image_format) = (read_u16(subtable, 0)?, read_u16(subtable, 2)?);
+    let image_data_offset = read_u32(subtable, 4)? as usize;
+    let glyph_index = (glyph_id - first_glyph) as usize;
+
+    // Locate the glyph's image data, and the big metrics that some index formats share among all
+    // of their glyphs.
+    let (start, end, index_metrics) = match index_format {
+        1 => (
+            read_u32(subtable, 8 + glyph_index * 4)? as usize,
+            read_u32(subtable, 12 + glyph_index * 4)? as usize,
+            None,
+        ),
+        2 => {
+            let image_size = read_u32(subtable, 8)? as usize;
+            let start = image_size * glyph_index;
+            (start, start + image_size, subtable.get(12..20))
+        }
+        3 => (
+            read_u16(subtable, 8 + glyph_index * 2)? as usize,
+            read_u16(subtable, 10 + glyph_index * 2)? as usize,
+            None,
+        ),
+        4 => {
+            let glyph_count = read_u32(subtable, 8)? as usize;
+            let pair = (0..glyph_count)
+                .map(|index| 12 + index * 4)
+                .find(|&pair| read_u16(subtable, pair) == Some(glyph_id))?;
+            (
+                read_u16(subtable, pair + 2)? as usize,
+                read_u16(subtable, pair + 6)? as usize,
+                None,
+            )
+        }
+        5 => {
+            let image_size = read_u32(subtable, 8)? as usize;
+            let glyph_count = read_u32(subtable, 20)? as usize;
+            let index = (0..glyph_count)
+                .find(|&index| read_u16(subtable, 24 + index * 2) == Some(glyph_id))?;
+            let start = image_size * index;
+            (start, start + image_size, subtable.get(12..20))
+        }
+        _ => return None,
+    };
+    let glyph = cbdt.get((image_data_offset + start)..(image_data_offset + end))?;
+
+    // Small and big metrics both start with the height, width, and horizontal bearings, in
+    // pixels; the bearings place the top left corner of the image relative to the glyph origin.
+    let (metrics, data) = match image_format {
+        CBDT_FORMAT_PNG_SMALL_METRICS => (glyph.get(0..5)?, glyph.get(5..)?),
+        CBDT_FORMAT_PNG_BIG_METRICS => (glyph.get(0..8)?, glyph.get(8..)?),
+        CBDT_FORMAT_PNG_INDEX_METRICS => (index_metrics?, glyph),
+        _ => return None,
+    };
+    let data_length = read_u32(data, 0)? as usize;
+    let data = data.get(4..(4 + data_length))?;
+    let (height, width) = (metrics[0] as i32, metrics[1] as i32);
+    let (bearing_x, bearing_y) = (metrics[2] as i8 as i32, metrics[3] as i8 as i32);
+    Some(GlyphRasterImage {
+        format: ImageFormat::Png,
+        data: data.to_vec(),
+        size: Vector2I::new(width, height),
+        origin: Vector2I::new(bearing_x, bearing_y - height),
+        pixels_per_em: strike_pixels_per_em,
+    })
+}
+
+/// Chooses the smallest strike at least as large as `pixels_per_em`, or the largest strike if
+/// none is.
+fn best_strike<'a>(strikes: &[(u16, &'a [u8])], pixels_per_em: f32) -> Option<&'a [u8]> {
+    let larger = strikes
+        .iter()
+        .filter(|(strike_pixels_per_em, _)| *strike_pixels_per_em as f32 >= pixels_per_em)
+        .min_by_key(|(strike_pixels_per_em, _)| *strike_pixels_per_em);
+    let largest = || {
+        strikes
+            .iter()
+            .max_by_key(|(strike_pixels_per_em, _)| *strike_pixels_per_em)
+    };
+    larger.or_else(largest).map(|&(_, strike)| strike)
+}
+
+/// Reads the width and height from the `IHDR` chunk of a PNG image.
+fn png_size(png: &[u8]) -> Option<Vector2I> {
+    if !png.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    Some(Vector2I::new(
+        read_u32(png, 16)? as i32,
+        read_u32(png, 20)? as i32,
+    ))
+}
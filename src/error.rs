@@ -10,6 +10,7 @@
 
 //! Various types of errors that `font-kit` can return.
 
+use pathfinder_geometry::rect::RectI;
 use std::borrow::Cow;
 use std::convert::From;
 use std::error::Error;
@@ -71,12 +72,24 @@ impl From<io::Error> for FontLoadingError {
 }
 
 /// Reasons why a font might fail to load a glyph.
+///
+/// More reasons may be added in the future, so matches on this type need a wildcard arm.
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum GlyphLoadingError {
     /// The font didn't contain a glyph with that ID.
     NoSuchGlyph,
     /// A platform function returned an error.
     PlatformError,
+    /// The loader doesn't support this operation.
+    NotImplemented,
+    /// The canvas is too small to hold the rasterized glyph.
+    CanvasTooSmall {
+        /// The pixel bounds the glyph needs, relative to the canvas origin.
+        required: RectI,
+    },
+    /// The canvas format can't be used with the requested options.
+    UnsupportedCanvasFormat,
 }
 
 impl Error for GlyphLoadingError {}
@@ -84,6 +97,9 @@ impl Error for GlyphLoadingError {}
 impl_display! { GlyphLoadingError, {
         NoSuchGlyph => "no such glyph",
         PlatformError => "platform error",
+        NotImplemented => "not implemented",
+        CanvasTooSmall { required } => format!("canvas too small for glyph bounds {:?}", required),
+        UnsupportedCanvasFormat => "unsupported canvas format",
     }
 }
 
@@ -10,12 +10,22 @@
 
 //! Encapsulates the information needed to locate and open the fonts in a family.
 
+#[cfg(feature = "source")]
+use float_ord::FloatOrd;
+#[cfg(feature = "source")]
+use std::cmp::Ordering;
+
+#[cfg(feature = "source")]
+use crate::font::Font;
 use crate::handle::Handle;
+#[cfg(feature = "source")]
+use crate::properties::{Properties, Style};
 
 /// Encapsulates the information needed to locate and open the fonts in a family.
 #[derive(Debug)]
 pub struct FamilyHandle {
     pub(crate) fonts: Vec<Handle>,
+    pub(crate) name: Option<String>,
 }
 
 impl Default for FamilyHandle {
@@ -28,7 +38,10 @@ impl FamilyHandle {
     /// Creates an empty set of family handles.
     #[inline]
     pub fn new() -> FamilyHandle {
-        FamilyHandle { fonts: vec![] }
+        FamilyHandle {
+            fonts: vec![],
+            name: None,
+        }
     }
 
     /// Creates a set of font family handles.
@@ -39,6 +52,7 @@ impl FamilyHandle {
     {
         FamilyHandle {
             fonts: fonts.collect::<Vec<Handle>>(),
+            name: None,
         }
     }
 
@@ -48,6 +62,21 @@ impl FamilyHandle {
         self.fonts.push(font)
     }
 
+    /// Returns the name of the family, if the source that created this set recorded it.
+    ///
+    /// The built-in sources record the name they know the family by when they look it up, so this
+    /// doesn't load any fonts.
+    #[inline]
+    pub fn family_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Records the name of the family, as returned by `family_name()`.
+    #[inline]
+    pub fn set_family_name(&mut self, family_name: String) {
+        self.name = Some(family_name)
+    }
+
     /// Returns true if and only if this set has no fonts in it.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -55,8 +84,55 @@ impl FamilyHandle {
     }
 
     /// Returns all the handles in this set.
+    ///
+    /// Sources return the handles sorted by weight, then style, then stretch, so the lightest,
+    /// upright, narrowest face comes first.
     #[inline]
     pub fn fonts(&self) -> &[Handle] {
         &self.fonts
     }
+
+    /// Sorts the handles in the order described in `fonts()`.
+    ///
+    /// `properties` holds the properties the source already knows for each handle, in the same
+    /// order, so the fonts don't have to be opened. Fonts whose properties are unknown are loaded
+    /// to read them; those that fail to load go last.
+    #[cfg(feature = "source")]
+    pub(crate) fn sort_by_properties(&mut self, properties: Vec<Option<Properties>>) {
+        debug_assert_eq!(properties.len(), self.fonts.len());
+        let mut fonts: Vec<(Option<Properties>, Handle)> = properties
+            .into_iter()
+            .zip(self.fonts.drain(..))
+            .map(|(properties, handle)| {
+                let properties = properties.or_else(|| {
+                    Font::from_handle(&handle)
+                        .ok()
+                        .map(|font| font.properties())
+                });
+                (properties, handle)
+            })
+            .collect();
+        fonts.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => compare_properties(a, b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+        self.fonts = fonts.into_iter().map(|(_, handle)| handle).collect();
+    }
+}
+
+/// Orders the faces of a family by weight, then style (normal, oblique, italic), then stretch.
+#[cfg(feature = "source")]
+pub(crate) fn compare_properties(a: &Properties, b: &Properties) -> Ordering {
+    fn style_rank(style: Style) -> u8 {
+        match style {
+            Style::Normal => 0,
+            Style::Oblique(_) => 1,
+            Style::Italic => 2,
+        }
+    }
+
+    FloatOrd(a.weight.0)
+        .cmp(&FloatOrd(b.weight.0))
+        .then(style_rank(a.style).cmp(&style_rank(b.style)))
+        .then(FloatOrd(a.stretch.0).cmp(&FloatOrd(b.stretch.0)))
 }
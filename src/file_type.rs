@@ -8,13 +8,89 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! The type of a font file: either a single font or a TrueType/OpenType collection.
+//! The type of a font file: either a single font, a TrueType/OpenType collection, or a
+//! PostScript Type 1 font.
 
-/// The type of a font file: either a single font or a TrueType/OpenType collection.
+/// The type of a font file: either a single font, a TrueType/OpenType collection, or a PostScript
+/// Type 1 font.
+///
+/// To tell TrueType outlines from CFF outlines, load the font and call
+/// `Loader::outline_format()`.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FileType {
     /// The font file represents a single font (`.ttf`, `.otf`, `.woff`, etc.)
     Single,
     /// The font file represents a collection of fonts (`.ttc`, `.otc`, etc.)
     Collection(u32),
+    /// The font file represents a single PostScript Type 1 font, either binary (`.pfb`) or ASCII
+    /// (`.pfa`).
+    ///
+    /// Only the FreeType loader recognizes these.
+    Type1,
+}
+
+/// Returns true if the data starts like a Type 1 font: a PFB segment header followed by the
+/// PostScript font header, or the PostScript font header itself for PFA.
+#[cfg(any(
+    all(
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "windows",
+            target_arch = "wasm32"
+        )),
+        not(feature = "pure-rust")
+    ),
+    feature = "loader-freetype"
+))]
+pub(crate) fn is_type_1(font_data: &[u8]) -> bool {
+    let has_header =
+        |data: &[u8]| data.starts_with(b"%!PS-AdobeFont") || data.starts_with(b"%!FontType1");
+    match font_data {
+        [0x80, 0x01, _, _, _, _, rest @ ..] => has_header(rest),
+        _ => has_header(font_data),
+    }
+}
+
+/// Returns true if the data starts with the signature of a font format that some loader might
+/// read.
+///
+/// This is used to tell data that isn't a font at all apart from a damaged font when a loader
+/// fails, so it errs on the side of recognizing too much.
+#[cfg(any(
+    all(
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "windows",
+            target_arch = "wasm32"
+        )),
+        not(feature = "pure-rust")
+    ),
+    feature = "loader-freetype"
+))]
+pub(crate) fn has_font_signature(font_data: &[u8]) -> bool {
+    const SIGNATURES: [&[u8]; 13] = [
+        b"\0\x01\0\0", // TrueType
+        b"OTTO",       // OpenType with CFF outlines
+        b"true",       // Apple TrueType
+        b"typ1",       // Apple Type 1 in an sfnt wrapper
+        b"ttcf",       // TrueType/OpenType collections
+        b"wOFF",       // WOFF
+        b"wOF2",       // WOFF 2
+        b"\0\0\x01\0", // Mac resource fork (`.dfont`)
+        b"\x01fcp",    // PCF
+        b"STARTFONT",  // BDF
+        b"MZ",         // Windows `.fon`
+        b"PFR0",       // Portable Font Resource
+        b"%!",         // PostScript: Type 42, CID-keyed fonts, etc.
+    ];
+    if SIGNATURES
+        .iter()
+        .any(|signature| font_data.starts_with(signature))
+    {
+        return true;
+    }
+    // A bare CFF font: major version 1 and a four-byte header.
+    is_type_1(font_data) || matches!(font_data, [1, _, 4, _, ..])
 }
@@ -14,17 +14,32 @@
 //!
 //! To open the font referenced by a handle, use a loader.
 
-use std::path::PathBuf;
+#[cfg(feature = "source")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "source")]
+use std::collections::HashMap;
+#[cfg(feature = "source")]
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "source")]
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::error::FontLoadingError;
 use crate::font::Font;
+use crate::loader::{self, Loader};
+use crate::properties::Properties;
 
 /// Encapsulates the information needed to locate and open a font.
 ///
 /// This is either the path to the font or the raw in-memory font data.
 ///
 /// To open the font referenced by a handle, use a loader.
+///
+/// Handles compare equal if they have the same path and font index, or the same data and font
+/// index. Paths are compared as given, without touching the file system; a path handle never
+/// equals a memory handle, even if they refer to the same font.
 #[derive(Debug, Clone)]
 pub enum Handle {
     /// A font on disk referenced by a path.
@@ -71,4 +86,456 @@ impl Handle {
     pub fn load(&self) -> Result<Font, FontLoadingError> {
         Font::from_handle(self)
     }
+
+    /// Loads this handle with a specific loader, such as the FreeType loader on a platform whose
+    /// default loader is something else.
+    #[inline]
+    pub fn load_with<L>(&self) -> Result<L, FontLoadingError>
+    where
+        L: Loader,
+    {
+        L::from_handle(self)
+    }
+
+    /// Expands a handle to a variable font into one handle per named instance of the font, such
+    /// as "Light" or "Bold", so that each can be offered as a face of its own.
+    ///
+    /// Fonts that aren't variable have no named instances, so this returns an empty list for
+    /// them.
+    pub fn named_instances(&self) -> Result<Vec<InstanceHandle>, FontLoadingError> {
+        let font = self.load()?;
+        let default_properties = font.properties();
+        Ok(font
+            .named_instances()
+            .into_iter()
+            .map(|instance| {
+                let mut properties = default_properties;
+                loader::apply_variation_coordinates(&mut properties, &instance.coordinates);
+                InstanceHandle {
+                    handle: self.clone(),
+                    name: instance.name,
+                    variations: instance.coordinates,
+                    properties,
+                }
+            })
+            .collect())
+    }
+
+    /// Returns a compact textual form of this handle, for configuration files and logs.
+    ///
+    /// A path handle becomes a `file:` URI with the font index as its fragment, such as
+    /// `file:///usr/share/fonts/Font.ttc#2`; the fragment is left out for index 0. Characters that
+    /// can't appear in a URI path are percent-encoded, and relative paths are written without the
+    /// `//`. A memory handle becomes a `data:` URI holding the font data in base64, so it is as
+    /// long as the font but needs nothing else to load. `from_uri()` turns either back into an
+    /// equal handle.
+    pub fn to_uri(&self) -> String {
+        let (mut uri, font_index) = match *self {
+            Handle::Path {
+                ref path,
+                font_index,
+            } => {
+                let path = path_to_uri_bytes(path);
+                let mut uri = String::from(if path.starts_with(b"/") {
+                    "file://"
+                } else {
+                    "file:"
+                });
+                for &byte in &path {
+                    if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+                        uri.push(byte as char);
+                    } else {
+                        uri.push_str(&format!("%{:02X}", byte));
+                    }
+                }
+                (uri, font_index)
+            }
+            Handle::Memory {
+                ref bytes,
+                font_index,
+            } => {
+                let mut uri = String::from("data:application/octet-stream;base64,");
+                push_base64(&mut uri, bytes);
+                (uri, font_index)
+            }
+        };
+        if font_index != 0 {
+            uri.push_str(&format!("#{}", font_index));
+        }
+        uri
+    }
+
+    /// Parses a handle from the textual form produced by `to_uri()`.
+    ///
+    /// `file:` URIs may also name `localhost` as their host. Returns `None` if `uri` isn't a
+    /// `file:` URI or a base64 `data:` URI, or if its fragment isn't a font index.
+    pub fn from_uri(uri: &str) -> Option<Handle> {
+        let (uri, font_index) = match uri.split_once('#') {
+            Some((uri, fragment)) => (uri, fragment.parse().ok()?),
+            None => (uri, 0),
+        };
+        if let Some(path) = uri.strip_prefix("file:") {
+            let path = match path.strip_prefix("//") {
+                Some(path) => path.strip_prefix("localhost").unwrap_or(path),
+                None => path,
+            };
+            if uri.starts_with("file://") && !path.starts_with('/') {
+                return None;
+            }
+            let path = uri_bytes_to_path(percent_decode(path)?)?;
+            return Some(Handle::from_path(path, font_index));
+        }
+        let (metadata, data) = uri.strip_prefix("data:")?.split_once(',')?;
+        if !metadata.ends_with(";base64") {
+            return None;
+        }
+        Some(Handle::from_memory(
+            Arc::new(decode_base64(data)?),
+            font_index,
+        ))
+    }
+
+    /// Returns true if both handles refer to the same font: the same file after canonicalization,
+    /// or identical data in memory, and the same font index.
+    #[cfg(feature = "source")]
+    pub(crate) fn refers_to_same_font(&self, other: &Handle) -> bool {
+        match (self, other) {
+            (
+                Handle::Path {
+                    path: ref path_a,
+                    font_index: index_a,
+                },
+                Handle::Path {
+                    path: ref path_b,
+                    font_index: index_b,
+                },
+            ) => {
+                index_a == index_b
+                    && (path_a == path_b
+                        || matches!(
+                            (path_a.canonicalize(), path_b.canonicalize()),
+                            (Ok(ref a), Ok(ref b)) if a == b
+                        ))
+            }
+            (
+                Handle::Memory {
+                    bytes: ref bytes_a,
+                    font_index: index_a,
+                },
+                Handle::Memory {
+                    bytes: ref bytes_b,
+                    font_index: index_b,
+                },
+            ) => index_a == index_b && (Arc::ptr_eq(bytes_a, bytes_b) || bytes_a == bytes_b),
+            (Handle::Path { .. }, Handle::Memory { .. })
+            | (Handle::Memory { .. }, Handle::Path { .. }) => false,
+        }
+    }
+
+    /// Returns a hash of the font index and the length and start of the font data, which is the
+    /// same for a path handle and a memory handle to the same font. Only the start of a file is
+    /// read. Returns `None` if the file can't be read.
+    #[cfg(feature = "source")]
+    pub(crate) fn fingerprint(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        match *self {
+            Handle::Path {
+                ref path,
+                font_index,
+            } => {
+                let file = File::open(path).ok()?;
+                let len = file.metadata().ok()?.len() as usize;
+                let mut start = vec![];
+                file.take(MEMORY_FINGERPRINT_LENGTH as u64)
+                    .read_to_end(&mut start)
+                    .ok()?;
+                hash_fingerprint(&mut hasher, len, &start, font_index);
+            }
+            Handle::Memory {
+                ref bytes,
+                font_index,
+            } => hash_font_data(&mut hasher, bytes, font_index),
+        }
+        Some(hasher.finish())
+    }
+
+    // Returns the font data, reading it from disk for a path handle.
+    #[cfg(feature = "source")]
+    fn data(&self) -> Option<Arc<Vec<u8>>> {
+        match *self {
+            Handle::Path { ref path, .. } => fs::read(path).ok().map(Arc::new),
+            Handle::Memory { ref bytes, .. } => Some(bytes.clone()),
+        }
+    }
+
+    #[cfg(feature = "source")]
+    fn font_index(&self) -> u32 {
+        match *self {
+            Handle::Path { font_index, .. } | Handle::Memory { font_index, .. } => font_index,
+        }
+    }
+}
+
+/// Removes handles that refer to the same font as an earlier one, keeping the earlier handle.
+///
+/// Unlike `Handle::refers_to_same_font()`, this also finds a font on disk and the same font in
+/// memory. Each handle's `Handle::fingerprint()` is computed once, and font data is only read in
+/// full, at most once per handle, to confirm a match between handles whose fingerprints collide.
+#[cfg(feature = "source")]
+pub(crate) fn dedup_handles(handles: &mut Vec<Handle>) {
+    let mut kept: Vec<Handle> = Vec::with_capacity(handles.len());
+    // The full data of each kept handle, once it has been read.
+    let mut kept_data: Vec<Option<Option<Arc<Vec<u8>>>>> = Vec::with_capacity(handles.len());
+    let mut kept_by_fingerprint: HashMap<u64, Vec<usize>> = HashMap::new();
+    for handle in handles.drain(..) {
+        let fingerprint = match handle.fingerprint() {
+            Some(fingerprint) => fingerprint,
+            None => {
+                if !kept.contains(&handle) {
+                    kept.push(handle);
+                    kept_data.push(None);
+                }
+                continue;
+            }
+        };
+        let candidates = kept_by_fingerprint.entry(fingerprint).or_default();
+        let mut data = None;
+        let is_duplicate = candidates.iter().any(|&index| {
+            let kept_handle = &kept[index];
+            if *kept_handle == handle {
+                return true;
+            }
+            if kept_handle.font_index() != handle.font_index() {
+                return false;
+            }
+            let kept_data = kept_data[index].get_or_insert_with(|| kept_handle.data());
+            let data = data.get_or_insert_with(|| handle.data());
+            matches!((kept_data, data), (Some(ref a), Some(ref b)) if a == b)
+        });
+        if !is_duplicate {
+            candidates.push(kept.len());
+            kept.push(handle);
+            kept_data.push(data);
+        }
+    }
+    handles.extend(kept);
+}
+
+/// Returns true if two copies of font data hold the same bytes.
+///
+/// Shared data is equal without looking at it. Otherwise the lengths and starts are compared first,
+/// as `Handle::fingerprint()` hashes them, so different fonts are usually told apart without
+/// reading them in full; only data whose fingerprints match is compared byte for byte, as
+/// `Handle` does. Loaders use this for `PartialEq`, together with `hash_font_data()` for `Hash`.
+pub(crate) fn font_data_matches(a: &Arc<Vec<u8>>, b: &Arc<Vec<u8>>) -> bool {
+    Arc::ptr_eq(a, b)
+        || (a.len() == b.len() && fingerprint_start(a) == fingerprint_start(b) && a[..] == b[..])
 }
+
+/// Hashes font data and a face index consistently with `font_data_matches()`.
+pub(crate) fn hash_font_data<H: Hasher>(state: &mut H, font_data: &[u8], font_index: u32) {
+    hash_fingerprint(
+        state,
+        font_data.len(),
+        fingerprint_start(font_data),
+        font_index,
+    );
+}
+
+fn fingerprint_start(font_data: &[u8]) -> &[u8] {
+    &font_data[..font_data.len().min(MEMORY_FINGERPRINT_LENGTH)]
+}
+
+fn hash_fingerprint<H: Hasher>(state: &mut H, len: usize, start: &[u8], font_index: u32) {
+    len.hash(state);
+    start.hash(state);
+    font_index.hash(state);
+}
+
+// Returns the bytes of a path as they appear in a `file:` URI, with `/` separators.
+#[cfg(unix)]
+fn path_to_uri_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_to_uri_bytes(path: &Path) -> Vec<u8> {
+    let path = path.to_string_lossy().replace('\\', "/");
+    // Drive letters follow the slash that starts the path, as in `file:///C:/Fonts/Font.ttf`.
+    if path.as_bytes().get(1) == Some(&b':') {
+        format!("/{}", path).into_bytes()
+    } else {
+        path.into_bytes()
+    }
+}
+
+#[cfg(unix)]
+fn uri_bytes_to_path(bytes: Vec<u8>) -> Option<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+    Some(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+fn uri_bytes_to_path(bytes: Vec<u8>) -> Option<PathBuf> {
+    let path = String::from_utf8(bytes).ok()?;
+    let path = match path.as_bytes().get(2) {
+        Some(b':') if path.starts_with('/') => &path[1..],
+        _ => &path[..],
+    };
+    Some(PathBuf::from(path.replace('/', "\\")))
+}
+
+fn percent_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    Some(bytes)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Appends `bytes` in standard padded base64, as used in `data:` URIs.
+fn push_base64(text: &mut String, bytes: &[u8]) {
+    text.reserve(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | (byte as u32) << (16 - index * 8)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(BASE64_ALPHABET[(group >> (18 - index * 6)) as usize & 0x3f] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut group = 0;
+        for (index, &character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|&candidate| candidate == character)?;
+            group |= (value as u32) << (18 - index * 6);
+        }
+        for index in 0..(chunk.len() - 1) {
+            bytes.push((group >> (16 - index * 8)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// A handle to one named instance of a variable font, as returned by `Handle::named_instances()`.
+///
+/// Loading it opens the underlying font and applies the instance's axis coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceHandle {
+    /// The handle to the variable font.
+    pub handle: Handle,
+    /// The subfamily name of the instance, such as "Bold", if the font has one.
+    pub name: Option<String>,
+    /// The coordinates of the instance along each axis of the font, as `(tag, value)` pairs.
+    pub variations: Vec<(u32, f32)>,
+    /// The properties of the instance, resolved from its axis coordinates.
+    pub properties: Properties,
+}
+
+impl InstanceHandle {
+    /// Loads this instance with the default loader, producing a Font set to its coordinates.
+    #[inline]
+    pub fn load(&self) -> Result<Font, FontLoadingError> {
+        self.load_with()
+    }
+
+    /// Loads this instance with a specific loader, producing a font set to its coordinates.
+    ///
+    /// Loaders that can't vary fonts produce the default instance.
+    pub fn load_with<L>(&self) -> Result<L, FontLoadingError>
+    where
+        L: Loader,
+    {
+        let mut font = L::from_handle(&self.handle)?;
+        font.set_variations(&self.variations)?;
+        Ok(font)
+    }
+}
+
+impl PartialEq for Handle {
+    fn eq(&self, other: &Handle) -> bool {
+        match (self, other) {
+            (
+                Handle::Path {
+                    path: ref path_a,
+                    font_index: index_a,
+                },
+                Handle::Path {
+                    path: ref path_b,
+                    font_index: index_b,
+                },
+            ) => index_a == index_b && path_a == path_b,
+            (
+                Handle::Memory {
+                    bytes: ref bytes_a,
+                    font_index: index_a,
+                },
+                Handle::Memory {
+                    bytes: ref bytes_b,
+                    font_index: index_b,
+                },
+            ) => index_a == index_b && (Arc::ptr_eq(bytes_a, bytes_b) || bytes_a == bytes_b),
+            (Handle::Path { .. }, Handle::Memory { .. })
+            | (Handle::Memory { .. }, Handle::Path { .. }) => false,
+        }
+    }
+}
+
+impl Eq for Handle {}
+
+impl Hash for Handle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Handle::Path {
+                ref path,
+                font_index,
+            } => {
+                0u8.hash(state);
+                path.hash(state);
+                font_index.hash(state);
+            }
+            Handle::Memory {
+                ref bytes,
+                font_index,
+            } => {
+                // Hashing a whole font would be slow. The length and the start of the data, which
+                // for SFNT fonts includes the table directory with its checksums, are enough of a
+                // fingerprint; equality still compares everything.
+                1u8.hash(state);
+                hash_font_data(state, bytes, font_index);
+            }
+        }
+    }
+}
+
+const MEMORY_FINGERPRINT_LENGTH: usize = 1024;
@@ -53,4 +53,16 @@ impl HintingOptions {
             | HintingOptions::Full(size) => Some(size),
         }
     }
+
+    /// Returns these options with the grid fitting size multiplied by `factor`.
+    pub(crate) fn scaled(self, factor: f32) -> HintingOptions {
+        match self {
+            HintingOptions::None => HintingOptions::None,
+            HintingOptions::Vertical(size) => HintingOptions::Vertical(size * factor),
+            HintingOptions::VerticalSubpixel(size) => {
+                HintingOptions::VerticalSubpixel(size * factor)
+            }
+            HintingOptions::Full(size) => HintingOptions::Full(size * factor),
+        }
+    }
 }
@@ -31,6 +31,8 @@
 //!                                   .load()
 //!                                   .unwrap();
 //!     let glyph_id = font.glyph_for_char('A').unwrap();
+//!     # // The pure-Rust loader can't rasterize.
+//!     # if !font.supports_hinting_options(HintingOptions::None, true) { return; }
 //!     let mut canvas = Canvas::new(Vector2I::splat(32), Format::A8);
 //!     font.rasterize_glyph(&mut canvas,
 //!                          glyph_id,
@@ -59,6 +61,11 @@
 //!
 //! * FreeType (cross-platform): A full-featured font rendering framework.
 //!
+//! * Pure Rust (cross-platform): A loader built on `ttf-parser` that needs no system libraries.
+//!   Reads names, metrics, glyphs, and outlines, but can't hint or rasterize. Enable it with the
+//!   `pure-rust` Cargo feature. Where FreeType would be the default, this makes the pure-Rust
+//!   loader the default instead, and leaves FreeType out unless `loader-freetype` is also enabled.
+//!
 //! Available sources:
 //!
 //! * Core Text (macOS): The system font database on macOS.
@@ -124,7 +131,9 @@
 #[macro_use]
 extern crate bitflags;
 
+pub mod aat;
 pub mod canvas;
+pub mod coverage;
 pub mod error;
 pub mod family;
 pub mod family_handle;
@@ -144,5 +153,10 @@ pub mod source;
 #[cfg(feature = "source")]
 pub mod sources;
 
+mod embedded_bitmaps;
+#[cfg(feature = "source")]
 mod matching;
+mod rasterizer;
+mod sdf;
+mod tables;
 mod utils;
@@ -11,20 +11,28 @@
 //! Provides a common interface to the platform-specific API that loads, parses, and rasterizes
 //! fonts.
 
-use log::warn;
+use pathfinder_geometry::line_segment::LineSegment2F;
 use pathfinder_geometry::rect::{RectF, RectI};
 use pathfinder_geometry::transform2d::Transform2F;
-use pathfinder_geometry::vector::Vector2F;
+use pathfinder_geometry::vector::{Vector2F, Vector2I};
+use std::ops::Range;
 use std::sync::Arc;
 
-use crate::canvas::{Canvas, RasterizationOptions};
+use crate::aat::morx;
+use crate::aat::Feature;
+use crate::canvas::{BorrowedCanvas, Canvas, Format, RasterizationOptions, RasterizationSettings};
+use crate::coverage::{CodePage, UnicodeRanges};
+use crate::embedded_bitmaps;
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
 use crate::handle::Handle;
 use crate::hinting::HintingOptions;
-use crate::metrics::Metrics;
-use crate::outline::OutlineSink;
-use crate::properties::Properties;
+use crate::metrics::{LineHeightConvention, Metrics};
+use crate::outline::{BoundsSink, Mirror, OutlineSink, TransformingSink};
+use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::rasterizer;
+use crate::sdf::{self, Flattener};
+use crate::tables;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
@@ -112,12 +120,179 @@ pub trait Loader: Clone + Sized {
     /// Returns the name of the font family.
     fn family_name(&self) -> String;
 
+    /// Returns the names of the font family in every language the font provides, such as
+    /// "メイリオ" as well as "Meiryo", starting with `family_name()`.
+    ///
+    /// The names come from the typographic family (name ID 16) and family (name ID 1) records of
+    /// the `name` table, without duplicates. Fonts without a `name` table have only
+    /// `family_name()`. The Core Text and DirectWrite loaders also include the localized names
+    /// the platform reports.
+    fn localized_family_names(&self) -> Vec<String> {
+        let mut family_names = vec![self.family_name()];
+        push_name_table_family_names(self, &mut family_names);
+        family_names
+    }
+
+    /// Returns the version string of the font (name ID 5), for example "Version 1.002".
+    ///
+    /// The string is returned as stored, without trimming. Fonts without a `name` table or a
+    /// version name return `None`.
+    #[inline]
+    fn version_string(&self) -> Option<String> {
+        self.load_font_table(tables::TAG_NAME)
+            .and_then(|name| tables::name_string(&name, tables::NAME_ID_VERSION))
+    }
+
+    /// Returns the font revision set by the font vendor, from `head.fontRevision`.
+    ///
+    /// Fonts without a `head` table return 0.
+    #[inline]
+    fn revision(&self) -> f32 {
+        self.load_font_table(tables::TAG_HEAD)
+            .and_then(|head| tables::head_font_revision(&head))
+            .unwrap_or(0.0)
+    }
+
     /// Returns true if and only if the font is monospace (fixed-width).
     fn is_monospace(&self) -> bool;
 
     /// Returns the values of various font properties, corresponding to those defined in CSS.
     fn properties(&self) -> Properties;
 
+    /// Returns the axes along which a variable font can vary, from the `fvar` table.
+    ///
+    /// Fonts that aren't variable have none.
+    fn variation_axes(&self) -> Vec<VariationAxis> {
+        self.load_font_table(tables::TAG_FVAR)
+            .and_then(|fvar| tables::fvar_axes(&fvar))
+            .unwrap_or_default()
+    }
+
+    /// Returns the named instances of a variable font, such as "Bold" or "Condensed Light", from
+    /// the `fvar` table.
+    ///
+    /// Fonts that aren't variable have none.
+    fn named_instances(&self) -> Vec<NamedInstance> {
+        let fvar = match self.load_font_table(tables::TAG_FVAR) {
+            Some(fvar) => fvar,
+            None => return vec![],
+        };
+        let axes = self.variation_axes();
+        let name = self.load_font_table(tables::TAG_NAME);
+        tables::fvar_instances(&fvar)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(subfamily_name_id, coordinates)| NamedInstance {
+                name: name
+                    .as_ref()
+                    .and_then(|name| tables::name_string(name, subfamily_name_id)),
+                coordinates: axes.iter().map(|axis| axis.tag).zip(coordinates).collect(),
+            })
+            .collect()
+    }
+
+    /// Sets the coordinates of a variable font along its axes of variation, as `(tag, value)`
+    /// pairs in each axis's own units.
+    ///
+    /// Loaders that can't vary fonts ignore this. Returns an error if the font's variation tables
+    /// can't be applied.
+    fn set_variations(&mut self, _: &[(u32, f32)]) -> Result<(), FontLoadingError> {
+        Ok(())
+    }
+
+    /// Returns the recommended distance between baselines at the given size, in pixels per em,
+    /// following the given platform convention.
+    ///
+    /// If the tables that the convention needs are missing, this falls back to the ascent,
+    /// descent, and line gap from `metrics()`.
+    fn recommended_line_height(&self, point_size: f32, convention: LineHeightConvention) -> f32 {
+        let os2 = self.load_font_table(tables::TAG_OS_2);
+        let hhea_line_height = || {
+            let hhea = self.load_font_table(tables::TAG_HHEA)?;
+            let [ascender, descender, line_gap] = tables::hhea_line_metrics(&hhea)?;
+            Some(ascender as f32 - descender as f32 + line_gap as f32)
+        };
+        let line_height = match convention {
+            LineHeightConvention::Css => os2
+                .as_ref()
+                .filter(|os2| tables::os2_use_typo_metrics(os2) == Some(true))
+                .and_then(|os2| tables::os2_typo_line_metrics(os2))
+                .map(|[ascender, descender, line_gap]| {
+                    ascender as f32 - descender as f32 + line_gap as f32
+                })
+                .or_else(hhea_line_height),
+            LineHeightConvention::Windows => os2
+                .as_ref()
+                .and_then(|os2| tables::os2_win_metrics(os2))
+                .map(|[ascent, descent]| ascent as f32 + descent as f32),
+            LineHeightConvention::Mac => hhea_line_height(),
+        };
+
+        let metrics = self.metrics();
+        let line_height =
+            line_height.unwrap_or(metrics.ascent - metrics.descent + metrics.line_gap);
+        line_height * point_size / metrics.units_per_em as f32
+    }
+
+    /// Returns the position of a baseline for the given script, from the `BASE` table, in font
+    /// units above the alphabetic (roman) baseline used for the outlines.
+    ///
+    /// `script` is an OpenType script tag as a big-endian integer, such as `hani` (0x68616e69) for
+    /// CJK ideographs. Scripts the table doesn't list fall back to its default script. Returns
+    /// `None` if the font has no `BASE` table, as most Latin fonts don't, or if the table doesn't
+    /// define that baseline. Only the horizontal axis is consulted.
+    fn baseline(&self, script: u32, baseline: BaselineTag) -> Option<f32> {
+        let base = self.load_font_table(tables::TAG_BASE)?;
+        tables::base_coordinate(&base, script, baseline.tag()).map(|coordinate| coordinate as f32)
+    }
+
+    /// Returns the italic angle of the font from the `post` table, in counterclockwise degrees
+    /// from the vertical.
+    ///
+    /// Fonts that slant forward have a negative italic angle. Upright fonts, and fonts without a
+    /// `post` table, return 0.
+    #[inline]
+    fn italic_angle(&self) -> f32 {
+        self.load_font_table(tables::TAG_POST)
+            .and_then(|post| tables::post_italic_angle(&post))
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the Unicode blocks that the font claims to support, from the `OS/2` table.
+    ///
+    /// These bits are often inaccurate; use `glyph_for_char()` for an authoritative answer. Fonts
+    /// without an `OS/2` table return an empty set.
+    #[inline]
+    fn unicode_ranges(&self) -> UnicodeRanges {
+        self.load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_unicode_ranges(&os2))
+            .map(UnicodeRanges::from_bits)
+            .unwrap_or_default()
+    }
+
+    /// Returns the restrictions that the font's license places on embedding it in documents, from
+    /// `OS/2.fsType`.
+    ///
+    /// Tools that embed or subset fonts, such as PDF writers, should honor these. Fonts without
+    /// an `OS/2` table are installable, with no restrictions.
+    fn embedding_restrictions(&self) -> EmbeddingRestrictions {
+        self.load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_fs_type(&os2))
+            .map(EmbeddingRestrictions::from_fs_type)
+            .unwrap_or_default()
+    }
+
+    /// Returns the legacy code pages that the font claims to support, from the `OS/2` table.
+    ///
+    /// Fonts without an `OS/2` table, or with a version 0 table (which lacks these fields),
+    /// return an empty list.
+    fn codepage_ranges(&self) -> Vec<CodePage> {
+        self.load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_codepage_ranges(&os2))
+            .map(CodePage::from_bits)
+            .unwrap_or_default()
+    }
+
     /// Returns the number of glyphs in the font.
     ///
     /// Glyph IDs range from 0 inclusive to this value exclusive.
@@ -127,22 +302,184 @@ pub trait Loader: Clone + Sized {
     ///
     /// Be careful with this function; typographically correct character-to-glyph mapping must be
     /// done using a *shaper* such as HarfBuzz. This function is only useful for best-effort simple
-    /// use cases like "what does character X look like on its own".
+    /// use cases like "what does character X look like on its own". In particular, emoji
+    /// sequences, such as those joined with U+200D ZERO WIDTH JOINER, need shaping.
     fn glyph_for_char(&self, character: char) -> Option<u32>;
 
+    /// Returns the usual glyph ID for each `char` of `text`, in order, as `glyph_for_char()` does.
+    ///
+    /// This is *not* shaping: there is exactly one entry per `char`, so ligatures, combining
+    /// sequences, contextual forms, and complex scripts such as Arabic or Devanagari come out
+    /// wrong. Use a shaper such as HarfBuzz for real text layout.
+    #[inline]
+    fn map_str(&self, text: &str) -> Vec<Option<u32>> {
+        text.chars()
+            .map(|character| self.glyph_for_char(character))
+            .collect()
+    }
+
+    /// Looks up a character in the font's character map, distinguishing an explicit mapping to
+    /// `.notdef` (glyph 0) from no mapping at all.
+    ///
+    /// This is useful for fallback decisions: a font that explicitly maps a character to `.notdef`
+    /// is claiming that it has no glyph for it. Fonts without a `cmap` table fall back to
+    /// `glyph_for_char()`, which can't tell the two cases apart and so never returns `NotDef`.
+    fn map_char(&self, character: char) -> GlyphMapping {
+        if let Some(mapping) = self
+            .load_font_table(tables::TAG_CMAP)
+            .and_then(|cmap| tables::cmap_lookup(&cmap, character as u32))
+        {
+            return mapping;
+        }
+        match self.glyph_for_char(character) {
+            Some(glyph_id) if glyph_id != 0 => GlyphMapping::Mapped(glyph_id),
+            _ => GlyphMapping::Unmapped,
+        }
+    }
+
     /// Returns the glyph ID for the specified glyph name.
+    ///
+    /// `.notdef` always resolves to glyph 0. In TrueType fonts that don't name their glyphs, such
+    /// as those with a format 3 `post` table, `.null` and `nonmarkingreturn` resolve to glyphs 1
+    /// and 2, which the format reserves for them by convention.
+    ///
+    /// The default implementation only resolves these special names.
+    #[inline]
+    fn glyph_by_name(&self, name: &str) -> Option<u32> {
+        conventional_glyph_by_name(self, name)
+    }
+
+    /// Returns the ID of the `.notdef` glyph, which fonts draw for characters they don't cover.
+    ///
+    /// This is glyph 0 in every font.
     #[inline]
-    fn glyph_by_name(&self, _name: &str) -> Option<u32> {
-        warn!("unimplemented");
-        None
+    fn notdef_glyph(&self) -> u32 {
+        0
+    }
+
+    /// Returns the name of the glyph with the given ID, the reverse of `glyph_by_name()`.
+    ///
+    /// The default implementation reads the `post` table. Returns `None` if the font doesn't name
+    /// its glyphs.
+    fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        let post = self.load_font_table(tables::TAG_POST)?;
+        tables::post_glyph_name(&post, glyph_id)
+    }
+
+    /// Applies the Apple Advanced Typography substitutions in the `morx` table to a run of glyphs,
+    /// with `features` turned on or off relative to the font's defaults.
+    ///
+    /// Ligatures can merge several glyphs into one, so the result may be shorter than `glyphs`.
+    /// Fonts without a `morx` table return the glyphs unchanged. Only noncontextual and ligature
+    /// substitutions are supported; see the `aat` module.
+    fn apply_aat_features(&self, glyphs: &[u32], features: &[Feature]) -> Vec<u32> {
+        match self.load_font_table(tables::TAG_MORX) {
+            Some(morx) => morx::apply(&morx, glyphs, features),
+            None => glyphs.to_vec(),
+        }
+    }
+
+    /// Returns the embedded color image of a glyph from the strike that best fits
+    /// `pixels_per_em`, as color emoji fonts provide in their `sbix` or `CBDT` tables.
+    ///
+    /// The smallest strike at least `pixels_per_em` in size is chosen, or the largest strike if
+    /// none is that large; callers scale the image from `GlyphRasterImage::pixels_per_em`. The
+    /// image bytes are passed through untouched, so PNG data can go straight to a decoder.
+    /// Returns `None` if the font has no image for the glyph.
+    fn glyph_raster_image(&self, glyph_id: u32, pixels_per_em: f32) -> Option<GlyphRasterImage> {
+        if let Some(sbix) = self.load_font_table(tables::TAG_SBIX) {
+            let image =
+                embedded_bitmaps::sbix_image(&sbix, self.glyph_count(), glyph_id, pixels_per_em);
+            if image.is_some() {
+                return image;
+            }
+        }
+        let cblc = self.load_font_table(tables::TAG_CBLC)?;
+        let cbdt = self.load_font_table(tables::TAG_CBDT)?;
+        embedded_bitmaps::cbdt_image(&cblc, &cbdt, glyph_id, pixels_per_em)
+    }
+
+    /// Returns the class of a glyph from the `GDEF` glyph class definition.
+    ///
+    /// Returns `None` if the font has no `GDEF` table or doesn't classify the glyph.
+    fn glyph_class(&self, glyph_id: u32) -> Option<GlyphClass> {
+        let gdef = self.load_font_table(tables::TAG_GDEF)?;
+        match tables::gdef_glyph_class(&gdef, glyph_id)? {
+            1 => Some(GlyphClass::Base),
+            2 => Some(GlyphClass::Ligature),
+            3 => Some(GlyphClass::Mark),
+            4 => Some(GlyphClass::Component),
+            _ => None,
+        }
+    }
+
+    /// Returns the caret positions within a ligature glyph, from the `GDEF` ligature caret list.
+    ///
+    /// Each position is an x offset from the glyph origin, in font units, marking a boundary
+    /// between the characters that the ligature represents. This lets a text editor place the
+    /// cursor inside a ligature. Glyphs that aren't ligatures, and fonts without caret data,
+    /// return an empty list.
+    fn ligature_carets(&self, glyph_id: u32) -> Vec<f32> {
+        self.load_font_table(tables::TAG_GDEF)
+            .and_then(|gdef| tables::gdef_ligature_carets(&gdef, glyph_id))
+            .unwrap_or_default()
+    }
+
+    /// Returns the languages and scripts the font was designed for, from the `dlng` entry of the
+    /// `meta` table.
+    ///
+    /// Entries are ScriptLangTags such as `"Latn"`, `"Hant"`, or `"ja"`, in the order the font
+    /// lists them. Fonts without a `meta` table or a `dlng` entry return an empty list.
+    fn design_languages(&self) -> Vec<String> {
+        self.load_font_table(tables::TAG_META)
+            .and_then(|meta| tables::meta_languages(&meta, tables::META_TAG_DLNG))
+            .unwrap_or_default()
+    }
+
+    /// Returns the languages and scripts the font can render, from the `slng` entry of the `meta`
+    /// table.
+    ///
+    /// Entries are ScriptLangTags as in `design_languages()`. Fonts without a `meta` table or an
+    /// `slng` entry return an empty list.
+    fn supported_languages(&self) -> Vec<String> {
+        self.load_font_table(tables::TAG_META)
+            .and_then(|meta| tables::meta_languages(&meta, tables::META_TAG_SLNG))
+            .unwrap_or_default()
+    }
+
+    /// Returns the kind of curves that the font's glyph outlines are made of, which tells whether
+    /// `outline()` sends quadratic or cubic Béziers.
+    ///
+    /// This is read from the outline tables, so it works for fonts in collections too. Fonts
+    /// without a `glyf`, `CFF `, or `CFF2` table, such as Type 1 and bitmap-only fonts, return
+    /// `None`.
+    fn outline_format(&self) -> Option<OutlineFormat> {
+        if self.table_len(tables::TAG_GLYF).is_some() {
+            Some(OutlineFormat::TrueType)
+        } else if self.table_len(tables::TAG_CFF).is_some()
+            || self.table_len(tables::TAG_CFF2).is_some()
+        {
+            Some(OutlineFormat::Cff)
+        } else {
+            None
+        }
     }
 
     /// Sends the vector path for a glyph to a sink.
     ///
     /// If `hinting_mode` is not None, this function performs grid-fitting as requested before
-    /// sending the hinding outlines to the builder.
+    /// sending the hinted outlines to the builder. The size that `hinting_mode` carries (see
+    /// `HintingOptions::grid_fitting_size()`) is the pixel size of the grid, so hinted outlines
+    /// differ from size to size. `HintingOptions::None` carries no size: unhinted outlines are the
+    /// same at every size.
+    ///
+    /// Glyphs in bitmap-only fonts have no vector path, so nothing is sent for them. Use
+    /// `outline_with_bitmap_fallback()` to get approximate geometry instead.
     ///
-    /// TODO(pcwalton): What should we do for bitmap glyphs?
+    /// Curves are sent as the font stores them: quadratic Béziers for TrueType outlines, and cubic
+    /// Béziers for CFF and Type 1 outlines.
+    ///
+    /// Points are in font units, with y pointing up, whether or not the outline is hinted.
     fn outline<S>(
         &self,
         glyph_id: u32,
@@ -152,6 +489,93 @@ pub trait Loader: Clone + Sized {
     where
         S: OutlineSink;
 
+    /// Counts the contours and points of a glyph, to judge its complexity before extracting its
+    /// outline.
+    ///
+    /// Simple TrueType glyphs are counted from their `glyf` headers and point flags without
+    /// decoding coordinates. Other glyphs, including composite glyphs and CFF charstrings, are
+    /// counted from the segments of their unhinted outline: each contour start, line end, and
+    /// curve end is an on-curve point, except an end that closes the contour at its start, and
+    /// each curve control point is an off-curve point. Both ways count the on-curve points that
+    /// TrueType implies between two consecutive off-curve points, so a glyph gives the same
+    /// stats whichever way it's counted.
+    fn glyph_complexity(&self, glyph_id: u32) -> Result<GlyphStats, GlyphLoadingError> {
+        if glyph_id >= self.glyph_count() {
+            return Err(GlyphLoadingError::NoSuchGlyph);
+        }
+        if let Some(stats) = glyf_glyph(self, glyph_id).and_then(|glyph| tables::glyf_stats(&glyph))
+        {
+            return Ok(stats);
+        }
+
+        let mut sink = GlyphStatsSink::default();
+        self.outline(glyph_id, HintingOptions::None, &mut sink)?;
+        Ok(sink.stats)
+    }
+
+    /// Sends the vector path for a glyph to a sink like `outline()`, except that glyphs from
+    /// bitmap-only fonts (such as PCF and BDF) are traced into filled rectangles, one per run of
+    /// set pixels in the font's first bitmap strike.
+    ///
+    /// The traced outline is in font units and follows the pixel grid exactly, so it's lossy: it
+    /// looks blocky when scaled. Loaders that can't read bitmap strikes behave like `outline()`.
+    #[inline]
+    fn outline_with_bitmap_fallback<S>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        self.outline(glyph_id, hinting_mode, sink)
+    }
+
+    /// Sends the vector path for a glyph to a sink, with all coordinates divided by the font's
+    /// units per em.
+    ///
+    /// The resulting coordinates are relative to the em square, so they lie roughly in the range
+    /// [0, 1]. This is convenient for glyph atlases and distance-field pipelines.
+    fn outline_em_normalized<S>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        let units_per_em = self.metrics().units_per_em;
+        self.outline(
+            glyph_id,
+            hinting_mode,
+            &mut TransformingSink::em_normalized(sink, units_per_em),
+        )
+    }
+
+    /// Sends the vector path for a glyph to a sink like `outline()`, mirrored about the center of
+    /// the glyph's `typographic_bounds()`.
+    ///
+    /// See `Mirror` for how this differs from Unicode bidi mirroring.
+    fn outline_mirrored<S>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        mirror: Mirror,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        let transform = mirror.transform(self.typographic_bounds(glyph_id)?);
+        self.outline(
+            glyph_id,
+            hinting_mode,
+            &mut TransformingSink::new(sink, transform),
+        )
+    }
+
     /// Returns the boundaries of a glyph in font units. The origin of the coordinate
     /// space is at the bottom left.
     fn typographic_bounds(&self, glyph_id: u32) -> Result<RectF, GlyphLoadingError>;
@@ -160,6 +584,75 @@ pub trait Loader: Clone + Sized {
     /// units.
     fn advance(&self, glyph_id: u32) -> Result<Vector2F, GlyphLoadingError>;
 
+    /// Returns the advance of the glyph with the given ID at `point_size`, in pixels.
+    ///
+    /// This is `advance()` scaled by `point_size / units_per_em`.
+    fn scaled_advance(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+    ) -> Result<Vector2F, GlyphLoadingError> {
+        Ok(self.advance(glyph_id)? * (point_size / self.metrics().units_per_em as f32))
+    }
+
+    /// Returns the hinted advance width of the glyph with the given ID at `ppem` pixels per em, as
+    /// recorded in the font's `hdmx` table.
+    ///
+    /// These pixel-exact widths are what legacy Windows text layout used. Returns `None` if the
+    /// font has no `hdmx` table, has no record for `ppem`, or has no glyph with this ID.
+    fn device_advance(&self, glyph_id: u32, ppem: u8) -> Option<u8> {
+        if glyph_id >= self.glyph_count() {
+            return None;
+        }
+        // `hdmx` has a byte per glyph per size, so read only the bytes needed.
+        let header = self.font_table_range(tables::TAG_HDMX, 0..8)?;
+        let record = tables::hdmx_record_offsets(&header, glyph_id)?.find(|&record| {
+            self.font_table_range(tables::TAG_HDMX, record..(record + 1))
+                .as_deref()
+                == Some(&[ppem])
+        })?;
+        let width_offset = record + 2 + glyph_id as usize;
+        let width = self.font_table_range(tables::TAG_HDMX, width_offset..(width_offset + 1))?;
+        Some(width[0])
+    }
+
+    /// Returns the smallest ppem from which the glyph's hinted advance width scales linearly with
+    /// the font size, as recorded in the font's `LTSH` table.
+    ///
+    /// At and above this size, the hinted advance is the linearly scaled one rounded to whole
+    /// pixels, so it needn't appear in `hdmx`; see `device_advance()`. Returns `None` if the font
+    /// has no `LTSH` table or the table has no entry for this glyph.
+    fn linear_threshold(&self, glyph_id: u32) -> Option<u8> {
+        let header = self.font_table_range(tables::TAG_LTSH, 0..4)?;
+        let offset = tables::ltsh_entry_offset(&header, glyph_id)?;
+        let threshold = self.font_table_range(tables::TAG_LTSH, offset..(offset + 1))?;
+        Some(threshold[0])
+    }
+
+    /// Returns the ink bounds of a run of glyphs laid out left to right by their advances, at
+    /// `point_size`, in pixels.
+    ///
+    /// As with `typographic_bounds()`, the origin is the start of the run on the baseline and y
+    /// points up. Glyphs without ink, such as spaces, advance the pen but don't extend the bounds;
+    /// a run without ink has empty bounds at the origin. No kerning or shaping is applied.
+    fn run_bounds(&self, glyph_ids: &[u32], point_size: f32) -> Result<RectF, GlyphLoadingError> {
+        let mut pen = Vector2F::zero();
+        let mut run_bounds: Option<RectF> = None;
+        for &glyph_id in glyph_ids {
+            let glyph_bounds = self.typographic_bounds(glyph_id)?;
+            if !is_empty_bounds(glyph_bounds) {
+                let glyph_bounds = glyph_bounds + pen;
+                run_bounds = Some(match run_bounds {
+                    Some(run_bounds) => run_bounds.union_rect(glyph_bounds),
+                    None => glyph_bounds,
+                });
+            }
+            pen += self.advance(glyph_id)?;
+        }
+        let scale = point_size / self.metrics().units_per_em as f32;
+        Ok(run_bounds.unwrap_or_default() * scale)
+    }
+
     /// Returns the amount that the given glyph should be displaced from the origin.
     fn origin(&self, glyph_id: u32) -> Result<Vector2F, GlyphLoadingError>;
 
@@ -178,7 +671,8 @@ pub trait Loader: Clone + Sized {
     /// Attempts to return the raw font data (contents of the font file).
     ///
     /// If this font is a member of a collection, this function returns the data for the entire
-    /// collection.
+    /// collection. Returns `None` if the data isn't available, which can happen for fonts created
+    /// with `from_native_font()`.
     fn copy_font_data(&self) -> Option<Arc<Vec<u8>>>;
 
     /// Returns true if and only if the font loader can perform hinting in the requested way.
@@ -196,17 +690,70 @@ pub trait Loader: Clone + Sized {
     /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
     /// rasterizer at the given `point_size` and `transform`. The origin of the coordinate space is
     /// at the top left.
+    ///
+    /// Glyphs without any outline, such as spaces, have empty bounds at the origin.
+    ///
+    /// The default implementation transforms the glyph's outline and rounds out its control box,
+    /// without rasterizing it. This contains every pixel the glyph covers, and under rotation or
+    /// skew it's tighter than transforming the typographic bounds. Glyphs with only a bitmap and no
+    /// outline use the bounds of their `glyph_raster_image()` scaled from its strike, or else
+    /// their transformed typographic bounds. Loaders that can measure glyphs more quickly natively
+    /// override this.
     fn raster_bounds(
         &self,
         glyph_id: u32,
         point_size: f32,
         transform: Transform2F,
-        _: HintingOptions,
+        hinting_options: HintingOptions,
         _: RasterizationOptions,
     ) -> Result<RectI, GlyphLoadingError> {
+        // Scale to pixels and flip to the top-left origin before applying `transform`.
+        let scale = point_size / self.metrics().units_per_em as f32;
+        let outline_transform = transform * Transform2F::from_scale(Vector2F::new(scale, -scale));
+        let mut sink = TransformingSink::new(BoundsSink::new(), outline_transform);
+        self.outline(glyph_id, hinting_options, &mut sink)?;
+        let raster_bounds = match sink.into_inner().bounds() {
+            Some(outline_bounds) => outline_bounds,
+            None => match self.glyph_raster_image(glyph_id, point_size) {
+                Some(image) if image.size.x() > 0 && image.size.y() > 0 => {
+                    // The image's bottom left corner is at `origin`, with y pointing up.
+                    let image_bounds = RectF::new(
+                        Vector2F::new(
+                            image.origin.x() as f32,
+                            -(image.origin.y() + image.size.y()) as f32,
+                        ),
+                        image.size.to_f32(),
+                    );
+                    transform * (image_bounds * (point_size / image.pixels_per_em as f32))
+                }
+                _ => self.raster_typographic_bounds(glyph_id, point_size, transform)?,
+            },
+        };
+        if is_empty_bounds(raster_bounds) || !is_finite_bounds(raster_bounds) {
+            return Ok(RectI::default());
+        }
+        Ok(raster_bounds.round_out().to_i32())
+    }
+
+    /// Returns the glyph's typographic bounds scaled to `point_size` and transformed by
+    /// `transform`, in the same top-left-origin pixel space as `raster_bounds()`.
+    ///
+    /// Unlike `raster_bounds()`, the result is not rounded out to whole pixels, which makes it
+    /// suitable for precise layout. Fonts with no units per em, which only have bitmap strikes,
+    /// give empty bounds.
+    fn raster_typographic_bounds(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+    ) -> Result<RectF, GlyphLoadingError> {
         let typographic_bounds = self.typographic_bounds(glyph_id)?;
-        let typographic_raster_bounds =
-            typographic_bounds * (point_size / self.metrics().units_per_em as f32);
+        // Fonts with only bitmap strikes may have no units per em to scale from.
+        let units_per_em = self.metrics().units_per_em;
+        if units_per_em == 0 {
+            return Ok(RectF::default());
+        }
+        let typographic_raster_bounds = typographic_bounds * (point_size / units_per_em as f32);
 
         // Translate the origin to "origin is top left" coordinate system.
         let new_origin = Vector2F::new(
@@ -214,7 +761,7 @@ pub trait Loader: Clone + Sized {
             -typographic_raster_bounds.origin_y() - typographic_raster_bounds.height(),
         );
         let typographic_raster_bounds = RectF::new(new_origin, typographic_raster_bounds.size());
-        Ok((transform * typographic_raster_bounds).round_out().to_i32())
+        Ok(transform * typographic_raster_bounds)
     }
 
     /// Rasterizes a glyph to a canvas with the given size and transform.
@@ -226,6 +773,13 @@ pub trait Loader: Clone + Sized {
     /// loader.
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
+    ///
+    /// Glyphs with empty `raster_bounds()`, such as spaces, return immediately without invoking
+    /// the rasterizer.
+    ///
+    /// If the glyph's `raster_bounds()` don't fit inside the canvas, nothing is drawn and
+    /// `GlyphLoadingError::CanvasTooSmall` is returned. Use `rasterize_glyph_clipped()` to draw
+    /// the visible part of the glyph instead.
     fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -236,6 +790,301 @@ pub trait Loader: Clone + Sized {
         rasterization_options: RasterizationOptions,
     ) -> Result<(), GlyphLoadingError>;
 
+    /// Rasterizes a glyph to a canvas like `rasterize_glyph()`, returning its advance at
+    /// `point_size`, in pixels, as `scaled_advance()` does.
+    ///
+    /// Layout code that draws each glyph and then moves the pen past it can use this to avoid
+    /// loading the glyph again for its advance; loaders that can read the advance while
+    /// rasterizing do so. The advance is unhinted and ignores `transform`.
+    fn rasterize_and_advance(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<Vector2F, GlyphLoadingError> {
+        self.rasterize_glyph(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        self.scaled_advance(glyph_id, point_size)
+    }
+
+    /// Rasterizes a glyph to a canvas with the given size and transform, clipping it to the
+    /// bounds of the canvas.
+    ///
+    /// This behaves like `rasterize_glyph()`, except that parts of the glyph that fall outside
+    /// the canvas are silently discarded.
+    ///
+    /// The default implementation rasterizes a glyph that doesn't fit into a temporary canvas
+    /// covering its `raster_bounds()` and copies the visible part across. Loaders that can clip
+    /// natively override this.
+    fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        let required = self.raster_bounds(
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        if fits_in_canvas(canvas, required) {
+            return self.rasterize_glyph(
+                canvas,
+                glyph_id,
+                point_size,
+                transform,
+                hinting_options,
+                rasterization_options,
+            );
+        }
+        rasterize_visible_part(
+            self,
+            &mut canvas.as_borrowed(),
+            required,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph into a canvas that borrows its pixels from the caller, clipping it to
+    /// the bounds of the canvas.
+    ///
+    /// This behaves like `rasterize_glyph_clipped()`, but draws straight into memory that the
+    /// caller owns, such as a mapped texture, without wrapping it in a `Canvas`. The glyph is
+    /// rasterized into a temporary canvas covering its `raster_bounds()`, and the part of it that
+    /// lands inside `canvas` is copied across.
+    fn rasterize_glyph_into(
+        &self,
+        canvas: &mut BorrowedCanvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        let required = self.raster_bounds(
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        rasterize_visible_part(
+            self,
+            canvas,
+            required,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph in the given color and composites it over an `Rgba32` canvas.
+    ///
+    /// `color` is non-premultiplied RGBA. The glyph coverage is multiplied by it and drawn over
+    /// the existing contents of the canvas with source-over blending, leaving the canvas
+    /// premultiplied. Subpixel antialiasing tints each channel by its own coverage.
+    ///
+    /// As with `rasterize_glyph()`, `GlyphLoadingError::CanvasTooSmall` is returned if the glyph
+    /// doesn't fit. There's no color to draw into `A8` or `Rgb24` canvases, so
+    /// `GlyphLoadingError::UnsupportedCanvasFormat` is returned for them.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize_glyph_colored(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        color: [u8; 4],
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        if canvas.format != Format::Rgba32 {
+            return Err(GlyphLoadingError::UnsupportedCanvasFormat);
+        }
+        let bounds = self.raster_bounds(
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        if !fits_in_canvas(canvas, bounds) {
+            return Err(GlyphLoadingError::CanvasTooSmall { required: bounds });
+        }
+        if bounds.width() <= 0 || bounds.height() <= 0 {
+            return Ok(());
+        }
+
+        let coverage_format = match rasterization_options {
+            RasterizationOptions::SubpixelAa => Format::Rgb24,
+            RasterizationOptions::Bilevel | RasterizationOptions::GrayscaleAa => Format::A8,
+        };
+        let mut coverage = Canvas::new(bounds.size(), coverage_format);
+        self.rasterize_glyph_clipped(
+            &mut coverage,
+            glyph_id,
+            point_size,
+            Transform2F::from_translation(-bounds.origin().to_f32()) * transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        canvas.composite_coverage(bounds.origin(), &coverage, color);
+        Ok(())
+    }
+
+    /// Rasterizes a glyph to a canvas like `rasterize_glyph()`, mirrored about the center of its
+    /// typographic bounds before `transform` is applied.
+    ///
+    /// The mirrored glyph covers the same `raster_bounds()` as the original, up to rounding. See
+    /// `Mirror` for how this differs from Unicode bidi mirroring.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize_glyph_mirrored(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        mirror: Mirror,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        let bounds =
+            self.raster_typographic_bounds(glyph_id, point_size, Transform2F::default())?;
+        self.rasterize_glyph(
+            canvas,
+            glyph_id,
+            point_size,
+            transform * mirror.transform(bounds),
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas like `rasterize_glyph()`, with the size given in points at
+    /// the given resolution in dots per inch rather than in pixels per em.
+    ///
+    /// There are 72 points to the inch, so this is `rasterize_glyph()` at a size of
+    /// `point_size * dpi / 72.0` pixels. The grid fitting size in `hinting_options` is in points
+    /// too and is scaled the same way, so hinting runs at the ppem the glyph is drawn at.
+    /// `transform` is in device pixels and is applied after scaling, as usual.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize_glyph_at_dpi(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        dpi: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        let scale = dpi / 72.0;
+        self.rasterize_glyph(
+            canvas,
+            glyph_id,
+            point_size * scale,
+            transform,
+            hinting_options.scaled(scale),
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas as `rasterize_glyph()` does, taking hinting, antialiasing,
+    /// and post-processing from a single `RasterizationSettings` value.
+    ///
+    /// Gamma, thresholds, dithering, and non-premultiplied output are applied only to the pixels
+    /// the glyph covers, so other contents of the canvas are left untouched. If the settings have
+    /// a background color, the whole canvas is instead filled with the glyph blended against it;
+    /// see `RasterizationOptionsBuilder::background_color()`. A background can't be blended into
+    /// an `A8` canvas, so `GlyphLoadingError::UnsupportedCanvasFormat` is returned for one.
+    fn rasterize_glyph_with_settings(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        settings: &RasterizationSettings,
+    ) -> Result<(), GlyphLoadingError> {
+        rasterize_glyph_with_settings(self, canvas, glyph_id, point_size, transform, settings)
+    }
+
+    /// Rasterizes a glyph to floating-point coverage with the given size and transform, avoiding
+    /// the 8-bit quantization of `rasterize_glyph()`.
+    ///
+    /// Returns the pixel rectangle covered by the glyph, with the origin at the top left as in
+    /// `raster_bounds()`, and one coverage value from 0.0 to 1.0 per pixel in row-major order with
+    /// no row padding. Coverage is computed analytically from the unhinted outline by `font-kit`
+    /// itself rather than the platform rasterizer, so it can differ slightly from
+    /// `rasterize_glyph()` output. Glyphs with no outline produce an empty rectangle and no data.
+    fn rasterize_glyph_f32(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+    ) -> Result<(RectI, Vec<f32>), GlyphLoadingError> {
+        let scale = point_size / self.metrics().units_per_em as f32;
+        let mut flattener = Flattener::new();
+        self.outline(
+            glyph_id,
+            HintingOptions::None,
+            &mut TransformingSink::new(
+                &mut flattener,
+                transform * Transform2F::row_major(scale, 0.0, 0.0, -scale, 0.0, 0.0),
+            ),
+        )?;
+        Ok(rasterizer::rasterize(flattener.segments()))
+    }
+
+    /// Generates a single-channel signed distance field for a glyph at the given size, in pixels
+    /// per em.
+    ///
+    /// `spread` is the distance from the glyph edge, in pixels, at which the field saturates. The
+    /// returned rectangle is the pixel area covered by the field, with the origin at the top left
+    /// as in `raster_bounds()`; it is padded by `spread` on all sides. The field contains one byte
+    /// per pixel in row-major order with no row padding. A value of 128 (0.5) lies on the edge,
+    /// values above that are inside the glyph, and values below are outside, reaching 255 and 0
+    /// respectively `spread` pixels away from the edge.
+    ///
+    /// Glyphs with no outline produce an empty rectangle and no data.
+    fn generate_sdf(
+        &self,
+        glyph_id: u32,
+        size: f32,
+        spread: f32,
+    ) -> Result<(RectI, Vec<u8>), GlyphLoadingError> {
+        let scale = size / self.metrics().units_per_em as f32;
+        let mut flattener = Flattener::new();
+        self.outline(
+            glyph_id,
+            HintingOptions::None,
+            &mut TransformingSink::new(
+                &mut flattener,
+                Transform2F::row_major(scale, 0.0, 0.0, -scale, 0.0, 0.0),
+            ),
+        )?;
+        Ok(sdf::generate(flattener.segments(), spread))
+    }
+
     /// Get font fallback results for the given text and locale.
     ///
     /// The `locale` argument is a language tag such as `"en-US"` or `"zh-Hans-CN"`.
@@ -243,6 +1092,213 @@ pub trait Loader: Clone + Sized {
 
     /// Returns the OpenType font table with the given tag, if the table exists.
     fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>>;
+
+    /// Returns the bytes in `range` of the OpenType font table with the given tag, if the table
+    /// exists and is at least `range.end` bytes long.
+    ///
+    /// Loaders that can read part of a table do so without copying the rest of it, which makes
+    /// this cheaper than `load_font_table()` for reading the header of a large table.
+    fn font_table_range(&self, table_tag: u32, range: Range<usize>) -> Option<Box<[u8]>> {
+        self.load_font_table(table_tag)?.get(range).map(Box::from)
+    }
+
+    /// Returns the length in bytes of the OpenType font table with the given tag, if the table
+    /// exists.
+    fn table_len(&self, table_tag: u32) -> Option<usize> {
+        self.load_font_table(table_tag).map(|table| table.len())
+    }
+}
+
+/// How a font's character map treats a character, as returned by `Loader::map_char()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphMapping {
+    /// The character maps to this glyph, which is never 0.
+    Mapped(u32),
+    /// The character map explicitly maps the character to glyph 0 (`.notdef`).
+    NotDef,
+    /// The character map has no entry for the character.
+    Unmapped,
+}
+
+/// The class of a glyph, as defined in the `GDEF` table and returned by `Loader::glyph_class()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphClass {
+    /// A single character, spacing glyph.
+    Base,
+    /// A multiple character, spacing glyph.
+    Ligature,
+    /// A non-spacing combining glyph, such as an accent.
+    Mark,
+    /// Part of a single character, spacing glyph.
+    Component,
+}
+
+/// The kind of glyph outlines in a font, as returned by `Loader::outline_format()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OutlineFormat {
+    /// TrueType outlines, from a `glyf` table, made of quadratic curves.
+    TrueType,
+    /// Compact Font Format outlines, from a `CFF ` or `CFF2` table, made of cubic curves.
+    Cff,
+}
+
+/// The number of contours and points in a glyph, as returned by `Loader::glyph_complexity()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GlyphStats {
+    /// The number of closed paths in the glyph.
+    pub contour_count: u32,
+    /// The total number of points, on and off the curve.
+    pub point_count: u32,
+    /// The number of points that the outline passes through.
+    pub on_curve_point_count: u32,
+    /// The number of Bézier control points.
+    pub off_curve_point_count: u32,
+}
+
+/// The licensing restrictions on embedding a font, as returned by
+/// `Loader::embedding_restrictions()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EmbeddingRestrictions {
+    /// What a document that embeds the font may do with it.
+    pub permission: EmbeddingPermission,
+    /// True if the font must be embedded whole rather than subset.
+    pub no_subsetting: bool,
+    /// True if only the font's embedded bitmaps may be embedded, not its outlines.
+    pub bitmap_only: bool,
+}
+
+impl EmbeddingRestrictions {
+    /// Decodes the bits of an `OS/2.fsType` value.
+    ///
+    /// Fonts from before version 3 of the `OS/2` table may set several usage permission bits; the
+    /// least restrictive of them applies.
+    pub fn from_fs_type(fs_type: u16) -> EmbeddingRestrictions {
+        let permission = if fs_type & 0x0008 != 0 {
+            EmbeddingPermission::Editable
+        } else if fs_type & 0x0004 != 0 {
+            EmbeddingPermission::PreviewAndPrint
+        } else if fs_type & 0x0002 != 0 {
+            EmbeddingPermission::Restricted
+        } else {
+            EmbeddingPermission::Installable
+        };
+        EmbeddingRestrictions {
+            permission,
+            no_subsetting: fs_type & 0x0100 != 0,
+            bitmap_only: fs_type & 0x0200 != 0,
+        }
+    }
+}
+
+/// The usage permission granted by a font's license, as part of `EmbeddingRestrictions`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EmbeddingPermission {
+    /// The font may be embedded and permanently installed on the remote system.
+    #[default]
+    Installable,
+    /// The font must not be embedded without permission from the legal owner.
+    Restricted,
+    /// The font may be embedded in documents that are only viewed and printed.
+    PreviewAndPrint,
+    /// The font may be embedded in documents that are viewed, printed, and edited.
+    Editable,
+}
+
+/// The encoding of an embedded glyph image, as returned by `Loader::glyph_raster_image()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// A PNG image.
+    Png,
+    /// A JPEG image.
+    Jpeg,
+    /// A TIFF image.
+    Tiff,
+}
+
+/// An embedded image of a glyph at one strike, as returned by `Loader::glyph_raster_image()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphRasterImage {
+    /// How `data` is encoded.
+    pub format: ImageFormat,
+    /// The encoded image, exactly as stored in the font.
+    pub data: Vec<u8>,
+    /// The size of the image in pixels, or zero if the font doesn't record it and the image isn't
+    /// a PNG.
+    pub size: Vector2I,
+    /// The position of the bottom left corner of the image relative to the glyph origin, in
+    /// pixels, with y pointing up.
+    pub origin: Vector2I,
+    /// The size of the em square, in pixels, that the image was drawn for.
+    pub pixels_per_em: u16,
+}
+
+/// A baseline defined in the `BASE` table, as passed to `Loader::baseline()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BaselineTag {
+    /// The alphabetic baseline of Latin, Greek, and Cyrillic (`romn`).
+    Roman,
+    /// The hanging baseline of Tibetan and Indic scripts such as Devanagari (`hang`).
+    Hanging,
+    /// The bottom of the ideographic em box, used to align CJK ideographs (`ideo`).
+    Ideographic,
+    /// The top of the ideographic em box (`idtp`).
+    IdeographicTop,
+    /// The bottom of the ideographic character face (`icfb`).
+    IdeographicFaceBottom,
+    /// The top of the ideographic character face (`icft`).
+    IdeographicFaceTop,
+    /// The baseline about which mathematical characters are centered (`math`).
+    Math,
+}
+
+impl BaselineTag {
+    /// Returns the four-character tag of the baseline as a big-endian integer.
+    pub fn tag(self) -> u32 {
+        match self {
+            BaselineTag::Roman => 0x726f6d6e,
+            BaselineTag::Hanging => 0x68616e67,
+            BaselineTag::Ideographic => 0x6964656f,
+            BaselineTag::IdeographicTop => 0x69647470,
+            BaselineTag::IdeographicFaceBottom => 0x69636662,
+            BaselineTag::IdeographicFaceTop => 0x69636674,
+            BaselineTag::Math => 0x6d617468,
+        }
+    }
+}
+
+/// An axis along which a variable font can vary, as returned by `Loader::variation_axes()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VariationAxis {
+    /// The four-character tag identifying the axis, such as `wght`, as a big-endian integer.
+    pub tag: u32,
+    /// The minimum coordinate value for the axis.
+    pub min_value: f32,
+    /// The coordinate value the font uses unless told otherwise.
+    pub default_value: f32,
+    /// The maximum coordinate value for the axis.
+    pub max_value: f32,
+}
+
+impl VariationAxis {
+    /// The weight axis, `wght`, with values on the same scale as `Weight`.
+    pub const WEIGHT: u32 = 0x77676874;
+    /// The width axis, `wdth`, with values as a percentage of the normal width.
+    pub const WIDTH: u32 = 0x77647468;
+    /// The italic axis, `ital`, from 0 (upright) to 1 (italic).
+    pub const ITALIC: u32 = 0x6974616c;
+    /// The slant axis, `slnt`, in counterclockwise degrees from the vertical.
+    pub const SLANT: u32 = 0x736c6e74;
+    /// The optical size axis, `opsz`, in points.
+    pub const OPTICAL_SIZE: u32 = 0x6f70737a;
+}
+
+/// A named instance of a variable font, as returned by `Loader::named_instances()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedInstance {
+    /// The subfamily name of the instance, such as "Bold", if the `name` table has one.
+    pub name: Option<String>,
+    /// The coordinates of the instance along each axis of the font, as `(tag, value)` pairs.
+    pub coordinates: Vec<(u32, f32)>,
 }
 
 /// The result of a fallback query.
@@ -263,3 +1319,462 @@ pub struct FallbackFont<Font> {
     pub scale: f32,
     // TODO: add font simulation data
 }
+
+/// Fills in a zero `cap_height` or `x_height` by measuring the top of the `H` or `x` glyph, for
+/// loaders that read them from the `OS/2` table.
+///
+/// Version 0 and 1 `OS/2` tables don't have these fields, some broken fonts set them to zero,
+/// and fonts without an `OS/2` table lack them entirely. A height stays zero if the font has no
+/// glyph for the character, or if that glyph is empty.
+pub(crate) fn measure_missing_heights<L>(loader: &L, metrics: &mut Metrics)
+where
+    L: Loader,
+{
+    let measure = |character| {
+        loader
+            .glyph_for_char(character)
+            .and_then(|glyph_id| loader.typographic_bounds(glyph_id).ok())
+            .filter(|&bounds| !is_empty_bounds(bounds))
+            .map_or(0.0, |bounds| bounds.max_y())
+    };
+    if metrics.cap_height == 0.0 {
+        metrics.cap_height = measure('H');
+    }
+    if metrics.x_height == 0.0 {
+        metrics.x_height = measure('x');
+    }
+}
+
+/// Resolves the glyph names that belong to the first glyphs by convention, for loaders whose
+/// lookup by name misses them, as `Loader::glyph_by_name()` describes.
+///
+/// `.null` and `nonmarkingreturn` are only resolved in TrueType fonts without glyph names, since
+/// a font that names its glyphs says which ones these are, and CFF fonts don't reserve them.
+pub(crate) fn conventional_glyph_by_name<L>(loader: &L, name: &str) -> Option<u32>
+where
+    L: Loader,
+{
+    let glyph_id = match name {
+        ".notdef" => return Some(loader.notdef_glyph()),
+        ".null" => 1,
+        "nonmarkingreturn" => 2,
+        _ => return None,
+    };
+    if glyph_id >= loader.glyph_count()
+        || loader.table_len(tables::TAG_GLYF).is_none()
+        || loader.glyph_name(0).is_some()
+    {
+        return None;
+    }
+    Some(glyph_id)
+}
+
+// The ranges of characters with the `Emoji_Presentation` property, from the Unicode 15.1
+// `emoji-data.txt`.
+#[cfg(any(
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_family = "windows",
+        target_arch = "wasm32"
+    )),
+    feature = "loader-freetype",
+    feature = "pure-rust"
+))]
+static EMOJI_PRESENTATION_RANGES: [(u32, u32); 81] = [
+    (0x231a, 0x231b),
+    (0x23e9, 0x23ec),
+    (0x23f0, 0x23f0),
+    (0x23f3, 0x23f3),
+    (0x25fd, 0x25fe),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267f, 0x267f),
+    (0x2693, 0x2693),
+    (0x26a1, 0x26a1),
+    (0x26aa, 0x26ab),
+    (0x26bd, 0x26be),
+    (0x26c4, 0x26c5),
+    (0x26ce, 0x26ce),
+    (0x26d4, 0x26d4),
+    (0x26ea, 0x26ea),
+    (0x26f2, 0x26f3),
+    (0x26f5, 0x26f5),
+    (0x26fa, 0x26fa),
+    (0x26fd, 0x26fd),
+    (0x2705, 0x2705),
+    (0x270a, 0x270b),
+    (0x2728, 0x2728),
+    (0x274c, 0x274c),
+    (0x274e, 0x274e),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27b0, 0x27b0),
+    (0x27bf, 0x27bf),
+    (0x2b1b, 0x2b1c),
+    (0x2b50, 0x2b50),
+    (0x2b55, 0x2b55),
+    (0x1f004, 0x1f004),
+    (0x1f0cf, 0x1f0cf),
+    (0x1f18e, 0x1f18e),
+    (0x1f191, 0x1f19a),
+    (0x1f1e6, 0x1f1ff),
+    (0x1f201, 0x1f201),
+    (0x1f21a, 0x1f21a),
+    (0x1f22f, 0x1f22f),
+    (0x1f232, 0x1f236),
+    (0x1f238, 0x1f23a),
+    (0x1f250, 0x1f251),
+    (0x1f300, 0x1f320),
+    (0x1f32d, 0x1f335),
+    (0x1f337, 0x1f37c),
+    (0x1f37e, 0x1f393),
+    (0x1f3a0, 0x1f3ca),
+    (0x1f3cf, 0x1f3d3),
+    (0x1f3e0, 0x1f3f0),
+    (0x1f3f4, 0x1f3f4),
+    (0x1f3f8, 0x1f43e),
+    (0x1f440, 0x1f440),
+    (0x1f442, 0x1f4fc),
+    (0x1f4ff, 0x1f53d),
+    (0x1f54b, 0x1f54e),
+    (0x1f550, 0x1f567),
+    (0x1f57a, 0x1f57a),
+    (0x1f595, 0x1f596),
+    (0x1f5a4, 0x1f5a4),
+    (0x1f5fb, 0x1f64f),
+    (0x1f680, 0x1f6c5),
+    (0x1f6cc, 0x1f6cc),
+    (0x1f6d0, 0x1f6d2),
+    (0x1f6d5, 0x1f6d7),
+    (0x1f6dc, 0x1f6df),
+    (0x1f6eb, 0x1f6ec),
+    (0x1f6f4, 0x1f6fc),
+    (0x1f7e0, 0x1f7eb),
+    (0x1f7f0, 0x1f7f0),
+    (0x1f90c, 0x1f93a),
+    (0x1f93c, 0x1f945),
+    (0x1f947, 0x1f9ff),
+    (0x1fa70, 0x1fa7c),
+    (0x1fa80, 0x1fa88),
+    (0x1fa90, 0x1fabd),
+    (0x1fabf, 0x1fac5),
+    (0x1face, 0x1fadb),
+    (0x1fae0, 0x1fae8),
+    (0x1faf0, 0x1faf8),
+];
+
+/// Returns true if the character is displayed as an emoji by default, that is, if it has the
+/// Unicode `Emoji_Presentation` property.
+///
+/// Loaders prefer the emoji glyph that U+FE0F selects only for these characters. Others, such as
+/// U+263A WHITE SMILING FACE, default to their text presentation.
+#[cfg(any(
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_family = "windows",
+        target_arch = "wasm32"
+    )),
+    feature = "loader-freetype",
+    feature = "pure-rust"
+))]
+pub(crate) fn has_emoji_presentation(character: char) -> bool {
+    let character = character as u32;
+    let index = EMOJI_PRESENTATION_RANGES.partition_point(|&(_, last)| last < character);
+    EMOJI_PRESENTATION_RANGES
+        .get(index)
+        .is_some_and(|&(first, _)| first <= character)
+}
+
+/// Overrides `properties` with those implied by the given variation axis coordinates, for
+/// loaders whose style tables describe only the default instance.
+pub(crate) fn apply_variation_coordinates(properties: &mut Properties, coordinates: &[(u32, f32)]) {
+    let (mut italic, mut slant) = (None, None);
+    for &(tag, value) in coordinates {
+        match tag {
+            VariationAxis::WEIGHT => properties.weight = Weight(value),
+            VariationAxis::WIDTH => properties.stretch = Stretch(value / 100.0),
+            VariationAxis::ITALIC => italic = Some(value),
+            VariationAxis::SLANT => slant = Some(value),
+            _ => {}
+        }
+    }
+    match (italic, slant) {
+        (Some(italic), _) if italic >= 0.5 => properties.style = Style::Italic,
+        (_, Some(slant)) if slant != 0.0 => properties.style = Style::Oblique(-slant),
+        (Some(_), _) | (_, Some(_)) => properties.style = Style::Normal,
+        (None, None) => {}
+    }
+}
+
+/// The default implementation of `Loader::rasterize_glyph_with_settings()`, for loaders that
+/// override it to apply settings of their own first.
+pub(crate) fn rasterize_glyph_with_settings<L>(
+    loader: &L,
+    canvas: &mut Canvas,
+    glyph_id: u32,
+    point_size: f32,
+    transform: Transform2F,
+    settings: &RasterizationSettings,
+) -> Result<(), GlyphLoadingError>
+where
+    L: Loader,
+{
+    // Dithering and thresholds work from the antialiased coverage that bilevel rendering would
+    // discard.
+    let bilevel = settings.rasterization_options() == RasterizationOptions::Bilevel;
+    let dither = bilevel && settings.dithering();
+    let threshold = settings.threshold().filter(|_| bilevel && !dither);
+    let rasterization_options = if dither || threshold.is_some() {
+        RasterizationOptions::GrayscaleAa
+    } else {
+        settings.rasterization_options()
+    };
+
+    // With a background, coverage goes to a separate mask that is then blended into the canvas.
+    let mut coverage = match settings.background_color() {
+        Some(_) if canvas.format == Format::A8 => {
+            return Err(GlyphLoadingError::UnsupportedCanvasFormat)
+        }
+        Some(_) => {
+            let coverage_format = match rasterization_options {
+                RasterizationOptions::SubpixelAa => Format::Rgb24,
+                RasterizationOptions::Bilevel | RasterizationOptions::GrayscaleAa => Format::A8,
+            };
+            Some(Canvas::new(canvas.size, coverage_format))
+        }
+        None => None,
+    };
+    let target = coverage.as_mut().unwrap_or(&mut *canvas);
+
+    loader.rasterize_glyph(
+        target,
+        glyph_id,
+        point_size,
+        transform,
+        settings.hinting_options(),
+        rasterization_options,
+    )?;
+
+    let unpremultiply = !settings.premultiplied() && target.format == Format::Rgba32;
+    if settings.gamma() != 1.0 || dither || threshold.is_some() || unpremultiply {
+        let bounds = loader.raster_bounds(
+            glyph_id,
+            point_size,
+            transform,
+            settings.hinting_options(),
+            rasterization_options,
+        )?;
+        if settings.gamma() != 1.0 {
+            target.apply_gamma(bounds, settings.gamma());
+        }
+        if dither {
+            target.apply_ordered_dither(bounds);
+        } else if let Some(threshold) = threshold {
+            target.apply_threshold(bounds, threshold);
+        }
+        if unpremultiply {
+            target.unpremultiply(bounds);
+        }
+    }
+
+    if let (Some(coverage), Some(background_color)) = (coverage, settings.background_color()) {
+        canvas.blend_coverage(&coverage, background_color, settings.text_color());
+    }
+    Ok(())
+}
+
+// Returns true if `bounds` covers no area, as for glyphs with no outline.
+fn is_empty_bounds(bounds: RectF) -> bool {
+    bounds.width() <= 0.0 || bounds.height() <= 0.0
+}
+
+fn is_finite_bounds(bounds: RectF) -> bool {
+    bounds.origin_x().is_finite()
+        && bounds.origin_y().is_finite()
+        && bounds.width().is_finite()
+        && bounds.height().is_finite()
+}
+
+/// Rasterizes a glyph as `Loader::rasterize_glyph()` describes, by checking that its
+/// `raster_bounds()` fit the canvas and then calling `rasterize_glyph_clipped()`.
+///
+/// Loaders that clip natively implement `rasterize_glyph()` with this.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_family = "windows",
+    target_arch = "wasm32",
+    not(feature = "pure-rust"),
+    feature = "loader-freetype"
+))]
+pub(crate) fn rasterize_glyph<L>(
+    loader: &L,
+    canvas: &mut Canvas,
+    glyph_id: u32,
+    point_size: f32,
+    transform: Transform2F,
+    hinting_options: HintingOptions,
+    rasterization_options: RasterizationOptions,
+) -> Result<(), GlyphLoadingError>
+where
+    L: Loader,
+{
+    let required = loader.raster_bounds(
+        glyph_id,
+        point_size,
+        transform,
+        hinting_options,
+        rasterization_options,
+    )?;
+    if required.width() <= 0 || required.height() <= 0 {
+        return Ok(());
+    }
+    if !fits_in_canvas(canvas, required) {
+        return Err(GlyphLoadingError::CanvasTooSmall { required });
+    }
+
+    loader.rasterize_glyph_clipped(
+        canvas,
+        glyph_id,
+        point_size,
+        transform,
+        hinting_options,
+        rasterization_options,
+    )
+}
+
+// Appends the typographic family and family names from the font's `name` table, in every
+// language, that aren't in `family_names` already.
+pub(crate) fn push_name_table_family_names<L>(loader: &L, family_names: &mut Vec<String>)
+where
+    L: Loader,
+{
+    if let Some(name) = loader.load_font_table(tables::TAG_NAME) {
+        for name_id in [tables::NAME_ID_TYPOGRAPHIC_FAMILY, tables::NAME_ID_FAMILY] {
+            for family_name in tables::name_strings(&name, name_id) {
+                if !family_names.contains(&family_name) {
+                    family_names.push(family_name);
+                }
+            }
+        }
+    }
+}
+
+// Rasterizes the part of a glyph covering `required` that lands inside `canvas`, by way of a
+// temporary canvas.
+#[allow(clippy::too_many_arguments)]
+fn rasterize_visible_part<L>(
+    loader: &L,
+    canvas: &mut BorrowedCanvas,
+    required: RectI,
+    glyph_id: u32,
+    point_size: f32,
+    transform: Transform2F,
+    hinting_options: HintingOptions,
+    rasterization_options: RasterizationOptions,
+) -> Result<(), GlyphLoadingError>
+where
+    L: Loader,
+{
+    let visible = match required.intersection(RectI::new(Vector2I::default(), canvas.size)) {
+        Some(visible) => visible,
+        None => return Ok(()),
+    };
+
+    // Leave a pixel of slack on each side so that rounding after the translation can't make the
+    // glyph overflow the temporary canvas.
+    let temp_origin = required.origin() - Vector2I::splat(1);
+    let mut temp_canvas = Canvas::new(required.size() + Vector2I::splat(2), canvas.format);
+    loader.rasterize_glyph(
+        &mut temp_canvas,
+        glyph_id,
+        point_size,
+        Transform2F::from_translation(-temp_origin.to_f32()) * transform,
+        hinting_options,
+        rasterization_options,
+    )?;
+    let visible_pixels =
+        temp_canvas.crop(RectI::new(visible.origin() - temp_origin, visible.size()));
+    canvas.blit_from(
+        visible.origin(),
+        &visible_pixels.pixels,
+        visible_pixels.size,
+        visible_pixels.stride,
+        visible_pixels.format,
+    );
+    Ok(())
+}
+
+// Returns true if `bounds` lies entirely inside `canvas`. Empty bounds always fit.
+pub(crate) fn fits_in_canvas(canvas: &Canvas, bounds: RectI) -> bool {
+    bounds.width() <= 0
+        || bounds.height() <= 0
+        || (bounds.min_x() >= 0
+            && bounds.min_y() >= 0
+            && bounds.max_x() <= canvas.size.x()
+            && bounds.max_y() <= canvas.size.y())
+}
+
+// Returns a glyph's data in the `glyf` table, reading only its `loca` entries and the glyph itself.
+fn glyf_glyph<L>(loader: &L, glyph_id: u32) -> Option<Box<[u8]>>
+where
+    L: Loader,
+{
+    let head = loader.font_table_range(tables::TAG_HEAD, 50..52)?;
+    let index_to_loc_format = tables::read_i16(&head, 0)?;
+    let loca_entries = loader.font_table_range(
+        tables::TAG_LOCA,
+        tables::loca_entries_range(index_to_loc_format, glyph_id),
+    )?;
+    let glyph_range = tables::glyf_range(index_to_loc_format, &loca_entries)?;
+    if glyph_range.is_empty() {
+        return Some(Box::new([]));
+    }
+    loader.font_table_range(tables::TAG_GLYF, glyph_range)
+}
+
+// Counts the points of an outline for `Loader::glyph_complexity()`.
+#[derive(Default)]
+struct GlyphStatsSink {
+    stats: GlyphStats,
+    contour_start: Vector2F,
+}
+
+impl GlyphStatsSink {
+    fn add_points(&mut self, on_curve_point_count: u32, off_curve_point_count: u32) {
+        self.stats.on_curve_point_count += on_curve_point_count;
+        self.stats.off_curve_point_count += off_curve_point_count;
+        self.stats.point_count += on_curve_point_count + off_curve_point_count;
+    }
+
+    fn add_segment_end(&mut self, to: Vector2F, off_curve_point_count: u32) {
+        // A segment that returns to the start of the contour closes it rather than adding a point.
+        let on_curve_point_count = if to == self.contour_start { 0 } else { 1 };
+        self.add_points(on_curve_point_count, off_curve_point_count);
+    }
+}
+
+impl OutlineSink for GlyphStatsSink {
+    fn move_to(&mut self, to: Vector2F) {
+        self.stats.contour_count += 1;
+        self.add_points(1, 0);
+        self.contour_start = to;
+    }
+
+    fn line_to(&mut self, to: Vector2F) {
+        self.add_segment_end(to, 0);
+    }
+
+    fn quadratic_curve_to(&mut self, _: Vector2F, to: Vector2F) {
+        self.add_segment_end(to, 1);
+    }
+
+    fn cubic_curve_to(&mut self, _: LineSegment2F, to: Vector2F) {
+        self.add_segment_end(to, 2);
+    }
+
+    fn close(&mut self) {}
+}
@@ -11,6 +11,8 @@
 //! A loader that uses Apple's Core Text API to load and rasterize fonts.
 
 use byteorder::{BigEndian, ReadBytesExt};
+use core_foundation::base::TCFType;
+use core_foundation::string::{CFString, CFStringRef};
 use core_graphics::base::{kCGImageAlphaPremultipliedLast, CGFloat};
 use core_graphics::color_space::CGColorSpace;
 use core_graphics::context::{CGContext, CGTextDrawingMode};
@@ -19,7 +21,7 @@ use core_graphics::geometry::{CGAffineTransform, CGPoint, CGRect, CGSize};
 use core_graphics::geometry::{CG_AFFINE_TRANSFORM_IDENTITY, CG_ZERO_POINT, CG_ZERO_SIZE};
 use core_graphics::path::CGPathElementType;
 use core_text;
-use core_text::font::CTFont;
+use core_text::font::{CTFont, CTFontNameSpecifier, CTFontRef};
 use core_text::font_descriptor::kCTFontDefaultOrientation;
 use core_text::font_descriptor::{SymbolicTraitAccessors, TraitAccessors};
 use log::warn;
@@ -32,20 +34,23 @@ use std::cmp::Ordering;
 use std::f32;
 use std::fmt::{self, Debug, Formatter};
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::{Seek, SeekFrom};
 use std::ops::Deref;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::ptr;
 use std::sync::Arc;
 
 use crate::canvas::{Canvas, Format, RasterizationOptions};
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
-use crate::handle::Handle;
+use crate::handle::{self, Handle};
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackResult, Loader};
+use crate::loader::{self, FallbackResult, Loader};
 use crate::metrics::Metrics;
 use crate::outline::OutlineSink;
 use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::tables;
 use crate::utils;
 
 const TTC_TAG: [u8; 4] = [b't', b't', b'c', b'f'];
@@ -126,6 +131,11 @@ impl Font {
         Font::from_core_text_font(core_text_font)
     }
 
+    // Returns the path of the file that the Core Text font was loaded from, if it has one.
+    fn font_path(&self) -> Option<PathBuf> {
+        self.core_text_font.url().and_then(|url| url.to_path())
+    }
+
     unsafe fn from_core_text_font(core_text_font: NativeFont) -> Font {
         let mut font_data = FontData::Unavailable;
         match core_text_font.url() {
@@ -219,6 +229,30 @@ impl Font {
         self.core_text_font.family_name()
     }
 
+    /// Returns the names of the font family in every language the font provides, starting with
+    /// `family_name()`.
+    ///
+    /// The family name in the user's preferred language, according to Core Text, comes next,
+    /// followed by the other family names in the font's `name` table.
+    pub fn localized_family_names(&self) -> Vec<String> {
+        let mut family_names = vec![self.family_name()];
+        unsafe {
+            let localized_name = CTFontCopyLocalizedName(
+                self.core_text_font.as_concrete_TypeRef(),
+                CTFontNameSpecifier::Family.into(),
+                ptr::null_mut(),
+            );
+            if !localized_name.is_null() {
+                let localized_name = CFString::wrap_under_create_rule(localized_name).to_string();
+                if !family_names.contains(&localized_name) {
+                    family_names.push(localized_name);
+                }
+            }
+        }
+        loader::push_name_table_family_names(self, &mut family_names);
+        family_names
+    }
+
     /// Returns the name of the font style, according to Core Text.
     ///
     /// NB: This function is only available on the Core Text backend.
@@ -241,7 +275,7 @@ impl Font {
         let style = if symbolic_traits.is_italic() {
             Style::Italic
         } else if all_traits.normalized_slant() > 0.0 {
-            Style::Oblique
+            Style::oblique_from_italic_angle(self.italic_angle())
         } else {
             Style::Normal
         };
@@ -270,10 +304,15 @@ impl Font {
     /// use cases like "what does character X look like on its own".
     pub fn glyph_for_char(&self, character: char) -> Option<u32> {
         unsafe {
+            // Characters beyond the Basic Multilingual Plane take a surrogate pair, for which Core
+            // Text returns the glyph in the first slot.
             let (mut dest, mut src) = ([0, 0], [0, 0]);
             let src = character.encode_utf16(&mut src);
-            self.core_text_font
-                .get_glyphs_for_characters(src.as_ptr(), dest.as_mut_ptr(), 2);
+            self.core_text_font.get_glyphs_for_characters(
+                src.as_ptr(),
+                dest.as_mut_ptr(),
+                src.len() as isize,
+            );
 
             let id = dest[0] as u32;
             if id != 0 {
@@ -285,11 +324,16 @@ impl Font {
     }
 
     /// Returns the glyph ID for the specified glyph name.
+    ///
+    /// `.notdef`, `.null`, and `nonmarkingreturn` also resolve by convention, as
+    /// `Loader::glyph_by_name()` describes.
     #[inline]
     pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
-        let code = self.core_text_font.get_glyph_with_name(name);
-
-        Some(u32::from(code))
+        // Core Text returns glyph 0 for names it doesn't know.
+        match self.core_text_font.get_glyph_with_name(name) {
+            0 => loader::conventional_glyph_by_name(self, name),
+            code => Some(u32::from(code)),
+        }
     }
 
     /// Sends the vector path for a glyph to a path builder.
@@ -405,6 +449,15 @@ impl Font {
         );
         let bounding_box = bounding_box * units_per_point as f32;
 
+        let [caret_slope_rise, caret_slope_run, caret_offset] = self
+            .load_font_table(tables::TAG_HHEA)
+            .and_then(|hhea| tables::hhea_caret(&hhea))
+            .unwrap_or([1, 0, 0]);
+        let [typo_line_gap, win_ascent, win_descent] = self
+            .load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_leading_metrics(&os2))
+            .unwrap_or([0.0; 3]);
+
         Metrics {
             units_per_em,
             ascent: (self.core_text_font.ascent() * units_per_point) as f32,
@@ -416,6 +469,12 @@ impl Font {
             cap_height: (self.core_text_font.cap_height() * units_per_point) as f32,
             x_height: (self.core_text_font.x_height() * units_per_point) as f32,
             bounding_box,
+            caret_slope_rise: caret_slope_rise as f32,
+            caret_slope_run: caret_slope_run as f32,
+            caret_offset: caret_offset as f32,
+            typo_line_gap,
+            win_ascent,
+            win_descent,
         }
     }
 
@@ -469,9 +528,39 @@ impl Font {
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
     ///
+    /// If the glyph's raster bounds don't fit inside the canvas, this returns
+    /// `GlyphLoadingError::CanvasTooSmall`. Use `rasterize_glyph_clipped()` to draw the visible
+    /// part of the glyph instead.
+    #[inline]
+    pub fn rasterize_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        loader::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas with the given size and origin, clipping it to the bounds of
+    /// the canvas.
+    ///
+    /// This behaves like `rasterize_glyph()`, except that parts of the glyph that fall outside
+    /// the canvas are silently discarded.
+    ///
     /// TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
     /// implementation.
-    pub fn rasterize_glyph(
+    pub fn rasterize_glyph_clipped(
         &self,
         canvas: &mut Canvas,
         glyph_id: u32,
@@ -493,7 +582,7 @@ impl Font {
                     // FIXME(pcwalton): Could improve this by only allocating a canvas with a tight
                     // bounding rect and blitting only that part.
                     let mut temp_canvas = Canvas::new(canvas.size, Format::Rgba32);
-                    self.rasterize_glyph(
+                    self.rasterize_glyph_clipped(
                         &mut temp_canvas,
                         glyph_id,
                         point_size,
@@ -662,6 +751,11 @@ impl Loader for Font {
         self.family_name()
     }
 
+    #[inline]
+    fn localized_family_names(&self) -> Vec<String> {
+        self.localized_family_names()
+    }
+
     #[inline]
     fn is_monospace(&self) -> bool {
         self.is_monospace()
@@ -754,6 +848,26 @@ impl Loader for Font {
         )
     }
 
+    #[inline]
+    fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_clipped(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
     #[inline]
     fn get_fallbacks(&self, text: &str, locale: &str) -> FallbackResult<Self> {
         self.get_fallbacks(text, locale)
@@ -771,6 +885,46 @@ impl Debug for Font {
     }
 }
 
+/// Fonts compare equal if they were loaded from identical font data and have the same PostScript
+/// name. (Collections are unpacked on load, so the PostScript name stands in for the face index.)
+///
+/// This compares the source of the font, not its rendered output. In particular, two independently
+/// loaded copies of the same file compare equal. Font data is compared in full, but only once its
+/// length and start, which holds the table directory, match. Fonts created with
+/// `from_native_font()` whose data couldn't be read compare their file paths instead or, without
+/// those, the Core Text fonts themselves.
+impl PartialEq for Font {
+    fn eq(&self, other: &Font) -> bool {
+        self.postscript_name() == other.postscript_name()
+            && match (&self.font_data, &other.font_data) {
+                (FontData::Memory(data), FontData::Memory(other_data)) => {
+                    handle::font_data_matches(data, other_data)
+                }
+                (FontData::Unavailable, FontData::Unavailable) => {
+                    match (self.font_path(), other.font_path()) {
+                        (Some(path), Some(other_path)) => path == other_path,
+                        _ => self.core_text_font == other.core_text_font,
+                    }
+                }
+                _ => false,
+            }
+    }
+}
+
+impl Eq for Font {}
+
+impl Hash for Font {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        if let FontData::Memory(ref data) = self.font_data {
+            handle::hash_font_data(state, data, 0);
+        }
+        self.postscript_name().hash(state);
+    }
+}
+
 #[derive(Clone)]
 enum FontData {
     Unavailable,
@@ -798,13 +952,13 @@ impl CGPointExt for CGPoint {
     }
 }
 
-fn core_text_to_css_font_weight(core_text_weight: f32) -> Weight {
+pub(crate) fn core_text_to_css_font_weight(core_text_weight: f32) -> Weight {
     let index = piecewise_linear_find_index(core_text_weight, &FONT_WEIGHT_MAPPING);
 
     Weight(index * 100.0 + 100.0)
 }
 
-fn core_text_width_to_css_stretchiness(core_text_width: f32) -> Stretch {
+pub(crate) fn core_text_width_to_css_stretchiness(core_text_width: f32) -> Stretch {
     Stretch(piecewise_linear_lookup(
         (core_text_width + 1.0) * 4.0,
         &Stretch::MAPPING,
@@ -922,6 +1076,15 @@ fn unpack_data_fork_font(data: &mut [u8]) -> Result<(), FontLoadingError> {
     Ok(())
 }
 
+#[link(name = "CoreText", kind = "framework")]
+extern "C" {
+    fn CTFontCopyLocalizedName(
+        font: CTFontRef,
+        name_key: CFStringRef,
+        actual_language: *mut CFStringRef,
+    ) -> CFStringRef;
+}
+
 #[cfg(test)]
 mod test {
     use super::Font;
@@ -34,12 +34,17 @@ use std::borrow::Cow;
 use std::ffi::OsString;
 use std::fmt::{self, Debug, Formatter};
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Read, Seek, SeekFrom};
 use std::os::windows::ffi::OsStringExt;
 use std::os::windows::io::AsRawHandle;
 use std::path::{Path, PathBuf};
+use std::ptr;
 use std::sync::{Arc, Mutex};
-use winapi::shared::minwindef::{FALSE, MAX_PATH};
+use winapi::shared::minwindef::{FALSE, MAX_PATH, TRUE};
+use winapi::um::dwrite::IDWriteLocalizedStrings;
+use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_PREFERRED_FAMILY_NAMES;
+use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES;
 use winapi::um::dwrite::DWRITE_NUMBER_SUBSTITUTION_METHOD_NONE;
 use winapi::um::dwrite::DWRITE_READING_DIRECTION;
 use winapi::um::dwrite::DWRITE_READING_DIRECTION_LEFT_TO_RIGHT;
@@ -48,12 +53,13 @@ use winapi::um::fileapi;
 use crate::canvas::{Canvas, Format, RasterizationOptions};
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
-use crate::handle::Handle;
+use crate::handle::{self, Handle};
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackFont, FallbackResult, Loader};
+use crate::loader::{self, FallbackFont, FallbackResult, Loader};
 use crate::metrics::Metrics;
 use crate::outline::{OutlineBuilder, OutlineSink};
 use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::tables;
 
 const ERROR_BOUND: f32 = 0.0001;
 
@@ -231,6 +237,38 @@ impl Font {
         self.dwrite_font.family_name()
     }
 
+    /// Returns the names of the font family in every language the font provides, starting with
+    /// `family_name()`.
+    ///
+    /// The names come from DirectWrite's family names, then its typographic and Win32 family
+    /// names, in every locale.
+    pub fn localized_family_names(&self) -> Vec<String> {
+        let mut family_names = vec![self.family_name()];
+        unsafe {
+            let dwrite_font = &*self.dwrite_font.as_ptr();
+            let mut family = ptr::null_mut();
+            if dwrite_font.GetFontFamily(&mut family) == 0 {
+                let mut names = ptr::null_mut();
+                if (*family).GetFamilyNames(&mut names) == 0 {
+                    push_localized_strings(names, &mut family_names);
+                }
+                (*family).Release();
+            }
+            for id in [
+                DWRITE_INFORMATIONAL_STRING_PREFERRED_FAMILY_NAMES,
+                DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES,
+            ] {
+                let (mut names, mut exists) = (ptr::null_mut(), FALSE);
+                if dwrite_font.GetInformationalStrings(id, &mut names, &mut exists) == 0
+                    && exists == TRUE
+                {
+                    push_localized_strings(names, &mut family_names);
+                }
+            }
+        }
+        family_names
+    }
+
     /// Returns true if and only if the font is monospace (fixed-width).
     #[inline]
     pub fn is_monospace(&self) -> bool {
@@ -241,7 +279,10 @@ impl Font {
     pub fn properties(&self) -> Properties {
         let dwrite_font = &self.dwrite_font;
         Properties {
-            style: style_for_dwrite_style(dwrite_font.style()),
+            style: match style_for_dwrite_style(dwrite_font.style()) {
+                Style::Oblique(_) => Style::oblique_from_italic_angle(self.italic_angle()),
+                style => style,
+            },
             stretch: Stretch(Stretch::MAPPING[(dwrite_font.stretch() as usize) - 1]),
             weight: Weight(dwrite_font.weight().to_u32() as f32),
         }
@@ -363,6 +404,14 @@ impl Font {
     /// Retrieves various metrics that apply to the entire font.
     pub fn metrics(&self) -> Metrics {
         let dwrite_font = &self.dwrite_font;
+        let [caret_slope_rise, caret_slope_run, caret_offset] = self
+            .load_font_table(tables::TAG_HHEA)
+            .and_then(|hhea| tables::hhea_caret(&hhea))
+            .unwrap_or([1, 0, 0]);
+        let [typo_line_gap, win_ascent, win_descent] = self
+            .load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_leading_metrics(&os2))
+            .unwrap_or([0.0; 3]);
 
         // Unfortunately, the bounding box info is Windows 8 only, so we need a fallback. First,
         // try to grab it from the font. If that fails, we try the `head` table. If there's no
@@ -385,6 +434,12 @@ impl Font {
                     ),
                 )
                 .to_f32(),
+                caret_slope_rise: caret_slope_rise as f32,
+                caret_slope_run: caret_slope_run as f32,
+                caret_offset: caret_offset as f32,
+                typo_line_gap,
+                win_ascent,
+                win_descent,
             },
             DWriteFontMetrics::Metrics0(metrics) => {
                 let bounding_box = match self
@@ -415,6 +470,12 @@ impl Font {
                     underline_position: metrics.underlinePosition as f32,
                     underline_thickness: metrics.underlineThickness as f32,
                     bounding_box,
+                    caret_slope_rise: caret_slope_rise as f32,
+                    caret_slope_run: caret_slope_run as f32,
+                    caret_offset: caret_offset as f32,
+                    typo_line_gap,
+                    win_ascent,
+                    win_descent,
                 }
             }
         }
@@ -489,6 +550,11 @@ impl Font {
     /// loader.
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
+    ///
+    /// If the glyph's raster bounds don't fit inside the canvas, this returns
+    /// `GlyphLoadingError::CanvasTooSmall`. Use `rasterize_glyph_clipped()` to draw the visible
+    /// part of the glyph instead.
+    #[inline]
     pub fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -497,6 +563,31 @@ impl Font {
         transform: Transform2F,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        loader::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas with the given size and origin, clipping it to the bounds of
+    /// the canvas.
+    ///
+    /// This behaves like `rasterize_glyph()`, except that parts of the glyph that fall outside
+    /// the canvas are silently discarded.
+    pub fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
     ) -> Result<(), GlyphLoadingError> {
         // TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
         // implementation.
@@ -726,6 +817,40 @@ impl Debug for Font {
     }
 }
 
+/// Fonts compare equal if they were loaded from identical font data with the same face index.
+///
+/// This compares the source of the font, not its rendered output. In particular, two
+/// independently loaded copies of the same file compare equal. Font data is compared in full, but
+/// only once its length and start, which holds the table directory, match; it's read from the font
+/// file once and then kept. Fonts whose data can't be read are
+/// only equal to fonts that share their DirectWrite font face.
+impl PartialEq for Font {
+    fn eq(&self, other: &Font) -> bool {
+        if unsafe { self.dwrite_font_face.as_ptr() == other.dwrite_font_face.as_ptr() } {
+            return true;
+        }
+        self.dwrite_font_face.get_index() == other.dwrite_font_face.get_index()
+            && match (self.copy_font_data(), other.copy_font_data()) {
+                (Some(data), Some(other_data)) => handle::font_data_matches(&data, &other_data),
+                _ => false,
+            }
+    }
+}
+
+impl Eq for Font {}
+
+impl Hash for Font {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        if let Some(data) = self.copy_font_data() {
+            handle::hash_font_data(state, &data, 0);
+        }
+        self.dwrite_font_face.get_index().hash(state);
+    }
+}
+
 impl Loader for Font {
     type NativeFont = NativeFont;
 
@@ -781,6 +906,11 @@ impl Loader for Font {
         self.family_name()
     }
 
+    #[inline]
+    fn localized_family_names(&self) -> Vec<String> {
+        self.localized_family_names()
+    }
+
     #[inline]
     fn is_monospace(&self) -> bool {
         self.is_monospace()
@@ -868,6 +998,26 @@ impl Loader for Font {
         )
     }
 
+    #[inline]
+    fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_clipped(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
     #[inline]
     fn get_fallbacks(&self, text: &str, locale: &str) -> FallbackResult<Self> {
         self.get_fallbacks(text, locale)
@@ -953,10 +1103,31 @@ impl DWriteOutlineBuilder for OutlineCanonicalizer {
     }
 }
 
-fn style_for_dwrite_style(style: DWriteFontStyle) -> Style {
+pub(crate) fn style_for_dwrite_style(style: DWriteFontStyle) -> Style {
     match style {
         DWriteFontStyle::Normal => Style::Normal,
-        DWriteFontStyle::Oblique => Style::Oblique,
+        DWriteFontStyle::Oblique => Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE),
         DWriteFontStyle::Italic => Style::Italic,
     }
 }
+
+// Appends the strings in every locale of `strings` that aren't in `names` already, then releases
+// `strings`.
+unsafe fn push_localized_strings(strings: *mut IDWriteLocalizedStrings, names: &mut Vec<String>) {
+    for index in 0..(*strings).GetCount() {
+        let mut length = 0;
+        if (*strings).GetStringLength(index, &mut length) != 0 {
+            continue;
+        }
+        let mut buffer = vec![0; length as usize + 1];
+        if (*strings).GetString(index, buffer.as_mut_ptr(), length + 1) != 0 {
+            continue;
+        }
+        if let Ok(name) = String::from_utf16(&buffer[..(length as usize)]) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    (*strings).Release();
+}
@@ -15,15 +15,21 @@
 
 use byteorder::{BigEndian, ReadBytesExt};
 use freetype_sys::{
-    ft_sfnt_os2, FT_Byte, FT_Done_Face, FT_Done_FreeType, FT_Error, FT_Face, FT_Fixed,
-    FT_Get_Char_Index, FT_Get_Name_Index, FT_Get_Postscript_Name, FT_Get_Sfnt_Name,
-    FT_Get_Sfnt_Name_Count, FT_Get_Sfnt_Table, FT_Init_FreeType, FT_Library,
-    FT_Library_SetLcdFilter, FT_Load_Glyph, FT_Long, FT_Matrix, FT_New_Memory_Face, FT_Pos,
-    FT_Reference_Face, FT_Set_Char_Size, FT_Set_Transform, FT_UInt, FT_ULong, FT_Vector,
-    FT_FACE_FLAG_FIXED_WIDTH, FT_LCD_FILTER_DEFAULT, FT_LOAD_DEFAULT, FT_LOAD_MONOCHROME,
-    FT_LOAD_NO_HINTING, FT_LOAD_RENDER, FT_LOAD_TARGET_LCD, FT_LOAD_TARGET_LIGHT,
-    FT_LOAD_TARGET_MONO, FT_LOAD_TARGET_NORMAL, FT_PIXEL_MODE_GRAY, FT_PIXEL_MODE_LCD,
-    FT_PIXEL_MODE_LCD_V, FT_PIXEL_MODE_MONO, FT_STYLE_FLAG_ITALIC, TT_OS2,
+    ft_sfnt_os2, FT_Bool, FT_Byte, FT_Done_Face, FT_Done_FreeType, FT_Done_MM_Var,
+    FT_Err_Unknown_File_Format, FT_Error, FT_Face, FT_Face_GetCharVariantIndex, FT_Fixed,
+    FT_Get_Char_Index, FT_Get_Glyph_Name, FT_Get_MM_Var, FT_Get_Multi_Master, FT_Get_Name_Index,
+    FT_Get_Postscript_Name, FT_Get_Sfnt_Name, FT_Get_Sfnt_Name_Count, FT_Get_Sfnt_Table,
+    FT_Get_Var_Design_Coordinates, FT_Init_FreeType, FT_Int, FT_LcdFilter, FT_Library,
+    FT_Library_SetLcdFilter, FT_Load_Glyph, FT_Long, FT_Matrix, FT_Multi_Master,
+    FT_New_Memory_Face, FT_Pos, FT_Property_Get, FT_Property_Set, FT_Reference_Face,
+    FT_Select_Size, FT_Set_Char_Size, FT_Set_Charmap, FT_Set_Transform,
+    FT_Set_Var_Design_Coordinates, FT_Stream_IoFunc, FT_UInt, FT_ULong, FT_Vector,
+    FT_FACE_FLAG_FIXED_WIDTH, FT_FACE_FLAG_GLYPH_NAMES, FT_FACE_FLAG_SCALABLE, FT_FACE_FLAG_SFNT,
+    FT_LCD_FILTER_DEFAULT, FT_LCD_FILTER_LEGACY, FT_LCD_FILTER_NONE, FT_LOAD_COLOR,
+    FT_LOAD_DEFAULT, FT_LOAD_FORCE_AUTOHINT, FT_LOAD_MONOCHROME, FT_LOAD_NO_AUTOHINT,
+    FT_LOAD_NO_HINTING, FT_LOAD_NO_SCALE, FT_LOAD_RENDER, FT_LOAD_TARGET_LCD, FT_LOAD_TARGET_LIGHT,
+    FT_LOAD_TARGET_MONO, FT_LOAD_TARGET_NORMAL, FT_PIXEL_MODE_BGRA, FT_PIXEL_MODE_GRAY,
+    FT_PIXEL_MODE_LCD, FT_PIXEL_MODE_LCD_V, FT_PIXEL_MODE_MONO, FT_STYLE_FLAG_ITALIC, TT_OS2,
 };
 use log::warn;
 use pathfinder_geometry::line_segment::LineSegment2F;
@@ -31,26 +37,31 @@ use pathfinder_geometry::rect::{RectF, RectI};
 use pathfinder_geometry::transform2d::Transform2F;
 use pathfinder_geometry::vector::{Vector2F, Vector2I};
 use pathfinder_simd::default::F32x4;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::f32;
 use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::io::{Seek, SeekFrom};
-use std::iter;
 use std::mem;
+use std::ops::Range;
 use std::os::raw::{c_char, c_void};
 use std::ptr;
+use std::rc::Rc;
 use std::slice;
 use std::sync::Arc;
 
-use crate::canvas::{Canvas, Format, RasterizationOptions};
+use crate::canvas::{Canvas, Format, LcdFilter, RasterizationOptions, RasterizationSettings};
 use crate::error::{FontLoadingError, GlyphLoadingError};
-use crate::file_type::FileType;
-use crate::handle::Handle;
+use crate::file_type::{self, FileType};
+use crate::handle::{self, Handle};
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackResult, Loader};
+use crate::loader::{self, FallbackResult, Loader, VariationAxis};
 use crate::metrics::Metrics;
-use crate::outline::OutlineSink;
+use crate::outline::{self, Outline, OutlineBuilder, OutlineSink};
 use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::tables;
 use crate::utils;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -62,12 +73,23 @@ const PS_DICT_FULL_NAME: u32 = 38;
 const TT_NAME_ID_FULL_NAME: u16 = 4;
 
 const TT_PLATFORM_APPLE_UNICODE: u16 = 0;
+const TT_PLATFORM_MICROSOFT: u16 = 3;
+const TT_MS_ID_SYMBOL_CS: u16 = 0;
+
+// Symbol fonts map their characters at this offset in the Private Use Area.
+const SYMBOL_CHARMAP_BASE: u32 = 0xf000;
+
+// VARIATION SELECTOR-16, which requests the emoji presentation of the preceding character.
+const EMOJI_PRESENTATION_SELECTOR: u32 = 0xfe0f;
 
 const FT_POINT_TAG_ON_CURVE: c_char = 0x01;
 const FT_POINT_TAG_CUBIC_CONTROL: c_char = 0x02;
 
 const OS2_FS_SELECTION_OBLIQUE: u16 = 1 << 9;
 
+// Our FreeType bindings give this the value of `FT_LCD_FILTER_LEGACY1`.
+const FT_LCD_FILTER_LIGHT: FT_LcdFilter = 2;
+
 // Not in our FreeType bindings, so we define these ourselves.
 #[allow(dead_code)]
 const BDF_PROPERTY_TYPE_NONE: BDF_PropertyType = 0;
@@ -105,6 +127,20 @@ impl Drop for FtLibrary {
 /// The handle that the FreeType API natively uses to represent a font.
 pub type NativeFont = FT_Face;
 
+/// Which FreeType hinter grid-fits glyphs, as set with `Font::set_hinting_engine()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HintingEngine {
+    /// Let FreeType choose: the font's own bytecode instructions if it has them and the hinting
+    /// mode is full, and the autohinter otherwise.
+    #[default]
+    Default,
+    /// Always use the font's bytecode instructions, and never the autohinter. Glyphs without
+    /// instructions are left unhinted.
+    Bytecode,
+    /// Always use the autohinter, ignoring the font's instructions.
+    Autohinter,
+}
+
 // Not in our FreeType bindings, so we define this ourselves.
 #[allow(non_camel_case_types)]
 type BDF_PropertyType = i32;
@@ -124,6 +160,17 @@ struct BDF_PropertyRec {
 pub struct Font {
     freetype_face: FT_Face,
     font_data: Arc<Vec<u8>>,
+    // Decoded outlines, keyed by glyph ID, hinting mode, and hinting engine. They depend on the
+    // variation coordinates, which clones share along with the face, so clones share this too.
+    outline_cache: Rc<RefCell<OutlineCache>>,
+    // The font-wide metrics, computed on first use. Shared with clones, like the outlines.
+    metrics_cache: Rc<Cell<Option<Metrics>>>,
+    // Tables no longer than `TABLE_CACHE_MAX_LEN`, and the absence of missing ones, keyed by tag.
+    table_cache: RefCell<HashMap<u32, Option<Box<[u8]>>>>,
+    // Recent `glyph_for_char()` results. Clones share the face, and with it the charmap, so they
+    // share this too.
+    glyph_cache: Rc<GlyphCache>,
+    hinting_engine: HintingEngine,
 }
 
 impl Font {
@@ -134,15 +181,15 @@ impl Font {
     pub fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Font, FontLoadingError> {
         FREETYPE_LIBRARY.with(|freetype_library| unsafe {
             let mut freetype_face = ptr::null_mut();
-            if FT_New_Memory_Face(
+            let error = FT_New_Memory_Face(
                 freetype_library.0,
                 (*font_data).as_ptr(),
                 font_data.len() as FT_Long,
                 font_index as FT_Long,
                 &mut freetype_face,
-            ) != 0
-            {
-                return Err(FontLoadingError::Parse);
+            );
+            if error != 0 {
+                return Err(face_loading_error(&font_data, error));
             }
 
             setup_freetype_face(freetype_face);
@@ -150,6 +197,11 @@ impl Font {
             Ok(Font {
                 freetype_face,
                 font_data,
+                outline_cache: Rc::new(RefCell::new(OutlineCache::new())),
+                metrics_cache: Rc::new(Cell::new(None)),
+                table_cache: RefCell::new(HashMap::new()),
+                glyph_cache: Rc::new(GlyphCache::new()),
+                hinting_engine: HintingEngine::Default,
             })
         })
     }
@@ -180,26 +232,39 @@ impl Font {
     }
 
     /// Creates a font from a native API handle.
+    ///
+    /// We make an in-memory copy of the underlying font data, because the native font does not
+    /// necessarily hold a strong reference to the memory backing it. If the face's stream can't be
+    /// read, an SFNT font is reassembled from the face's tables instead. Failing that, the new
+    /// font shares the native face and `copy_font_data()` returns `None`.
     pub unsafe fn from_native_font(freetype_face: NativeFont) -> Font {
-        // We make an in-memory copy of the underlying font data. This is because the native font
-        // does not necessarily hold a strong reference to the memory backing it.
-        const CHUNK_SIZE: usize = 4096;
-        let mut font_data = vec![];
-        loop {
-            font_data.extend(iter::repeat(0).take(CHUNK_SIZE));
-            let freetype_stream = (*freetype_face).stream;
-            let n_read = ((*freetype_stream).read)(
-                freetype_stream,
-                font_data.len() as FT_ULong,
-                font_data.as_mut_ptr(),
-                CHUNK_SIZE as FT_ULong,
-            );
-            if n_read < CHUNK_SIZE as FT_ULong {
-                break;
+        let font_index = (*freetype_face).face_index as u32;
+        if let Some(font_data) = copy_stream_data(freetype_face) {
+            if let Ok(font) = Font::from_bytes(Arc::new(font_data), font_index) {
+                return font;
+            }
+        }
+        if let Some(font_data) = reassemble_sfnt(freetype_face) {
+            if let Ok(font) = Font::from_bytes(Arc::new(font_data), 0) {
+                return font;
             }
         }
 
-        Font::from_bytes(Arc::new(font_data), (*freetype_face).face_index as u32).unwrap()
+        Font::from_shared_face(freetype_face)
+    }
+
+    // Creates a font without data that shares a native face, taking a reference to it.
+    unsafe fn from_shared_face(freetype_face: NativeFont) -> Font {
+        assert_eq!(FT_Reference_Face(freetype_face), 0);
+        Font {
+            freetype_face,
+            font_data: Arc::new(vec![]),
+            outline_cache: Rc::new(RefCell::new(OutlineCache::new())),
+            metrics_cache: Rc::new(Cell::new(None)),
+            table_cache: RefCell::new(HashMap::new()),
+            glyph_cache: Rc::new(GlyphCache::new()),
+            hinting_engine: HintingEngine::Default,
+        }
     }
 
     /// Loads the font pointed to by a handle.
@@ -213,21 +278,18 @@ impl Font {
     pub fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
         FREETYPE_LIBRARY.with(|freetype_library| unsafe {
             let mut freetype_face = ptr::null_mut();
-            if FT_New_Memory_Face(
+            let error = FT_New_Memory_Face(
                 freetype_library.0,
                 (*font_data).as_ptr(),
                 font_data.len() as FT_Long,
                 0,
                 &mut freetype_face,
-            ) != 0
-            {
-                return Err(FontLoadingError::Parse);
+            );
+            if error != 0 {
+                return Err(face_loading_error(&font_data, error));
             }
 
-            let font_type = match (*freetype_face).num_faces {
-                1 => FileType::Single,
-                num_faces => FileType::Collection(num_faces as u32),
-            };
+            let font_type = file_type_of_face(&font_data, freetype_face);
             FT_Done_Face(freetype_face);
             Ok(font_type)
         })
@@ -241,21 +303,18 @@ impl Font {
             let font_data = Arc::new(utils::slurp_file(file).map_err(FontLoadingError::Io)?);
 
             let mut freetype_face = ptr::null_mut();
-            if FT_New_Memory_Face(
+            let error = FT_New_Memory_Face(
                 freetype_library.0,
                 (*font_data).as_ptr(),
                 font_data.len() as FT_Long,
                 0,
                 &mut freetype_face,
-            ) != 0
-            {
-                return Err(FontLoadingError::Parse);
+            );
+            if error != 0 {
+                return Err(face_loading_error(&font_data, error));
             }
 
-            let font_type = match (*freetype_face).num_faces {
-                1 => FileType::Single,
-                num_faces => FileType::Collection(num_faces as u32),
-            };
+            let font_type = file_type_of_face(&font_data, freetype_face);
             FT_Done_Face(freetype_face);
             Ok(font_type)
         })
@@ -347,7 +406,7 @@ impl Font {
             let os2_table = self.get_os2_table();
             let style = match os2_table {
                 Some(os2_table) if ((*os2_table).fsSelection & OS2_FS_SELECTION_OBLIQUE) != 0 => {
-                    Style::Oblique
+                    Style::oblique_from_italic_angle(self.italic_angle())
                 }
                 _ if ((*self.freetype_face).style_flags & (FT_STYLE_FLAG_ITALIC) as FT_Long)
                     != 0 =>
@@ -366,12 +425,157 @@ impl Font {
                 None => Weight::NORMAL,
                 Some(os2_table) => Weight((*os2_table).usWeightClass as f32),
             };
-            Properties {
+            let mut properties = Properties {
                 style,
                 stretch,
                 weight,
+            };
+
+            // The `OS/2` table describes the default instance of a variable font, so prefer the
+            // current axis coordinates.
+            loader::apply_variation_coordinates(&mut properties, &self.variation_coordinates());
+            properties
+        }
+    }
+
+    /// Returns the axes along which a variable font can vary.
+    ///
+    /// These come from the `fvar` table if there is one. Otherwise, legacy Type 1 Multiple Master
+    /// fonts report the axes of their design space, in the font's own design units. FreeType names
+    /// the standard ones, such as `Weight`, with the matching OpenType tags; any others are tagged
+    /// with the first four characters of their names, padded with spaces.
+    pub fn variation_axes(&self) -> Vec<VariationAxis> {
+        match self.load_font_table(tables::TAG_FVAR) {
+            Some(fvar) => tables::fvar_axes(&fvar).unwrap_or_default(),
+            None => self.multiple_master_axes(),
+        }
+    }
+
+    // Returns the axes of a Type 1 Multiple Master font, or none for other fonts.
+    //
+    // `FT_Get_Multi_Master()` only succeeds for Multiple Master fonts, as opposed to TrueType GX
+    // and OpenType variable fonts. Their default coordinates come from the blend of the masters
+    // that the font uses unless told otherwise, which `FT_Get_MM_Var()` maps back to design units.
+    fn multiple_master_axes(&self) -> Vec<VariationAxis> {
+        unsafe {
+            let mut multi_master: FT_Multi_Master = mem::zeroed();
+            if FT_Get_Multi_Master(self.freetype_face, &mut multi_master) != 0 {
+                return vec![];
+            }
+            let mut mm_var = ptr::null_mut();
+            if FT_Get_MM_Var(self.freetype_face, &mut mm_var) != 0 {
+                return vec![];
+            }
+            let axes = slice::from_raw_parts((*mm_var).axis, (*mm_var).num_axis as usize)
+                .iter()
+                .map(|axis| {
+                    let tag = if axis.tag as u32 != !0 {
+                        axis.tag as u32
+                    } else {
+                        let mut tag = *b"    ";
+                        if !axis.name.is_null() {
+                            for (byte, &name_byte) in
+                                tag.iter_mut().zip(CStr::from_ptr(axis.name).to_bytes())
+                            {
+                                *byte = name_byte;
+                            }
+                        }
+                        u32::from_be_bytes(tag)
+                    };
+                    VariationAxis {
+                        tag,
+                        min_value: axis.minimum as f32 / 65536.0,
+                        default_value: axis.def as f32 / 65536.0,
+                        max_value: axis.maximum as f32 / 65536.0,
+                    }
+                })
+                .collect();
+            FREETYPE_LIBRARY.with(|freetype_library| FT_Done_MM_Var(freetype_library.0, mm_var));
+            axes
+        }
+    }
+
+    /// Sets the coordinates of a variable font along its axes of variation, as `(tag, value)`
+    /// pairs in each axis's own units; for example, `(VariationAxis::WEIGHT, 700.0)` for bold.
+    ///
+    /// Axes that aren't listed return to their defaults, values are clamped to each axis's range,
+    /// and tags that don't name an axis of this font are ignored. This does nothing for fonts
+    /// that aren't variable. Multiple Master fonts are varied in design units as well; FreeType
+    /// rounds their coordinates to integers and blends the masters to match.
+    ///
+    /// Clones of this font share its FreeType face, so they see the new coordinates too. Returns
+    /// `FontLoadingError::Parse` if FreeType rejects the coordinates, which happens when the
+    /// font's variation tables are malformed; the previous coordinates remain in effect.
+    pub fn set_variations(&mut self, variations: &[(u32, f32)]) -> Result<(), FontLoadingError> {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return Ok(());
+        }
+        let coordinates: Vec<FT_Fixed> = axes
+            .iter()
+            .map(|axis| {
+                let value = variations
+                    .iter()
+                    .rev()
+                    .find(|&&(tag, _)| tag == axis.tag)
+                    .map_or(axis.default_value, |&(_, value)| {
+                        value.clamp(axis.min_value, axis.max_value)
+                    });
+                (value * 65536.0).round() as FT_Fixed
+            })
+            .collect();
+        unsafe {
+            if FT_Set_Var_Design_Coordinates(
+                self.freetype_face,
+                coordinates.len() as FT_UInt,
+                coordinates.as_ptr(),
+            ) != 0
+            {
+                return Err(FontLoadingError::Parse);
+            }
+        }
+        self.outline_cache.borrow_mut().clear();
+        self.metrics_cache.set(None);
+        Ok(())
+    }
+
+    /// Returns which hinter grid-fits this font's glyphs.
+    #[inline]
+    pub fn hinting_engine(&self) -> HintingEngine {
+        self.hinting_engine
+    }
+
+    /// Chooses which hinter grid-fits this font's glyphs when hinting is requested, for outlines,
+    /// raster bounds, and rasterization alike.
+    ///
+    /// The bytecode interpreter and the autohinter can produce quite different results, so forcing
+    /// one of them helps reproduce the rendering of a particular platform. Unlike variations, this
+    /// setting isn't shared with clones made before the call.
+    pub fn set_hinting_engine(&mut self, hinting_engine: HintingEngine) {
+        self.hinting_engine = hinting_engine;
+    }
+
+    // Returns the current coordinates of a variable font, paired with their axis tags.
+    fn variation_coordinates(&self) -> Vec<(u32, f32)> {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return vec![];
+        }
+        let mut coordinates: Vec<FT_Fixed> = vec![0; axes.len()];
+        unsafe {
+            if FT_Get_Var_Design_Coordinates(
+                self.freetype_face,
+                coordinates.len() as FT_UInt,
+                coordinates.as_mut_ptr(),
+            ) != 0
+            {
+                return vec![];
             }
         }
+        axes.iter()
+            .zip(coordinates)
+            .map(|(axis, coordinate)| (axis.tag, coordinate as f32 / 65536.0))
+            .collect()
     }
 
     /// Returns the usual glyph ID for a Unicode character.
@@ -379,10 +583,58 @@ impl Font {
     /// Be careful with this function; typographically correct character-to-glyph mapping must be
     /// done using a *shaper* such as HarfBuzz. This function is only useful for best-effort simple
     /// use cases like "what does character X look like on its own".
-    #[inline]
+    ///
+    /// The character is looked up in the charmap chosen with `select_charmap()`, which is a
+    /// Unicode one by default. FreeType prefers a full-repertoire charmap, such as a format 12
+    /// `cmap` subtable, to a BMP-only one, so characters outside the Basic Multilingual Plane
+    /// resolve whenever the font maps them. If a Windows symbol charmap is selected, characters
+    /// from U+0020 to U+00FF that it doesn't map are also tried at U+F020 to U+F0FF, where symbol
+    /// fonts such as Wingdings put them.
+    ///
+    /// If the character is displayed as an emoji by default (it has the Unicode
+    /// `Emoji_Presentation` property) and the font maps it to both a text and an emoji glyph
+    /// through a format 14 `cmap` subtable, the emoji glyph, selected by U+FE0F, is preferred.
+    /// Other characters, such as U+263A WHITE SMILING FACE, get their text glyph. Emoji sequences
+    /// of more than one character, such as flags or those joined with U+200D ZERO WIDTH JOINER,
+    /// can only be resolved by shaping.
+    ///
+    /// Results for recently looked up characters are cached, which speeds up mapping repetitive
+    /// text. The cache is cleared when `select_charmap()` changes the mapping.
     pub fn glyph_for_char(&self, character: char) -> Option<u32> {
+        if let Some(glyph_id) = self.glyph_cache.get(character) {
+            return glyph_id;
+        }
+        let glyph_id = self.lookup_glyph_for_char(character);
+        self.glyph_cache.insert(character, glyph_id);
+        glyph_id
+    }
+
+    fn lookup_glyph_for_char(&self, character: char) -> Option<u32> {
         unsafe {
-            let res = FT_Get_Char_Index(self.freetype_face, character as FT_ULong);
+            if loader::has_emoji_presentation(character) {
+                let emoji_glyph = FT_Face_GetCharVariantIndex(
+                    self.freetype_face,
+                    character as FT_ULong,
+                    EMOJI_PRESENTATION_SELECTOR as FT_ULong,
+                );
+                if emoji_glyph != 0 {
+                    return Some(emoji_glyph);
+                }
+            }
+
+            let mut res = FT_Get_Char_Index(self.freetype_face, character as FT_ULong);
+            if res == 0 && (0x20..=0xff).contains(&(character as u32)) {
+                let charmap = (*self.freetype_face).charmap;
+                if !charmap.is_null()
+                    && ((*charmap).platform_id, (*charmap).encoding_id)
+                        == (TT_PLATFORM_MICROSOFT, TT_MS_ID_SYMBOL_CS)
+                {
+                    res = FT_Get_Char_Index(
+                        self.freetype_face,
+                        (SYMBOL_CHARMAP_BASE | character as u32) as FT_ULong,
+                    );
+                }
+            }
             match res {
                 0 => None,
                 _ => Some(res),
@@ -390,7 +642,55 @@ impl Font {
         }
     }
 
+    /// Returns the character maps in this font as `(platform ID, encoding ID)` pairs, in the order
+    /// the font lists them.
+    ///
+    /// For OpenType fonts these identify the `cmap` subtables; for example, `(3, 1)` is Windows
+    /// Unicode and `(3, 0)` is Windows symbol. FreeType synthesizes charmaps for other formats.
+    pub fn charmaps(&self) -> Vec<(u16, u16)> {
+        unsafe {
+            let face = self.freetype_face;
+            if (*face).charmaps.is_null() {
+                return vec![];
+            }
+            slice::from_raw_parts((*face).charmaps, (*face).num_charmaps as usize)
+                .iter()
+                .map(|&charmap| ((*charmap).platform_id, (*charmap).encoding_id))
+                .collect()
+        }
+    }
+
+    /// Selects the character map that `glyph_for_char()` uses, by platform and encoding ID as
+    /// listed by `charmaps()`.
+    ///
+    /// Legacy symbol fonts often map their glyphs only in a Windows symbol charmap, `(3, 0)`,
+    /// which has to be selected to reach them. Returns false, leaving the selection unchanged, if
+    /// the font has no such charmap.
+    ///
+    /// Clones of this font share its FreeType face, so they use the new charmap too.
+    pub fn select_charmap(&self, platform: u16, encoding: u16) -> bool {
+        unsafe {
+            let face = self.freetype_face;
+            if (*face).charmaps.is_null() {
+                return false;
+            }
+            let charmaps = slice::from_raw_parts((*face).charmaps, (*face).num_charmaps as usize);
+            match charmaps.iter().find(|&&charmap| {
+                ((*charmap).platform_id, (*charmap).encoding_id) == (platform, encoding)
+            }) {
+                Some(&charmap) => {
+                    self.glyph_cache.clear();
+                    FT_Set_Charmap(face, charmap) == 0
+                }
+                None => false,
+            }
+        }
+    }
+
     /// Returns the glyph ID for the specified glyph name.
+    ///
+    /// `.notdef`, `.null`, and `nonmarkingreturn` also resolve by convention, as
+    /// `Loader::glyph_by_name()` describes.
     #[inline]
     pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
         if let Ok(ffi_name) = CString::new(name) {
@@ -401,7 +701,35 @@ impl Font {
                 return Some(code);
             }
         }
-        None
+        loader::conventional_glyph_by_name(self, name)
+    }
+
+    /// Returns the name of the glyph with the given ID, the reverse of `glyph_by_name()`.
+    ///
+    /// Names come from the `post` table or, for CFF and Type 1 fonts, the glyph charset. Returns
+    /// `None` if the font doesn't name its glyphs.
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        unsafe {
+            if (*self.freetype_face).face_flags & FT_FACE_FLAG_GLYPH_NAMES == 0
+                || glyph_id >= self.glyph_count()
+            {
+                return None;
+            }
+            let mut buffer = [0 as c_char; 256];
+            if FT_Get_Glyph_Name(
+                self.freetype_face,
+                glyph_id,
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer.len() as FT_UInt,
+            ) != 0
+            {
+                return None;
+            }
+            match CStr::from_ptr(buffer.as_ptr()).to_str() {
+                Ok(name) if !name.is_empty() => Some(name.to_owned()),
+                _ => None,
+            }
+        }
     }
 
     /// Returns the number of glyphs in the font.
@@ -415,9 +743,26 @@ impl Font {
     /// Sends the vector path for a glyph to a path builder.
     ///
     /// If `hinting_mode` is not None, this function performs grid-fitting as requested before
-    /// sending the hinding outlines to the builder.
+    /// sending the hinted outlines to the builder. The glyph is grid-fitted at the size that
+    /// `hinting_mode` carries, which may be fractional. Unhinted outlines don't depend on any size.
+    ///
+    /// Glyphs in bitmap-only fonts have no vector path, so nothing is sent for them. Use
+    /// `outline_with_bitmap_fallback()` to get approximate geometry instead.
+    ///
+    /// Curves are sent as the font stores them: quadratic Béziers for TrueType outlines, and cubic
+    /// Béziers for CFF and Type 1 outlines.
     ///
-    /// TODO(pcwalton): What should we do for bitmap glyphs?
+    /// Points are in font units, with y pointing up. Unhinted points are the font's own integer
+    /// coordinates, exactly; hinted points are grid-fitted to whole pixels at the hinting size and
+    /// scaled back to font units, so they can be fractional.
+    ///
+    /// Outlines of variable fonts reflect the coordinates set with `set_variations()`: FreeType
+    /// applies the `gvar` deltas or CFF2 blends as it loads each glyph.
+    ///
+    /// Outlines are decoded once per glyph and hinting mode and then replayed from a cache, so
+    /// repeated calls for the same glyph are cheap. The cache holds the 1024 most recently used
+    /// outlines. Clones share the cache along with the variation coordinates, and it's cleared
+    /// whenever the coordinates change.
     pub fn outline<S>(
         &self,
         glyph_id: u32,
@@ -427,19 +772,51 @@ impl Font {
     where
         S: OutlineSink,
     {
-        unsafe {
-            let rasterization_options = RasterizationOptions::GrayscaleAa;
-            let load_flags = self
-                .hinting_and_rasterization_options_to_load_flags(hinting, rasterization_options);
+        let key = (
+            glyph_id,
+            OutlineCacheHinting::from(hinting),
+            self.hinting_engine,
+        );
+        if let Some(outline) = self.outline_cache.borrow_mut().get(&key) {
+            outline.copy_to(sink);
+            return Ok(());
+        }
+
+        let mut builder = OutlineBuilder::new();
+        self.decode_outline(glyph_id, hinting, &mut builder)?;
+        let outline = builder.into_outline();
+        outline.copy_to(sink);
+        self.outline_cache.borrow_mut().insert(key, outline);
+        Ok(())
+    }
 
+    fn decode_outline<S>(
+        &self,
+        glyph_id: u32,
+        hinting: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        unsafe {
+            // Unhinted outlines are loaded unscaled, so that their points are the font's own
+            // integer coordinates rather than 26.6 fixed point values that need rounding.
             let units_per_em = (*self.freetype_face).units_per_EM;
             let grid_fitting_size = hinting.grid_fitting_size();
-            if let Some(size) = grid_fitting_size {
-                assert_eq!(
-                    FT_Set_Char_Size(self.freetype_face, size.f32_to_ft_fixed_26_6(), 0, 0, 0),
-                    0
-                );
-            }
+            let load_flags = match grid_fitting_size {
+                None => FT_LOAD_NO_SCALE | FT_LOAD_NO_HINTING,
+                Some(size) => {
+                    assert_eq!(
+                        FT_Set_Char_Size(self.freetype_face, size.f32_to_ft_fixed_26_6(), 0, 0, 0),
+                        0
+                    );
+                    self.hinting_and_rasterization_options_to_load_flags(
+                        hinting,
+                        RasterizationOptions::GrayscaleAa,
+                    )
+                }
+            };
 
             if FT_Load_Glyph(self.freetype_face, glyph_id, load_flags) != 0 {
                 return Err(GlyphLoadingError::NoSuchGlyph);
@@ -454,8 +831,9 @@ impl Font {
             let point_tags = slice::from_raw_parts(outline.tags, outline.n_points as usize);
 
             let mut current_point_index = 0;
-            for &last_point_index_in_contour in contours {
-                let last_point_index_in_contour = last_point_index_in_contour as usize;
+            for &contour_end in contours {
+                let contour_end = contour_end as usize;
+                let mut last_point_index_in_contour = contour_end;
                 let (mut first_point, first_tag) = get_point(
                     &mut current_point_index,
                     point_positions,
@@ -465,9 +843,10 @@ impl Font {
                     units_per_em,
                 );
                 if (first_tag & FT_POINT_TAG_ON_CURVE) == 0 {
-                    // Rare, but can happen; e.g. with Inconsolata (see pathfinder#84).
-                    //
-                    // FIXME(pcwalton): I'm not sure this is right.
+                    // TrueType contours may start off the curve; e.g. with Inconsolata (see
+                    // pathfinder#84). Like `FT_Outline_Decompose()`, start at the last point if
+                    // it's on the curve, and otherwise at the implied on-curve point between the
+                    // last and first points.
                     let mut temp_point_index = last_point_index_in_contour;
                     let (last_point, last_tag) = get_point(
                         &mut temp_point_index,
@@ -478,7 +857,10 @@ impl Font {
                         units_per_em,
                     );
                     if (last_tag & FT_POINT_TAG_ON_CURVE) != 0 {
-                        first_point = last_point
+                        // The last point is now the start, so the walk below must stop before
+                        // it; otherwise it would emit a zero-length segment back to itself.
+                        first_point = last_point;
+                        last_point_index_in_contour -= 1;
                     } else {
                         first_point = last_point.lerp(first_point, 0.5)
                     }
@@ -552,6 +934,7 @@ impl Font {
                     }
                 }
                 sink.close();
+                current_point_index = contour_end + 1;
             }
 
             if hinting.grid_fitting_size().is_some() {
@@ -575,15 +958,91 @@ impl Font {
             *current_point_index += 1;
 
             let point_position = Vector2I::new(point_position.x as i32, point_position.y as i32);
-            let mut point_position = point_position.ft_fixed_26_6_to_f32();
-            if let Some(grid_fitting_size) = grid_fitting_size {
-                point_position = point_position * (units_per_em as f32) / grid_fitting_size;
-            }
+            let point_position = match grid_fitting_size {
+                // Unscaled points are already in font units.
+                None => point_position.to_f32(),
+                Some(grid_fitting_size) => {
+                    point_position.ft_fixed_26_6_to_f32() * (units_per_em as f32)
+                        / grid_fitting_size
+                }
+            };
 
             (point_position, point_tag)
         }
     }
 
+    /// Sends the vector path for a glyph to a path builder like `outline()`, except that glyphs
+    /// from bitmap-only fonts (such as PCF and BDF) are traced into filled rectangles, one per run
+    /// of set pixels in the font's first bitmap strike.
+    ///
+    /// The traced outline follows the pixel grid exactly, so it's lossy. It's in font units, or in
+    /// pixels of the strike for fonts without units per em, matching `typographic_bounds()`.
+    pub fn outline_with_bitmap_fallback<S>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        unsafe {
+            let face = self.freetype_face;
+            if ((*face).face_flags & FT_FACE_FLAG_SCALABLE) != 0 || (*face).num_fixed_sizes == 0 {
+                return self.outline(glyph_id, hinting_mode, sink);
+            }
+
+            if FT_Select_Size(face, 0) != 0 {
+                return Err(GlyphLoadingError::PlatformError);
+            }
+            let load_flags = FT_LOAD_DEFAULT | FT_LOAD_RENDER | FT_LOAD_TARGET_MONO;
+            if FT_Load_Glyph(face, glyph_id, load_flags) != 0 {
+                reset_freetype_face_char_size(face);
+                return Err(GlyphLoadingError::NoSuchGlyph);
+            }
+
+            let glyph = &*(*face).glyph;
+            let bitmap = &glyph.bitmap;
+            let bitmap_size = Vector2I::new(bitmap.width, bitmap.rows);
+            let mut canvas = Canvas::new(bitmap_size, Format::A8);
+            if !bitmap.buffer.is_null() {
+                let bitmap_stride = bitmap.pitch as usize;
+                let buffer = slice::from_raw_parts(
+                    bitmap.buffer as *const u8,
+                    bitmap_stride * bitmap.rows as usize,
+                );
+                match bitmap.pixel_mode as u32 {
+                    FT_PIXEL_MODE_MONO => canvas.blit_from_bitmap_1bpp(
+                        Vector2I::default(),
+                        buffer,
+                        bitmap_size,
+                        bitmap_stride,
+                    ),
+                    _ => canvas.blit_from(
+                        Vector2I::default(),
+                        buffer,
+                        bitmap_size,
+                        bitmap_stride,
+                        Format::A8,
+                    ),
+                }
+            }
+
+            let units_per_em = (*face).units_per_EM;
+            let y_ppem = (*(*face).available_sizes).y_ppem as f32 / 64.0;
+            let pixel_size = if units_per_em > 0 && y_ppem > 0.0 {
+                units_per_em as f32 / y_ppem
+            } else {
+                1.0
+            };
+            let origin = Vector2I::new(glyph.bitmap_left, glyph.bitmap_top).to_f32() * pixel_size;
+            outline::trace_bitmap_rectangles(&canvas, origin, pixel_size, sink);
+
+            reset_freetype_face_char_size(face);
+            Ok(())
+        }
+    }
+
     /// Returns the boundaries of a glyph in font units.
     pub fn typographic_bounds(&self, glyph_id: u32) -> Result<RectF, GlyphLoadingError> {
         unsafe {
@@ -635,9 +1094,28 @@ impl Font {
     }
 
     /// Retrieves various metrics that apply to the entire font.
+    ///
+    /// The metrics are computed on the first call and cached until the variations change.
     pub fn metrics(&self) -> Metrics {
+        if let Some(metrics) = self.metrics_cache.get() {
+            return metrics;
+        }
+        let metrics = self.compute_metrics();
+        self.metrics_cache.set(Some(metrics));
+        metrics
+    }
+
+    fn compute_metrics(&self) -> Metrics {
         let os2_table = self.get_os2_table();
-        unsafe {
+        let [caret_slope_rise, caret_slope_run, caret_offset] = self
+            .load_font_table(tables::TAG_HHEA)
+            .and_then(|hhea| tables::hhea_caret(&hhea))
+            .unwrap_or([1, 0, 0]);
+        let [typo_line_gap, win_ascent, win_descent] = self
+            .load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_leading_metrics(&os2))
+            .unwrap_or([0.0; 3]);
+        let mut metrics = unsafe {
             let ascender = (*self.freetype_face).ascender;
             let descender = (*self.freetype_face).descender;
             let underline_position = (*self.freetype_face).underline_position;
@@ -662,8 +1140,16 @@ impl Font {
                     .map(|table| (*table).sxHeight as f32)
                     .unwrap_or(0.0),
                 bounding_box: bounding_box.to_f32(),
+                caret_slope_rise: caret_slope_rise as f32,
+                caret_slope_run: caret_slope_run as f32,
+                caret_offset: caret_offset as f32,
+                typo_line_gap,
+                win_ascent,
+                win_descent,
             }
-        }
+        };
+        loader::measure_missing_heights(self, &mut metrics);
+        metrics
     }
 
     /// Returns true if and only if the font loader can perform hinting in the requested way.
@@ -678,14 +1164,15 @@ impl Font {
         hinting_options: HintingOptions,
         for_rasterization: bool,
     ) -> bool {
+        // Hinted outlines come from the same `FT_Load_Glyph` call as hinted bitmaps, using the
+        // font's own hinter or the autohinter. Subpixel hinting targets a horizontally tripled
+        // grid, which is meaningless without an LCD rasterizer behind it.
         match (hinting_options, for_rasterization) {
             (HintingOptions::None, _)
-            | (HintingOptions::Vertical(_), true)
-            | (HintingOptions::VerticalSubpixel(_), true)
-            | (HintingOptions::Full(_), true) => true,
-            (HintingOptions::Vertical(_), false)
-            | (HintingOptions::VerticalSubpixel(_), false)
-            | (HintingOptions::Full(_), false) => false,
+            | (HintingOptions::Vertical(_), _)
+            | (HintingOptions::Full(_), _)
+            | (HintingOptions::VerticalSubpixel(_), true) => true,
+            (HintingOptions::VerticalSubpixel(_), false) => false,
         }
     }
 
@@ -755,6 +1242,11 @@ impl Font {
         }
     }
 
+    #[inline]
+    fn face_index(&self) -> FT_Long {
+        unsafe { (*self.freetype_face).face_index }
+    }
+
     /// Returns the pixel boundaries that the glyph will take up when rendered using this loader's
     /// rasterizer at the given size and origin.
     #[inline]
@@ -785,6 +1277,15 @@ impl Font {
     /// loader.
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
+    ///
+    /// The translation in `transform` may be fractional: the outline is shifted by the fractional
+    /// part before it's rendered, rather than snapped to whole pixels, so glyphs can be positioned
+    /// at subpixel offsets.
+    ///
+    /// If the glyph's raster bounds don't fit inside the canvas, this returns
+    /// `GlyphLoadingError::CanvasTooSmall`. Use `rasterize_glyph_clipped()` to draw the visible
+    /// part of the glyph instead.
+    #[inline]
     pub fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -794,12 +1295,127 @@ impl Font {
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
     ) -> Result<(), GlyphLoadingError> {
+        loader::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas with the given size and origin, returning its advance at
+    /// `point_size`, in pixels.
+    ///
+    /// This behaves like `rasterize_glyph()` followed by `scaled_advance()`, except that the
+    /// advance of a scalable glyph is read from the glyph as it's rasterized rather than loading
+    /// it again.
+    pub fn rasterize_and_advance(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<Vector2F, GlyphLoadingError> {
+        let required = self.raster_bounds(
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        let is_scalable =
+            unsafe { (*self.freetype_face).face_flags & (FT_FACE_FLAG_SCALABLE as FT_Long) != 0 };
+        if required.width() <= 0 || required.height() <= 0 || !is_scalable {
+            self.rasterize_glyph(
+                canvas,
+                glyph_id,
+                point_size,
+                transform,
+                hinting_options,
+                rasterization_options,
+            )?;
+            return self.scaled_advance(glyph_id, point_size);
+        }
+        if !loader::fits_in_canvas(canvas, required) {
+            return Err(GlyphLoadingError::CanvasTooSmall { required });
+        }
+
+        let linear_advance = self.rasterize_glyph_clipped_and_advance(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )?;
+        Ok(Vector2F::new(linear_advance, 0.0))
+    }
+
+    /// Rasterizes a glyph to a canvas with the given size and origin, clipping it to the bounds of
+    /// the canvas.
+    ///
+    /// This behaves like `rasterize_glyph()`, except that parts of the glyph that fall outside
+    /// the canvas are silently discarded.
+    pub fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_clipped_and_advance(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+        .map(|_| ())
+    }
+
+    // Rasterizes a glyph as `rasterize_glyph_clipped()` does, returning FreeType's unhinted
+    // horizontal advance for it at `point_size`, in pixels.
+    fn rasterize_glyph_clipped_and_advance(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<f32, GlyphLoadingError> {
+        // Fonts with only bitmap strikes, such as `CBDT` color emoji fonts, can't be set to an
+        // arbitrary size, so their glyph images are scaled from the nearest strike instead.
+        let is_scalable =
+            unsafe { (*self.freetype_face).face_flags & (FT_FACE_FLAG_SCALABLE as FT_Long) != 0 };
+        if !is_scalable {
+            if let Some(image) = self.glyph_raster_image(glyph_id, point_size) {
+                return self.rasterize_strike_glyph(
+                    canvas,
+                    glyph_id,
+                    point_size,
+                    transform,
+                    image.pixels_per_em,
+                );
+            }
+        }
+
         // TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
         // implementation.
         unsafe {
             let matrix = transform.matrix.0 * F32x4::new(65536.0, -65536.0, -65536.0, 65536.0);
             let matrix = matrix.to_i32x4();
-            let vector = transform.vector.f32_to_ft_fixed_26_6();
+            // Round rather than truncate, so that fractional origins (subpixel positioning) shift
+            // the outline by the nearest 1/64 pixel in either direction.
+            let vector = (transform.vector * 64.0).round().to_i32();
 
             let mut delta = FT_Vector {
                 x: vector.x() as FT_Pos,
@@ -860,25 +1476,144 @@ impl Font {
                     FT_PIXEL_MODE_GRAY => {
                         canvas.blit_from(dst_point, buffer, bitmap_size, bitmap_stride, Format::A8);
                     }
-                    FT_PIXEL_MODE_LCD | FT_PIXEL_MODE_LCD_V => {
+                    FT_PIXEL_MODE_LCD => {
+                        // The width counts subpixels, three to a pixel.
                         canvas.blit_from(
                             dst_point,
                             buffer,
-                            bitmap_size,
+                            Vector2I::new(bitmap_width / 3, bitmap_height),
                             bitmap_stride,
                             Format::Rgb24,
                         );
                     }
-                    FT_PIXEL_MODE_MONO => {
-                        canvas.blit_from_bitmap_1bpp(dst_point, buffer, bitmap_size, bitmap_stride);
-                    }
-                    _ => panic!("Unexpected FreeType pixel mode!"),
-                }
-            }
+                    FT_PIXEL_MODE_LCD_V => {
+                        // Each row of pixels takes three rows of the bitmap, one per subpixel.
+                        let size = Vector2I::new(bitmap_width, bitmap_height / 3);
+                        let mut pixels =
+                            Vec::with_capacity(size.x() as usize * size.y() as usize * 3);
+                        for y in 0..(size.y() as usize) {
+                            for x in 0..(size.x() as usize) {
+                                for subpixel in 0..3 {
+                                    pixels.push(buffer[(y * 3 + subpixel) * bitmap_stride + x]);
+                                }
+                            }
+                        }
+                        canvas.blit_from(
+                            dst_point,
+                            &pixels,
+                            size,
+                            size.x() as usize * 3,
+                            Format::Rgb24,
+                        );
+                    }
+                    FT_PIXEL_MODE_MONO => {
+                        canvas.blit_from_bitmap_1bpp(dst_point, buffer, bitmap_size, bitmap_stride);
+                    }
+                    _ => panic!("Unexpected FreeType pixel mode!"),
+                }
+            }
 
+            let linear_advance = (*(*self.freetype_face).glyph).linearHoriAdvance as f32 / 65536.0;
             FT_Set_Transform(self.freetype_face, ptr::null_mut(), ptr::null_mut());
             reset_freetype_face_char_size(self.freetype_face);
-            Ok(())
+            Ok(linear_advance)
+        }
+    }
+
+    // Rasterizes a glyph from the bitmap strike for `pixels_per_em`, as `glyph_raster_image()`
+    // chooses it, scaled to `point_size` and transformed by `transform`. Returns the advance as
+    // `rasterize_glyph_clipped_and_advance()` does.
+    fn rasterize_strike_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        pixels_per_em: u16,
+    ) -> Result<f32, GlyphLoadingError> {
+        unsafe {
+            let face = self.freetype_face;
+            let strike_index = (0..(*face).num_fixed_sizes)
+                .find(|&index| {
+                    let strike = &*(*face).available_sizes.offset(index as isize);
+                    (strike.y_ppem + 32) >> 6 == pixels_per_em as FT_Pos
+                })
+                .ok_or(GlyphLoadingError::NoSuchGlyph)?;
+            if FT_Select_Size(face, strike_index) != 0 {
+                return Err(GlyphLoadingError::PlatformError);
+            }
+            if FT_Load_Glyph(face, glyph_id, FT_LOAD_COLOR | FT_LOAD_RENDER) != 0 {
+                reset_freetype_face_char_size(face);
+                return Err(GlyphLoadingError::NoSuchGlyph);
+            }
+
+            let glyph = &*(*face).glyph;
+            let bitmap = &glyph.bitmap;
+            let bitmap_size = Vector2I::new(bitmap.width, bitmap.rows);
+            let bitmap_stride = bitmap.pitch as usize;
+            let buffer = if bitmap.buffer.is_null() {
+                &[][..]
+            } else {
+                slice::from_raw_parts(
+                    bitmap.buffer as *const u8,
+                    bitmap_stride * bitmap.rows as usize,
+                )
+            };
+            let image = match bitmap.pixel_mode as u32 {
+                FT_PIXEL_MODE_BGRA => {
+                    let mut image = Canvas::new(bitmap_size, Format::Rgba32);
+                    image.blit_from(
+                        Vector2I::default(),
+                        buffer,
+                        bitmap_size,
+                        bitmap_stride,
+                        Format::Rgba32,
+                    );
+                    for pixel in image.pixels.chunks_mut(4) {
+                        pixel.swap(0, 2);
+                    }
+                    image
+                }
+                FT_PIXEL_MODE_MONO => {
+                    let mut image = Canvas::new(bitmap_size, Format::A8);
+                    image.blit_from_bitmap_1bpp(
+                        Vector2I::default(),
+                        buffer,
+                        bitmap_size,
+                        bitmap_stride,
+                    );
+                    image
+                }
+                FT_PIXEL_MODE_GRAY => {
+                    let mut image = Canvas::new(bitmap_size, Format::A8);
+                    image.blit_from(
+                        Vector2I::default(),
+                        buffer,
+                        bitmap_size,
+                        bitmap_stride,
+                        Format::A8,
+                    );
+                    image
+                }
+                _ => {
+                    reset_freetype_face_char_size(face);
+                    return Err(GlyphLoadingError::PlatformError);
+                }
+            };
+
+            // Map strike pixels, with the top left corner of the image at the glyph's bearing, to
+            // the canvas.
+            let scale = point_size / pixels_per_em as f32;
+            let bearing = Vector2F::new(glyph.bitmap_left as f32, -glyph.bitmap_top as f32);
+            draw_transformed(
+                canvas,
+                &image,
+                transform * Transform2F::from_scale(scale) * Transform2F::from_translation(bearing),
+            );
+
+            let advance = glyph.advance.x as f32 / 64.0 * scale;
+            reset_freetype_face_char_size(face);
+            Ok(advance)
         }
     }
 
@@ -900,6 +1635,13 @@ impl Font {
         if rasterization == RasterizationOptions::Bilevel {
             options |= FT_LOAD_MONOCHROME
         }
+        if hinting != HintingOptions::None {
+            options |= match self.hinting_engine {
+                HintingEngine::Default => 0,
+                HintingEngine::Bytecode => FT_LOAD_NO_AUTOHINT,
+                HintingEngine::Autohinter => FT_LOAD_FORCE_AUTOHINT,
+            };
+        }
         options
     }
 
@@ -914,8 +1656,12 @@ impl Font {
     /// Attempts to return the raw font data (contents of the font file).
     ///
     /// If this font is a member of a collection, this function returns the data for the entire
-    /// collection.
+    /// collection. Returns `None` only for fonts created with `from_native_font()` whose data
+    /// couldn't be recovered from the native face.
     pub fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
+        if self.font_data.is_empty() {
+            return None;
+        }
         Some(self.font_data.clone())
     }
 
@@ -937,34 +1683,61 @@ impl Font {
     /// Tags are four-character codes. A list of tags can be found in the [OpenType specification].
     ///
     /// [OpenType specification]: https://docs.microsoft.com/en-us/typography/opentype/spec/
+    ///
+    /// Small tables, such as `head`, `OS/2`, and `name`, are kept after the first load, so later
+    /// calls return a copy without going back to FreeType. Large tables, such as `glyf` or `CFF `,
+    /// are read afresh each time to keep memory use bounded.
     pub fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
-        unsafe {
-            let mut len = 0;
+        if let Some(table) = self.table_cache.borrow().get(&table_tag) {
+            return table.clone();
+        }
+        let table = unsafe { load_sfnt_table(self.freetype_face, table_tag) };
+        if table
+            .as_ref()
+            .map_or(true, |table| table.len() <= TABLE_CACHE_MAX_LEN)
+        {
+            self.table_cache
+                .borrow_mut()
+                .insert(table_tag, table.clone());
+        }
+        table
+    }
 
-            if 0 != FT_Load_Sfnt_Table(
-                self.freetype_face,
-                table_tag as FT_ULong,
-                0,
-                ptr::null_mut(),
-                &mut len,
-            ) {
+    /// Returns the bytes in `range` of the OpenType table with the given tag, if the table exists
+    /// and is at least `range.end` bytes long.
+    ///
+    /// Only the requested bytes are read from the font.
+    pub fn font_table_range(&self, table_tag: u32, range: Range<usize>) -> Option<Box<[u8]>> {
+        unsafe {
+            let table_len = sfnt_table_len(self.freetype_face, table_tag)?;
+            if range.start > range.end || range.end > table_len {
                 return None;
             }
-
-            let mut buf = Box::<[u8]>::from(vec![0; len as usize]);
+            if range.is_empty() {
+                return Some(Box::from([]));
+            }
+            let mut len = range.len() as FT_ULong;
+            let mut buf = Box::<[u8]>::from(vec![0; range.len()]);
             if 0 != FT_Load_Sfnt_Table(
                 self.freetype_face,
                 table_tag as FT_ULong,
-                0,
+                range.start as FT_Long,
                 buf.as_mut_ptr() as *mut FT_Byte,
                 &mut len,
             ) {
                 return None;
             }
-
             Some(buf)
         }
     }
+
+    /// Returns the length in bytes of the OpenType table with the given tag, if the table exists.
+    pub fn table_len(&self, table_tag: u32) -> Option<usize> {
+        if let Some(table) = self.table_cache.borrow().get(&table_tag) {
+            return table.as_ref().map(|table| table.len());
+        }
+        unsafe { sfnt_table_len(self.freetype_face, table_tag) }
+    }
 }
 
 impl Clone for Font {
@@ -974,6 +1747,11 @@ impl Clone for Font {
             Font {
                 freetype_face: self.freetype_face,
                 font_data: self.font_data.clone(),
+                outline_cache: self.outline_cache.clone(),
+                metrics_cache: self.metrics_cache.clone(),
+                table_cache: self.table_cache.clone(),
+                glyph_cache: self.glyph_cache.clone(),
+                hinting_engine: self.hinting_engine,
             }
         }
     }
@@ -998,6 +1776,46 @@ impl Debug for Font {
     }
 }
 
+/// Fonts compare equal if they were loaded from identical font data with the same face index, and
+/// have the same variation coordinates.
+///
+/// This compares the source of the font, not its rendered output. In particular, two independently
+/// loaded copies of the same file compare equal. Font data is compared in full, but only once its
+/// length and start, which holds the table directory, match. Fonts created with
+/// `from_native_font()` whose data couldn't be recovered are only equal to other such fonts that
+/// share their FreeType face.
+impl PartialEq for Font {
+    fn eq(&self, other: &Font) -> bool {
+        if self.font_data.is_empty() || other.font_data.is_empty() {
+            return self.font_data.is_empty()
+                && other.font_data.is_empty()
+                && self.freetype_face == other.freetype_face;
+        }
+        self.face_index() == other.face_index()
+            && handle::font_data_matches(&self.font_data, &other.font_data)
+            && self.variation_coordinates() == other.variation_coordinates()
+    }
+}
+
+impl Eq for Font {}
+
+impl Hash for Font {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        if self.font_data.is_empty() {
+            (self.freetype_face as usize).hash(state);
+            return;
+        }
+        handle::hash_font_data(state, &self.font_data, self.face_index() as u32);
+        for (tag, value) in self.variation_coordinates() {
+            tag.hash(state);
+            value.to_bits().hash(state);
+        }
+    }
+}
+
 impl Loader for Font {
     type NativeFont = NativeFont;
 
@@ -1067,6 +1885,11 @@ impl Loader for Font {
         self.glyph_by_name(name)
     }
 
+    #[inline]
+    fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        self.glyph_name(glyph_id)
+    }
+
     #[inline]
     fn glyph_count(&self) -> u32 {
         self.glyph_count()
@@ -1085,6 +1908,19 @@ impl Loader for Font {
         self.outline(glyph_id, hinting_mode, sink)
     }
 
+    #[inline]
+    fn outline_with_bitmap_fallback<S>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        self.outline_with_bitmap_fallback(glyph_id, hinting_mode, sink)
+    }
+
     #[inline]
     fn typographic_bounds(&self, glyph_id: u32) -> Result<RectF, GlyphLoadingError> {
         self.typographic_bounds(glyph_id)
@@ -1105,6 +1941,16 @@ impl Loader for Font {
         self.metrics()
     }
 
+    #[inline]
+    fn variation_axes(&self) -> Vec<VariationAxis> {
+        self.variation_axes()
+    }
+
+    #[inline]
+    fn set_variations(&mut self, variations: &[(u32, f32)]) -> Result<(), FontLoadingError> {
+        self.set_variations(variations)
+    }
+
     #[inline]
     fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
         self.copy_font_data()
@@ -1139,6 +1985,76 @@ impl Loader for Font {
         )
     }
 
+    #[inline]
+    fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_clipped(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    #[inline]
+    fn rasterize_and_advance(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<Vector2F, GlyphLoadingError> {
+        self.rasterize_and_advance(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    fn rasterize_glyph_with_settings(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        settings: &RasterizationSettings,
+    ) -> Result<(), GlyphLoadingError> {
+        if settings.stem_darkening().is_none() && settings.lcd_filter().is_none() {
+            return loader::rasterize_glyph_with_settings(
+                self, canvas, glyph_id, point_size, transform, settings,
+            );
+        }
+        FREETYPE_LIBRARY.with(|freetype_library| unsafe {
+            let _stem_darkening = settings.stem_darkening().map(|stem_darkening| {
+                StemDarkeningOverride::new(
+                    freetype_library.0,
+                    stem_darkening,
+                    settings.stem_darkening_strength(),
+                )
+            });
+            let _lcd_filter = settings
+                .lcd_filter()
+                .map(|lcd_filter| LcdFilterOverride::new(freetype_library.0, lcd_filter));
+            loader::rasterize_glyph_with_settings(
+                self, canvas, glyph_id, point_size, transform, settings,
+            )
+        })
+    }
+
     #[inline]
     fn get_fallbacks(&self, text: &str, locale: &str) -> FallbackResult<Self> {
         self.get_fallbacks(text, locale)
@@ -1148,16 +2064,275 @@ impl Loader for Font {
     fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
         self.load_font_table(table_tag)
     }
+
+    #[inline]
+    fn font_table_range(&self, table_tag: u32, range: Range<usize>) -> Option<Box<[u8]>> {
+        self.font_table_range(table_tag, range)
+    }
+
+    #[inline]
+    fn table_len(&self, table_tag: u32) -> Option<usize> {
+        self.table_len(table_tag)
+    }
+}
+
+unsafe fn file_type_of_face(font_data: &[u8], face: FT_Face) -> FileType {
+    if file_type::is_type_1(font_data) {
+        return FileType::Type1;
+    }
+    match (*face).num_faces {
+        1 => FileType::Single,
+        num_faces => FileType::Collection(num_faces as u32),
+    }
+}
+
+// FreeType fails on data that isn't a font in various ways (a truncated PNG, for instance, gives
+// a stream error), so check the signature rather than trusting the error code alone.
+fn face_loading_error(font_data: &[u8], error: FT_Error) -> FontLoadingError {
+    if error == FT_Err_Unknown_File_Format || !file_type::has_font_signature(font_data) {
+        FontLoadingError::UnknownFormat
+    } else {
+        FontLoadingError::Parse
+    }
+}
+
+// The FreeType drivers that use the Adobe rendering engine, which implements stem darkening.
+const STEM_DARKENING_DRIVERS: [&[u8]; 3] = [b"cff\0", b"type1\0", b"t1cid\0"];
+
+// Stem darkening is a driver property, shared by every face in the library, so it's set for a
+// single render and the previous values are restored when this is dropped.
+struct StemDarkeningOverride {
+    library: FT_Library,
+    saved: Vec<(&'static [u8], FT_Bool, [FT_Int; 8])>,
+}
+
+impl StemDarkeningOverride {
+    unsafe fn new(
+        library: FT_Library,
+        stem_darkening: bool,
+        strength: f32,
+    ) -> StemDarkeningOverride {
+        let mut saved = vec![];
+        for &driver in &STEM_DARKENING_DRIVERS {
+            let driver_name = driver.as_ptr() as *const c_char;
+            let (mut no_stem_darkening, mut parameters): (FT_Bool, [FT_Int; 8]) = (0, [0; 8]);
+            if FT_Property_Get(
+                library,
+                driver_name,
+                b"no-stem-darkening\0".as_ptr() as *const c_char,
+                &mut no_stem_darkening as *mut FT_Bool as *mut c_void,
+            ) != 0
+                || FT_Property_Get(
+                    library,
+                    driver_name,
+                    b"darkening-parameters\0".as_ptr() as *const c_char,
+                    parameters.as_mut_ptr() as *mut c_void,
+                ) != 0
+            {
+                // Older FreeType versions don't support stem darkening in all drivers.
+                continue;
+            }
+
+            // The parameters are four (stem width, darkening amount) control points. Scale the
+            // amounts, which FreeType limits to 500.
+            let mut scaled_parameters = parameters;
+            for amount in scaled_parameters.iter_mut().skip(1).step_by(2) {
+                *amount = ((*amount as f32 * strength).round() as FT_Int).min(500);
+            }
+            set_stem_darkening_properties(
+                library,
+                driver,
+                !stem_darkening as FT_Bool,
+                &scaled_parameters,
+            );
+            saved.push((driver, no_stem_darkening, parameters));
+        }
+        StemDarkeningOverride { library, saved }
+    }
+}
+
+impl Drop for StemDarkeningOverride {
+    fn drop(&mut self) {
+        for &(driver, no_stem_darkening, ref parameters) in &self.saved {
+            unsafe {
+                set_stem_darkening_properties(self.library, driver, no_stem_darkening, parameters);
+            }
+        }
+    }
+}
+
+// The LCD filter is shared by every face in the library too. FreeType can't report the current
+// filter, so the library's default is restored when this is dropped.
+struct LcdFilterOverride {
+    library: FT_Library,
+}
+
+impl LcdFilterOverride {
+    unsafe fn new(library: FT_Library, lcd_filter: LcdFilter) -> LcdFilterOverride {
+        let lcd_filter = match lcd_filter {
+            LcdFilter::None => FT_LCD_FILTER_NONE,
+            LcdFilter::Default => FT_LCD_FILTER_DEFAULT,
+            LcdFilter::Light => FT_LCD_FILTER_LIGHT,
+            LcdFilter::Legacy => FT_LCD_FILTER_LEGACY,
+        };
+        FT_Library_SetLcdFilter(library, lcd_filter);
+        LcdFilterOverride { library }
+    }
+}
+
+impl Drop for LcdFilterOverride {
+    fn drop(&mut self) {
+        unsafe {
+            FT_Library_SetLcdFilter(self.library, FT_LCD_FILTER_DEFAULT);
+        }
+    }
+}
+
+unsafe fn set_stem_darkening_properties(
+    library: FT_Library,
+    driver: &[u8],
+    no_stem_darkening: FT_Bool,
+    parameters: &[FT_Int; 8],
+) {
+    let driver_name = driver.as_ptr() as *const c_char;
+    FT_Property_Set(
+        library,
+        driver_name,
+        b"no-stem-darkening\0".as_ptr() as *const c_char,
+        &no_stem_darkening as *const FT_Bool as *const c_void,
+    );
+    FT_Property_Set(
+        library,
+        driver_name,
+        b"darkening-parameters\0".as_ptr() as *const c_char,
+        parameters.as_ptr() as *const c_void,
+    );
+}
+
+unsafe fn load_sfnt_table(face: FT_Face, table_tag: u32) -> Option<Box<[u8]>> {
+    let mut len = sfnt_table_len(face, table_tag)? as FT_ULong;
+
+    let mut buf = Box::<[u8]>::from(vec![0; len as usize]);
+    if 0 != FT_Load_Sfnt_Table(
+        face,
+        table_tag as FT_ULong,
+        0,
+        buf.as_mut_ptr() as *mut FT_Byte,
+        &mut len,
+    ) {
+        return None;
+    }
+
+    Some(buf)
+}
+
+unsafe fn sfnt_table_len(face: FT_Face, table_tag: u32) -> Option<usize> {
+    let mut len = 0;
+    if 0 != FT_Load_Sfnt_Table(face, table_tag as FT_ULong, 0, ptr::null_mut(), &mut len) {
+        return None;
+    }
+    Some(len as usize)
+}
+
+// Copies the whole file or memory block that a face was opened from.
+unsafe fn copy_stream_data(face: FT_Face) -> Option<Vec<u8>> {
+    let stream = (*face).stream;
+    if stream.is_null() {
+        return None;
+    }
+
+    // Memory streams expose their data directly and have no read function.
+    if !(*stream).base.is_null() {
+        return Some(slice::from_raw_parts((*stream).base, (*stream).size as usize).to_vec());
+    }
+    let read = *(ptr::addr_of!((*stream).read) as *const Option<FT_Stream_IoFunc>);
+    let read = read?;
+    let mut font_data = vec![0; (*stream).size as usize];
+    let n_read = read(
+        stream,
+        0,
+        font_data.as_mut_ptr(),
+        font_data.len() as FT_ULong,
+    );
+    font_data.truncate(n_read as usize);
+    if font_data.is_empty() {
+        return None;
+    }
+    Some(font_data)
+}
+
+// Rebuilds a standalone SFNT file from the tables of a face.
+unsafe fn reassemble_sfnt(face: FT_Face) -> Option<Vec<u8>> {
+    if (*face).face_flags & FT_FACE_FLAG_SFNT == 0 {
+        return None;
+    }
+    let mut table_count = 0;
+    if FT_Sfnt_Table_Info(face, 0, ptr::null_mut(), &mut table_count) != 0 {
+        return None;
+    }
+    let mut font_tables = vec![];
+    for table_index in 0..table_count {
+        let (mut table_tag, mut table_length) = (0, 0);
+        if FT_Sfnt_Table_Info(
+            face,
+            table_index as FT_UInt,
+            &mut table_tag,
+            &mut table_length,
+        ) != 0
+        {
+            return None;
+        }
+        let table_tag = table_tag as u32;
+        font_tables.push((table_tag, load_sfnt_table(face, table_tag)?));
+    }
+    Some(tables::build_sfnt(font_tables))
 }
 
 unsafe fn setup_freetype_face(face: FT_Face) {
     reset_freetype_face_char_size(face);
 }
 
+// Draws `image` onto `canvas` with `transform` applied, taking the nearest image pixel for each
+// canvas pixel. Like a blit, this replaces the pixels within the transformed bounds of the image.
+fn draw_transformed(canvas: &mut Canvas, image: &Canvas, transform: Transform2F) {
+    let image_rect = RectI::new(Vector2I::default(), image.size);
+    let bounds = (transform * image_rect.to_f32()).round_out().to_i32();
+    let bounds = match bounds.intersection(RectI::new(Vector2I::default(), canvas.size)) {
+        Some(bounds) => bounds,
+        None => return,
+    };
+
+    let inverse = transform.inverse();
+    let bytes_per_pixel = image.format.bytes_per_pixel() as usize;
+    let mut resampled = Canvas::new(bounds.size(), image.format);
+    for y in 0..bounds.height() {
+        for x in 0..bounds.width() {
+            let center = (bounds.origin() + Vector2I::new(x, y)).to_f32() + Vector2F::splat(0.5);
+            let image_point = (inverse * center).floor().to_i32();
+            if !image_rect.contains_point(image_point) {
+                continue;
+            }
+            let src_start = image_point.y() as usize * image.stride
+                + image_point.x() as usize * bytes_per_pixel;
+            let dest_start = y as usize * resampled.stride + x as usize * bytes_per_pixel;
+            resampled.pixels[dest_start..(dest_start + bytes_per_pixel)]
+                .copy_from_slice(&image.pixels[src_start..(src_start + bytes_per_pixel)]);
+        }
+    }
+    canvas.blit_from(
+        bounds.origin(),
+        &resampled.pixels,
+        resampled.size,
+        resampled.stride,
+        resampled.format,
+    );
+}
+
 unsafe fn reset_freetype_face_char_size(face: FT_Face) {
-    // Apple Color Emoji has 0 units per em. Whee!
+    // Apple Color Emoji has 0 units per em. Whee! Fonts with only bitmap strikes, such as
+    // `CBDT` color emoji fonts, can't be set to an arbitrary size either.
     let units_per_em = (*face).units_per_EM as i64;
-    if units_per_em > 0 {
+    if units_per_em > 0 && (*face).face_flags & FT_FACE_FLAG_SCALABLE != 0 {
         assert_eq!(
             FT_Set_Char_Size(face, ((*face).units_per_EM as FT_Long) << 6, 0, 0, 0),
             0
@@ -1165,6 +2340,127 @@ unsafe fn reset_freetype_face_char_size(face: FT_Face) {
     }
 }
 
+// The largest table, in bytes, that `load_font_table()` keeps.
+const TABLE_CACHE_MAX_LEN: usize = 64 * 1024;
+
+const GLYPH_CACHE_SIZE: usize = 256;
+
+// An empty slot. `u32::MAX` isn't a valid `char`, so it never matches a lookup.
+const EMPTY_GLYPH_CACHE_ENTRY: (u32, u32) = (u32::MAX, 0);
+
+// A direct-mapped cache of `glyph_for_char()` results, indexed by the low bits of the character.
+// Each slot holds a character and its glyph ID, with 0 standing for no glyph, as in FreeType.
+struct GlyphCache {
+    entries: Box<[Cell<(u32, u32)>]>,
+}
+
+impl GlyphCache {
+    fn new() -> GlyphCache {
+        GlyphCache {
+            entries: vec![Cell::new(EMPTY_GLYPH_CACHE_ENTRY); GLYPH_CACHE_SIZE].into_boxed_slice(),
+        }
+    }
+
+    fn get(&self, character: char) -> Option<Option<u32>> {
+        let (cached_character, glyph_id) =
+            self.entries[character as usize % GLYPH_CACHE_SIZE].get();
+        if cached_character != character as u32 {
+            return None;
+        }
+        Some(if glyph_id == 0 { None } else { Some(glyph_id) })
+    }
+
+    fn insert(&self, character: char, glyph_id: Option<u32>) {
+        self.entries[character as usize % GLYPH_CACHE_SIZE]
+            .set((character as u32, glyph_id.unwrap_or(0)));
+    }
+
+    fn clear(&self) {
+        for entry in self.entries.iter() {
+            entry.set(EMPTY_GLYPH_CACHE_ENTRY);
+        }
+    }
+}
+
+// The most outlines that `outline()` keeps. Hinted outlines are cached per size, so without a
+// limit rendering a large font at many sizes would keep every outline it ever decoded.
+const OUTLINE_CACHE_CAPACITY: usize = 1024;
+
+// The glyph ID, hinting mode, and hinting engine that an outline was decoded with.
+type OutlineCacheKey = (u32, OutlineCacheHinting, HintingEngine);
+
+// Decoded outlines, evicting the least recently used one once `OUTLINE_CACHE_CAPACITY` is reached.
+// Each entry records the value of a counter that ticks on every access.
+struct OutlineCache {
+    entries: HashMap<OutlineCacheKey, (Outline, u64)>,
+    clock: u64,
+}
+
+impl OutlineCache {
+    fn new() -> OutlineCache {
+        OutlineCache {
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, key: &OutlineCacheKey) -> Option<&Outline> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(key).map(|(outline, last_used)| {
+            *last_used = clock;
+            &*outline
+        })
+    }
+
+    fn insert(&mut self, key: OutlineCacheKey, outline: Outline) {
+        if self.entries.len() >= OUTLINE_CACHE_CAPACITY && !self.entries.contains_key(&key) {
+            let least_recently_used = self
+                .entries
+                .iter()
+                .min_by_key(|(_, &(_, last_used))| last_used)
+                .map(|(&key, _)| key);
+            if let Some(least_recently_used) = least_recently_used {
+                self.entries.remove(&least_recently_used);
+            }
+        }
+        self.clock += 1;
+        self.entries.insert(key, (outline, self.clock));
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+// A hashable form of `HintingOptions` for the outline cache, with grid-fitting sizes compared
+// bitwise.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum OutlineCacheHinting {
+    None,
+    Vertical(u32),
+    VerticalSubpixel(u32),
+    Full(u32),
+}
+
+impl From<HintingOptions> for OutlineCacheHinting {
+    fn from(hinting: HintingOptions) -> OutlineCacheHinting {
+        match hinting {
+            HintingOptions::None => OutlineCacheHinting::None,
+            HintingOptions::Vertical(size) => OutlineCacheHinting::Vertical(size.to_bits()),
+            HintingOptions::VerticalSubpixel(size) => {
+                OutlineCacheHinting::VerticalSubpixel(size.to_bits())
+            }
+            HintingOptions::Full(size) => OutlineCacheHinting::Full(size.to_bits()),
+        }
+    }
+}
+
 trait F32ToFtFixed {
     type Output;
     fn f32_to_ft_fixed_26_6(self) -> Self::Output;
@@ -1228,18 +2524,619 @@ extern "C" {
         buffer: *mut FT_Byte,
         length: *mut FT_ULong,
     ) -> FT_Error;
+    fn FT_Sfnt_Table_Info(
+        face: FT_Face,
+        table_index: FT_UInt,
+        tag: *mut FT_ULong,
+        length: *mut FT_ULong,
+    ) -> FT_Error;
 }
 
 #[cfg(test)]
 mod test {
-    use crate::loaders::freetype::Font;
+    use freetype_sys::{FT_Done_Face, FT_New_Face};
+    use pathfinder_geometry::transform2d::Transform2F;
+    use pathfinder_geometry::vector::{Vector2F, Vector2I};
+    use std::collections::hash_map::DefaultHasher;
+    use std::ffi::CString;
+    use std::fs;
+    use std::hash::{Hash, Hasher};
+    use std::ptr;
+    use std::sync::Arc;
+
+    use super::{
+        load_sfnt_table, OutlineCacheHinting, FREETYPE_LIBRARY, OUTLINE_CACHE_CAPACITY,
+        TABLE_CACHE_MAX_LEN,
+    };
+    use crate::canvas::{Canvas, Format, RasterizationOptionsBuilder};
+    use crate::error::FontLoadingError;
+    use crate::file_type::FileType;
+    use crate::handle::Handle;
+    use crate::hinting::HintingOptions;
+    use crate::loader::{Loader, VariationAxis};
+    use crate::loaders::freetype::{Font, HintingEngine};
+    use crate::outline::OutlineBuilder;
+    use crate::properties::{Stretch, Style, Weight};
+    use crate::tables;
 
     static PCF_FONT_PATH: &str = "resources/tests/times-roman-pcf/timR12.pcf";
     static PCF_FONT_POSTSCRIPT_NAME: &str = "Times-Roman";
+    static TYPE_1_FONT_PATHS: [&str; 2] = [
+        "resources/tests/font-kit-test-type1/FontKitTest-Regular.pfa",
+        "resources/tests/font-kit-test-type1/FontKitTest-Regular.pfb",
+    ];
+    static TYPE_1_FONT_POSTSCRIPT_NAME: &str = "FontKitTest-Regular";
+    static TEST_FONT_FILE_PATH: &str = "resources/tests/eb-garamond/EBGaramond12-Regular.otf";
+    static TRUETYPE_FONT_PATHS: [&str; 2] = [
+        "resources/tests/eb-garamond/EBGaramond12-Regular.ttf",
+        "resources/tests/inconsolata/Inconsolata-Regular.ttf",
+    ];
+    static SYMBOL_FONT_PATH: &str = "resources/tests/font-kit-test-symbol/FontKitTestSymbol.ttf";
+    static VARIABLE_FONT_PATH: &str =
+        "resources/tests/font-kit-test-variable/FontKitTestVariable.ttf";
+    static MULTIPLE_MASTER_FONT_PATH: &str = "resources/tests/font-kit-test-mm/FontKitTestMM.pfa";
 
     #[test]
     fn get_pcf_postscript_name() {
         let font = Font::from_path(PCF_FONT_PATH, 0).unwrap();
         assert_eq!(font.postscript_name().unwrap(), PCF_FONT_POSTSCRIPT_NAME);
     }
+
+    #[test]
+    fn get_pcf_outline_with_bitmap_fallback() {
+        let font = Font::from_path(PCF_FONT_PATH, 0).unwrap();
+        let glyph_id = font.glyph_for_char('A').unwrap();
+
+        let mut builder = OutlineBuilder::new();
+        font.outline(glyph_id, HintingOptions::None, &mut builder)
+            .unwrap();
+        assert!(builder.into_outline().contours.is_empty());
+
+        let mut builder = OutlineBuilder::new();
+        font.outline_with_bitmap_fallback(glyph_id, HintingOptions::None, &mut builder)
+            .unwrap();
+        let outline = builder.into_outline();
+        assert!(!outline.contours.is_empty());
+
+        // Every contour is a rectangle within the glyph's bounds.
+        let bounds = font.typographic_bounds(glyph_id).unwrap();
+        for contour in &outline.contours {
+            assert_eq!(contour.positions.len(), 4);
+            for &position in &contour.positions {
+                assert!(bounds.contains_point(position));
+            }
+        }
+    }
+
+    #[test]
+    fn analyze_type_1_font() {
+        for path in &TYPE_1_FONT_PATHS {
+            let font_data = Arc::new(fs::read(path).unwrap());
+            assert_eq!(Font::analyze_bytes(font_data).unwrap(), FileType::Type1);
+            assert_eq!(Font::analyze_path(path).unwrap(), FileType::Type1);
+        }
+    }
+
+    #[test]
+    fn reject_non_font_data_as_unknown_format() {
+        let png_data =
+            b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0".to_vec();
+        let noise: Vec<u8> = (0..256u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+        for data in [png_data, noise] {
+            let data = Arc::new(data);
+            assert!(matches!(
+                Font::from_bytes(data.clone(), 0),
+                Err(FontLoadingError::UnknownFormat)
+            ));
+            assert!(matches!(
+                Font::analyze_bytes(data),
+                Err(FontLoadingError::UnknownFormat)
+            ));
+        }
+
+        // A damaged font is still recognizably a font.
+        let mut truncated_data = fs::read(TEST_FONT_FILE_PATH).unwrap();
+        truncated_data.truncate(64);
+        assert!(matches!(
+            Font::from_bytes(Arc::new(truncated_data), 0),
+            Err(FontLoadingError::Parse)
+        ));
+    }
+
+    #[test]
+    fn get_type_1_postscript_name_and_outline() {
+        for path in &TYPE_1_FONT_PATHS {
+            let font = Font::from_path(path, 0).unwrap();
+            assert_eq!(font.postscript_name().unwrap(), TYPE_1_FONT_POSTSCRIPT_NAME);
+            assert_eq!(font.family_name(), "Font Kit Test");
+
+            let glyph_id = font.glyph_for_char('A').unwrap();
+            let mut builder = OutlineBuilder::new();
+            font.outline(glyph_id, HintingOptions::None, &mut builder)
+                .unwrap();
+            let outline = builder.into_outline();
+            assert_eq!(outline.contours.len(), 1);
+            assert_eq!(outline.contours[0].positions.len(), 3);
+        }
+    }
+
+    #[test]
+    fn get_variable_font_properties() {
+        let mut font = Font::from_path(VARIABLE_FONT_PATH, 0).unwrap();
+        assert_eq!(
+            font.variation_axes(),
+            vec![
+                VariationAxis {
+                    tag: VariationAxis::WEIGHT,
+                    min_value: 100.0,
+                    default_value: 400.0,
+                    max_value: 900.0,
+                },
+                VariationAxis {
+                    tag: VariationAxis::WIDTH,
+                    min_value: 75.0,
+                    default_value: 100.0,
+                    max_value: 125.0,
+                },
+            ]
+        );
+        assert_eq!(font.properties().weight, Weight::NORMAL);
+
+        font.set_variations(&[(VariationAxis::WEIGHT, 700.0), (VariationAxis::WIDTH, 75.0)])
+            .unwrap();
+        let properties = font.properties();
+        assert_eq!(properties.weight, Weight::BOLD);
+        assert_eq!(properties.stretch, Stretch::CONDENSED);
+        assert_eq!(properties.style, Style::Normal);
+
+        // Unlisted axes return to their defaults, and values are clamped to the axis range.
+        font.set_variations(&[(VariationAxis::WIDTH, 200.0)])
+            .unwrap();
+        assert_eq!(font.properties().weight, Weight::NORMAL);
+        assert_eq!(font.properties().stretch, Stretch::EXPANDED);
+
+        // Fonts that aren't variable are unaffected.
+        let mut font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+        assert!(font.variation_axes().is_empty());
+        font.set_variations(&[(VariationAxis::WEIGHT, 700.0)])
+            .unwrap();
+        assert_eq!(font.properties().weight, Weight::NORMAL);
+    }
+
+    #[test]
+    fn get_multiple_master_font_axes() {
+        let mut font = Font::from_path(MULTIPLE_MASTER_FONT_PATH, 0).unwrap();
+        let axes = font.variation_axes();
+        assert_eq!(axes.len(), 1);
+        assert_eq!(axes[0].tag, VariationAxis::WEIGHT);
+        assert_eq!((axes[0].min_value, axes[0].max_value), (200.0, 900.0));
+        assert!((axes[0].default_value - 400.0).abs() < 0.5);
+        assert!(font.named_instances().is_empty());
+
+        // The width of the "A" is blended between 500 units in the light master and 600 in the
+        // bold one.
+        let glyph_id = font.glyph_for_char('A').unwrap();
+        let width = |font: &Font| font.typographic_bounds(glyph_id).unwrap().width();
+        font.set_variations(&[(VariationAxis::WEIGHT, 900.0)])
+            .unwrap();
+        assert_eq!(font.properties().weight, Weight::BLACK);
+        assert_eq!(width(&font), 600.0);
+        font.set_variations(&[(VariationAxis::WEIGHT, 100.0)])
+            .unwrap();
+        assert_eq!(font.properties().weight, Weight::EXTRA_LIGHT);
+        assert_eq!(width(&font), 500.0);
+
+        for path in &TYPE_1_FONT_PATHS {
+            assert!(Font::from_path(path, 0)
+                .unwrap()
+                .variation_axes()
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn expand_variable_font_into_named_instances() {
+        let handle = Handle::from_path(VARIABLE_FONT_PATH.into(), 0);
+        let instances = handle.named_instances().unwrap();
+        let names: Vec<_> = instances
+            .iter()
+            .map(|instance| instance.name.clone())
+            .collect();
+        assert_eq!(
+            names,
+            vec![Some("Thin".to_owned()), Some("Bold".to_owned())]
+        );
+        assert_eq!(
+            instances[1].variations,
+            vec![
+                (VariationAxis::WEIGHT, 700.0),
+                (VariationAxis::WIDTH, 100.0)
+            ]
+        );
+        assert_eq!(instances[0].properties.weight, Weight::THIN);
+        assert_eq!(instances[1].properties.weight, Weight::BOLD);
+        assert_eq!(instances[1].properties.stretch, Stretch::NORMAL);
+        assert!(instances.iter().all(|instance| instance.handle == handle));
+
+        // Loading an instance applies its coordinates.
+        let font: Font = instances[1].load_with().unwrap();
+        assert_eq!(font.properties(), instances[1].properties);
+
+        let handle = Handle::from_path(TEST_FONT_FILE_PATH.into(), 0);
+        assert!(handle.named_instances().unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_glyph_outlines_with_each_hinting_engine() {
+        let mut font = Font::from_path(TRUETYPE_FONT_PATHS[0], 0).unwrap();
+        let glyph = font.glyph_for_char('a').unwrap();
+        let outline = |font: &Font| {
+            let mut builder = OutlineBuilder::new();
+            font.outline(glyph, HintingOptions::Full(12.0), &mut builder)
+                .unwrap();
+            builder.into_outline()
+        };
+
+        let default_outline = outline(&font);
+        font.set_hinting_engine(HintingEngine::Bytecode);
+        assert_eq!(font.hinting_engine(), HintingEngine::Bytecode);
+        let bytecode_outline = outline(&font);
+        font.set_hinting_engine(HintingEngine::Autohinter);
+        let autohinted_outline = outline(&font);
+
+        // The font has instructions, which FreeType runs by default for full hinting.
+        assert_eq!(default_outline, bytecode_outline);
+        assert_ne!(bytecode_outline, autohinted_outline);
+        assert_eq!(
+            bytecode_outline.contours.len(),
+            autohinted_outline.contours.len()
+        );
+    }
+
+    #[test]
+    fn get_hinted_glyph_outlines_at_different_sizes() {
+        let font = Font::from_path(TRUETYPE_FONT_PATHS[0], 0).unwrap();
+        let glyph = font.glyph_for_char('a').unwrap();
+        let outline = |hinting| {
+            let mut builder = OutlineBuilder::new();
+            font.outline(glyph, hinting, &mut builder).unwrap();
+            builder.into_outline()
+        };
+
+        // Grid fitting snaps to a different pixel grid at each size, fractional sizes included.
+        let outline_10 = outline(HintingOptions::Full(10.0));
+        let outline_40 = outline(HintingOptions::Full(40.0));
+        assert_ne!(outline_10, outline_40);
+        assert_ne!(outline(HintingOptions::Full(10.5)), outline_10);
+        assert_ne!(
+            outline(HintingOptions::Vertical(40.0)),
+            outline(HintingOptions::None)
+        );
+        assert_eq!(outline(HintingOptions::Full(10.0)), outline_10);
+    }
+
+    #[test]
+    fn get_glyph_outline_after_variation_change() {
+        let mut font = Font::from_path(VARIABLE_FONT_PATH, 0).unwrap();
+        let glyph = font.glyph_for_char('I').unwrap();
+        let outline_max_x = |font: &Font, hinting| {
+            let mut builder = OutlineBuilder::new();
+            font.outline(glyph, hinting, &mut builder).unwrap();
+            builder
+                .into_outline()
+                .contours
+                .iter()
+                .flat_map(|contour| contour.positions.iter())
+                .fold(f32::MIN, |max_x, position| max_x.max(position.x()))
+        };
+        assert_eq!(outline_max_x(&font, HintingOptions::None), 300.0);
+        assert_eq!(font.typographic_bounds(glyph).unwrap().max_x(), 300.0);
+        let hinted_max_x = outline_max_x(&font, HintingOptions::Full(20.0));
+
+        // The `gvar` table moves the right edge of the glyph as the weight grows, so cached
+        // outlines from the default instance must not be reused.
+        font.set_variations(&[(VariationAxis::WEIGHT, 650.0)])
+            .unwrap();
+        assert_eq!(outline_max_x(&font, HintingOptions::None), 400.0);
+        assert_eq!(font.typographic_bounds(glyph).unwrap().max_x(), 400.0);
+        font.set_variations(&[(VariationAxis::WEIGHT, 900.0)])
+            .unwrap();
+        assert_eq!(outline_max_x(&font, HintingOptions::None), 500.0);
+        assert_eq!(font.typographic_bounds(glyph).unwrap().max_x(), 500.0);
+        assert!(outline_max_x(&font, HintingOptions::Full(20.0)) > hinted_max_x);
+
+        font.set_variations(&[]).unwrap();
+        assert_eq!(outline_max_x(&font, HintingOptions::None), 300.0);
+    }
+
+    #[test]
+    fn get_cached_metrics_after_variation_change() {
+        let mut font = Font::from_path(VARIABLE_FONT_PATH, 0).unwrap();
+        let default_metrics = font.metrics();
+        assert_eq!(default_metrics.x_height, 500.0);
+        assert_eq!(font.metrics().x_height, default_metrics.x_height);
+
+        // The `MVAR` table raises the x-height at the heaviest weight.
+        font.set_variations(&[(VariationAxis::WEIGHT, 900.0)])
+            .unwrap();
+        assert_eq!(font.metrics().x_height, 600.0);
+
+        font.set_variations(&[]).unwrap();
+        assert_eq!(font.metrics().x_height, default_metrics.x_height);
+    }
+
+    #[test]
+    fn get_outlines_and_metrics_of_clones_after_variation_change() {
+        let mut font = Font::from_path(VARIABLE_FONT_PATH, 0).unwrap();
+        let glyph = font.glyph_for_char('I').unwrap();
+        let outline_max_x = |font: &Font| {
+            let mut builder = OutlineBuilder::new();
+            font.outline(glyph, HintingOptions::None, &mut builder)
+                .unwrap();
+            builder
+                .into_outline()
+                .contours
+                .iter()
+                .flat_map(|contour| contour.positions.iter())
+                .fold(f32::MIN, |max_x, position| max_x.max(position.x()))
+        };
+
+        // Fill the caches of the default instance before cloning.
+        assert_eq!(outline_max_x(&font), 300.0);
+        assert_eq!(font.metrics().x_height, 500.0);
+        let clone = font.clone();
+        assert_eq!(clone, font);
+
+        // The clone shares the face, so it sees the new coordinates, and must not keep the
+        // outlines and metrics of the old ones.
+        font.set_variations(&[(VariationAxis::WEIGHT, 900.0)])
+            .unwrap();
+        for font in &[&font, &clone] {
+            assert_eq!(outline_max_x(font), 500.0);
+            assert_eq!(font.metrics().x_height, 600.0);
+            assert_eq!(font.properties().weight, Weight::BLACK);
+        }
+        assert_eq!(clone, font);
+
+        // A separately loaded copy of the default instance differs from both.
+        let default_instance = Font::from_path(VARIABLE_FONT_PATH, 0).unwrap();
+        assert_eq!(outline_max_x(&default_instance), 300.0);
+        assert_ne!(default_instance, font);
+    }
+
+    #[test]
+    fn evict_least_recently_used_outlines() {
+        let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+        assert!(font.glyph_count() as usize > OUTLINE_CACHE_CAPACITY + 1);
+        let load_outline = |glyph_id| {
+            let mut builder = OutlineBuilder::new();
+            font.outline(glyph_id, HintingOptions::None, &mut builder)
+                .unwrap();
+            builder.into_outline()
+        };
+        let first_outline = load_outline(0);
+        for glyph_id in 1..(OUTLINE_CACHE_CAPACITY as u32) {
+            load_outline(glyph_id);
+        }
+        assert_eq!(font.outline_cache.borrow().len(), OUTLINE_CACHE_CAPACITY);
+
+        // Using glyph 0 again makes glyph 1 the least recently used, so it's evicted instead.
+        assert_eq!(load_outline(0), first_outline);
+        load_outline(OUTLINE_CACHE_CAPACITY as u32);
+        let key = |glyph_id| (glyph_id, OutlineCacheHinting::None, HintingEngine::Default);
+        let mut outline_cache = font.outline_cache.borrow_mut();
+        assert_eq!(outline_cache.len(), OUTLINE_CACHE_CAPACITY);
+        assert!(outline_cache.get(&key(0)).is_some());
+        assert!(outline_cache.get(&key(1)).is_none());
+        assert!(outline_cache
+            .get(&key(OUTLINE_CACHE_CAPACITY as u32))
+            .is_some());
+    }
+
+    #[test]
+    fn supports_hinting_options_matrix() {
+        let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+        let expected = [
+            (HintingOptions::None, true, true),
+            (HintingOptions::Vertical(12.0), true, true),
+            (HintingOptions::VerticalSubpixel(12.0), true, false),
+            (HintingOptions::Full(12.0), true, true),
+        ];
+        for &(hinting_options, for_rasterization, for_outlines) in &expected {
+            assert_eq!(
+                font.supports_hinting_options(hinting_options, true),
+                for_rasterization
+            );
+            assert_eq!(
+                font.supports_hinting_options(hinting_options, false),
+                for_outlines
+            );
+        }
+    }
+
+    #[test]
+    fn stem_darkening_thickens_thin_strokes() {
+        let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+        let glyph_id = font.glyph_for_char('l').unwrap();
+        let transform = Transform2F::from_translation(Vector2F::new(4.0, 16.0));
+        let coverage = |settings: RasterizationOptionsBuilder| {
+            let mut canvas = Canvas::new(Vector2I::splat(24), Format::A8);
+            font.rasterize_glyph_with_settings(
+                &mut canvas,
+                glyph_id,
+                12.0,
+                transform,
+                &settings.build(),
+            )
+            .unwrap();
+            canvas.pixels.iter().map(|&pixel| pixel as u32).sum::<u32>()
+        };
+
+        let default = coverage(RasterizationOptionsBuilder::new());
+        let disabled = coverage(RasterizationOptionsBuilder::new().stem_darkening(false));
+        let enabled = coverage(RasterizationOptionsBuilder::new().stem_darkening(true));
+        let stronger = coverage(
+            RasterizationOptionsBuilder::new()
+                .stem_darkening(true)
+                .stem_darkening_strength(2.0),
+        );
+        assert!(enabled > disabled);
+        assert!(stronger > enabled);
+
+        // The override only lasts for a single render.
+        assert_eq!(coverage(RasterizationOptionsBuilder::new()), default);
+    }
+
+    #[test]
+    fn select_symbol_charmap() {
+        let font = Font::from_path(SYMBOL_FONT_PATH, 0).unwrap();
+        assert_eq!(font.charmaps(), vec![(3, 0), (3, 1)]);
+
+        // FreeType prefers the Unicode charmap, which doesn't have the symbol.
+        assert_eq!(font.glyph_for_char('A'), Some(1));
+        assert_eq!(font.glyph_for_char('\u{f04a}'), None);
+
+        // The smiley face is at 'J' in Wingdings, and so in the Private Use Area at U+F04A.
+        assert!(font.select_charmap(3, 0));
+        assert_eq!(font.glyph_for_char('\u{f04a}'), Some(1));
+        assert_eq!(font.glyph_for_char('J'), Some(1));
+        assert_eq!(font.glyph_for_char('A'), None);
+
+        assert!(!font.select_charmap(1, 0));
+        assert_eq!(font.glyph_for_char('J'), Some(1));
+        assert!(font.select_charmap(3, 1));
+        assert_eq!(font.glyph_for_char('J'), None);
+    }
+
+    #[test]
+    fn load_cached_font_tables() {
+        let font = Font::from_path(TRUETYPE_FONT_PATHS[0], 0).unwrap();
+        let head = font.load_font_table(tables::TAG_HEAD).unwrap();
+        assert!(font.table_cache.borrow().contains_key(&tables::TAG_HEAD));
+        assert_eq!(font.load_font_table(tables::TAG_HEAD).unwrap(), head);
+        assert_eq!(
+            unsafe { load_sfnt_table(font.freetype_face, tables::TAG_HEAD) }.unwrap(),
+            head
+        );
+        assert_eq!(font.table_len(tables::TAG_HEAD), Some(head.len()));
+
+        // Large tables aren't kept, but missing ones are remembered.
+        let glyf = font.load_font_table(tables::TAG_GLYF).unwrap();
+        assert!(glyf.len() > TABLE_CACHE_MAX_LEN);
+        assert!(!font.table_cache.borrow().contains_key(&tables::TAG_GLYF));
+        assert_eq!(font.load_font_table(tables::TAG_GLYF).unwrap(), glyf);
+        let missing_tag = u32::from_be_bytes(*b"zzzz");
+        assert_eq!(font.load_font_table(missing_tag), None);
+        assert_eq!(font.table_cache.borrow().get(&missing_tag), Some(&None));
+        assert_eq!(font.table_len(missing_tag), None);
+    }
+
+    #[test]
+    fn cached_glyph_for_char_matches_lookup() {
+        let font = Font::from_path(TRUETYPE_FONT_PATHS[0], 0).unwrap();
+        // 'A' and U+0141 share a cache slot, and U+10FFFF isn't mapped.
+        let characters = "Hello, world! A\u{141}A\u{141} \u{10ffff}\u{10ffff} Hello";
+        for _ in 0..2 {
+            for character in characters.chars() {
+                assert_eq!(
+                    font.glyph_for_char(character),
+                    font.lookup_glyph_for_char(character),
+                    "{:?}",
+                    character
+                );
+            }
+        }
+        assert!(font.glyph_for_char('\u{141}').is_some());
+        assert_eq!(font.glyph_for_char('\u{10ffff}'), None);
+
+        // Selecting a charmap clears the cache shared with clones.
+        let font = Font::from_path(SYMBOL_FONT_PATH, 0).unwrap();
+        let clone = font.clone();
+        assert_eq!(clone.glyph_for_char('J'), None);
+        assert!(font.select_charmap(3, 0));
+        assert_eq!(clone.glyph_for_char('J'), Some(1));
+    }
+
+    #[test]
+    fn post_glyph_names_match_freetype() {
+        for path in &TRUETYPE_FONT_PATHS {
+            let font = Font::from_path(path, 0).unwrap();
+            let post = font.load_font_table(tables::TAG_POST).unwrap();
+            for glyph_id in 0..font.glyph_count() {
+                assert_eq!(
+                    tables::post_glyph_name(&post, glyph_id),
+                    font.glyph_name(glyph_id)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn copy_font_data_from_native_font() {
+        let path = CString::new(TEST_FONT_FILE_PATH).unwrap();
+        let font = FREETYPE_LIBRARY.with(|freetype_library| unsafe {
+            let mut freetype_face = ptr::null_mut();
+            assert_eq!(
+                FT_New_Face(freetype_library.0, path.as_ptr(), 0, &mut freetype_face),
+                0
+            );
+            let font = Font::from_native_font(freetype_face);
+            assert_eq!(FT_Done_Face(freetype_face), 0);
+            font
+        });
+        assert_eq!(
+            *font.copy_font_data().unwrap(),
+            fs::read(TEST_FONT_FILE_PATH).unwrap()
+        );
+        assert_eq!(font.postscript_name().unwrap(), "EBGaramond12-Regular");
+    }
+
+    #[test]
+    fn compare_fonts_without_data() {
+        let font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+        let other_font = Font::from_path(TEST_FONT_FILE_PATH, 0).unwrap();
+        let (shared, also_shared, other_shared) = unsafe {
+            (
+                Font::from_shared_face(font.freetype_face),
+                Font::from_shared_face(font.freetype_face),
+                Font::from_shared_face(other_font.freetype_face),
+            )
+        };
+        assert!(shared.copy_font_data().is_none());
+        assert_eq!(font, other_font);
+
+        // Without data, only fonts sharing a face are equal.
+        assert_eq!(shared, also_shared);
+        assert_ne!(shared, other_shared);
+        assert_ne!(shared, font);
+        let hash = |font: &Font| {
+            let mut hasher = DefaultHasher::new();
+            font.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&shared), hash(&also_shared));
+    }
+
+    #[test]
+    fn reassemble_sfnt_from_tables() {
+        for path in TRUETYPE_FONT_PATHS.iter().chain(&[TEST_FONT_FILE_PATH]) {
+            let font = Font::from_path(path, 0).unwrap();
+            let font_data = unsafe { super::reassemble_sfnt(font.freetype_face) }.unwrap();
+            let reassembled_font = Font::from_bytes(Arc::new(font_data), 0).unwrap();
+            assert_eq!(reassembled_font.postscript_name(), font.postscript_name());
+            assert_eq!(reassembled_font.glyph_count(), font.glyph_count());
+            let glyph = font.glyph_for_char('a').unwrap();
+            assert_eq!(reassembled_font.glyph_for_char('a'), Some(glyph));
+            assert_eq!(
+                reassembled_font.typographic_bounds(glyph).unwrap(),
+                font.typographic_bounds(glyph).unwrap()
+            );
+        }
+
+        // Only SFNT fonts can be reassembled.
+        let font = Font::from_path(PCF_FONT_PATH, 0).unwrap();
+        assert!(unsafe { super::reassemble_sfnt(font.freetype_face) }.is_none());
+    }
 }
@@ -20,11 +20,21 @@ pub use crate::loaders::core_text as default;
 pub use crate::loaders::directwrite as default;
 
 #[cfg(any(
-    not(any(target_os = "macos", target_os = "ios", target_family = "windows", target_arch = "wasm32")),
+    all(
+        not(any(target_os = "macos", target_os = "ios", target_family = "windows", target_arch = "wasm32")),
+        not(feature = "pure-rust")
+    ),
     feature = "loader-freetype-default"
 ))]
 pub use crate::loaders::freetype as default;
 
+#[cfg(all(
+    not(any(target_os = "macos", target_os = "ios", target_family = "windows", target_arch = "wasm32")),
+    feature = "pure-rust",
+    not(feature = "loader-freetype-default")
+))]
+pub use crate::loaders::pure_rust as default;
+
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 pub mod core_text;
 
@@ -32,7 +42,10 @@ pub mod core_text;
 pub mod directwrite;
 
 #[cfg(any(
-    not(any(target_os = "macos", target_os = "ios", target_family = "windows", target_arch = "wasm32")),
+    all(
+        not(any(target_os = "macos", target_os = "ios", target_family = "windows", target_arch = "wasm32")),
+        not(feature = "pure-rust")
+    ),
     feature = "loader-freetype"
 ))]
 pub mod freetype;
@@ -40,5 +53,8 @@ pub mod freetype;
 #[cfg(target_arch = "wasm32")]
 pub use crate::loaders::web as default;
 
+#[cfg(feature = "pure-rust")]
+pub mod pure_rust;
+
 #[cfg(target_arch = "wasm32")]
 pub mod web;
\ No newline at end of file
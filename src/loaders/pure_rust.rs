@@ -0,0 +1,800 @@
+// font-kit/src/loaders/pure_rust.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A cross-platform loader that parses fonts in pure Rust with the `ttf-parser` crate.
+//!
+//! This loader needs no system libraries, which suits WebAssembly and other builds that can't
+//! link FreeType. It supports TrueType and OpenType fonts and collections only. It can map
+//! characters, measure glyphs, and return unhinted outlines, but it can't hint or rasterize
+//! glyphs: rasterization returns `GlyphLoadingError::NotImplemented`.
+//!
+//! Build it with the `pure-rust` Cargo feature. On platforms where FreeType is the default loader,
+//! the feature makes this loader the default instead, so FreeType needn't be linked.
+
+use log::warn;
+use pathfinder_geometry::line_segment::LineSegment2F;
+use pathfinder_geometry::rect::{RectF, RectI};
+use pathfinder_geometry::transform2d::Transform2F;
+use pathfinder_geometry::vector::{Vector2F, Vector2I};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::slice;
+use std::sync::Arc;
+use ttf_parser::{Face, FaceParsingError, GlyphId, Tag};
+
+use crate::canvas::{Canvas, RasterizationOptions};
+use crate::error::{FontLoadingError, GlyphLoadingError};
+use crate::file_type::FileType;
+use crate::handle::{self, Handle};
+use crate::hinting::HintingOptions;
+use crate::loader::{self, FallbackResult, Loader};
+use crate::metrics::Metrics;
+use crate::outline::OutlineSink;
+use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::tables;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+const TT_NAME_ID_FAMILY_NAME: u16 = 1;
+const TT_NAME_ID_FULL_NAME: u16 = 4;
+const TT_NAME_ID_POSTSCRIPT_NAME: u16 = 6;
+const TT_NAME_ID_TYPOGRAPHIC_FAMILY: u16 = 16;
+
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{fe0f}';
+
+/// This loader has no native font type of its own, so fonts stand in for themselves.
+pub type NativeFont = Font;
+
+/// A font parsed in pure Rust.
+///
+/// The font is parsed once, when it's loaded, and clones share the result. `ttf-parser` reads the
+/// tables in place, so the font data isn't copied.
+#[derive(Clone)]
+pub struct Font {
+    parsed_face: Arc<ParsedFace>,
+    font_index: u32,
+}
+
+// A `ttf-parser` face together with the data that it borrows.
+struct ParsedFace {
+    // Declared before `font_data` so that it's dropped first.
+    face: Face<'static>,
+    font_data: Arc<Vec<u8>>,
+}
+
+impl ParsedFace {
+    fn parse(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<ParsedFace, FaceParsingError> {
+        // The face points into the heap buffer of the vector, which stays put for as long as
+        // `font_data` keeps it alive. The data behind an `Arc` is never mutated.
+        let data: &'static [u8] =
+            unsafe { slice::from_raw_parts(font_data.as_ptr(), font_data.len()) };
+        let face = Face::parse(data, font_index)?;
+        Ok(ParsedFace { face, font_data })
+    }
+}
+
+impl Font {
+    /// Loads a font from raw font data (the contents of a `.ttf`/`.otf`/etc. file).
+    ///
+    /// If the data represents a collection (`.ttc`/`.otc`/etc.), `font_index` specifies the index
+    /// of the font to load from it. If the data represents a single font, pass 0 for `font_index`.
+    pub fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Font, FontLoadingError> {
+        let parsed_face =
+            ParsedFace::parse(font_data, font_index).map_err(|error| match error {
+                FaceParsingError::UnknownMagic => FontLoadingError::UnknownFormat,
+                FaceParsingError::FaceIndexOutOfBounds => FontLoadingError::NoSuchFontInCollection,
+                _ => FontLoadingError::Parse,
+            })?;
+        Ok(Font {
+            parsed_face: Arc::new(parsed_face),
+            font_index,
+        })
+    }
+
+    /// Loads a font from a `.ttf`/`.otf`/etc. file.
+    ///
+    /// If the file is a collection (`.ttc`/`.otc`/etc.), `font_index` specifies the index of the
+    /// font to load from it. If the file represents a single font, pass 0 for `font_index`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(file: &mut File, font_index: u32) -> Result<Font, FontLoadingError> {
+        file.seek(SeekFrom::Start(0))?;
+        let mut font_data = vec![];
+        file.read_to_end(&mut font_data)?;
+        Font::from_bytes(Arc::new(font_data), font_index)
+    }
+
+    /// Loads a font from the path to a `.ttf`/`.otf`/etc. file.
+    ///
+    /// If the file is a collection (`.ttc`/`.otc`/etc.), `font_index` specifies the index of the
+    /// font to load from it. If the file represents a single font, pass 0 for `font_index`.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path<P: AsRef<Path>>(path: P, font_index: u32) -> Result<Font, FontLoadingError> {
+        <Font as Loader>::from_path(path, font_index)
+    }
+
+    /// Creates a font from a native API handle, which for this loader is the font itself.
+    ///
+    /// # Safety
+    ///
+    /// This is always safe; it's only `unsafe` to match the other loaders.
+    #[inline]
+    pub unsafe fn from_native_font(native_font: NativeFont) -> Font {
+        native_font
+    }
+
+    /// Loads the font pointed to by a handle.
+    #[inline]
+    pub fn from_handle(handle: &Handle) -> Result<Self, FontLoadingError> {
+        <Self as Loader>::from_handle(handle)
+    }
+
+    /// Determines whether a blob of raw font data represents a supported font, and, if so, what
+    /// type of font it is.
+    pub fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
+        if let Some(font_count) = ttf_parser::fonts_in_collection(&font_data) {
+            return Ok(FileType::Collection(font_count));
+        }
+        match Face::parse(&font_data, 0) {
+            Ok(_) => Ok(FileType::Single),
+            Err(_) => Err(FontLoadingError::UnknownFormat),
+        }
+    }
+
+    /// Determines whether a file represents a supported font, and, if so, what type of font it is.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn analyze_file(file: &mut File) -> Result<FileType, FontLoadingError> {
+        file.seek(SeekFrom::Start(0))?;
+        let mut font_data = vec![];
+        file.read_to_end(&mut font_data)?;
+        Font::analyze_bytes(Arc::new(font_data))
+    }
+
+    /// Determines whether a path points to a supported font, and, if so, what type of font it is.
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn analyze_path<P: AsRef<Path>>(path: P) -> Result<FileType, FontLoadingError> {
+        <Self as Loader>::analyze_path(path)
+    }
+
+    /// Returns the wrapped native font handle, which for this loader is a copy of the font.
+    #[inline]
+    pub fn native_font(&self) -> NativeFont {
+        self.clone()
+    }
+
+    #[inline]
+    fn face(&self) -> &Face<'_> {
+        &self.parsed_face.face
+    }
+
+    fn name_string(&self, name_id: u16) -> Option<String> {
+        self.load_font_table(tables::TAG_NAME)
+            .and_then(|name| tables::name_string(&name, name_id))
+    }
+
+    /// Returns the PostScript name of the font. This should be globally unique.
+    #[inline]
+    pub fn postscript_name(&self) -> Option<String> {
+        self.name_string(TT_NAME_ID_POSTSCRIPT_NAME)
+    }
+
+    /// Returns the full name of the font (also known as "display name" on macOS).
+    #[inline]
+    pub fn full_name(&self) -> String {
+        self.name_string(TT_NAME_ID_FULL_NAME)
+            .unwrap_or_else(|| self.family_name())
+    }
+
+    /// Returns the name of the font family.
+    ///
+    /// Like FreeType, this prefers the typographic family name, which groups weights and widths
+    /// that the legacy family name splits apart.
+    pub fn family_name(&self) -> String {
+        self.name_string(TT_NAME_ID_TYPOGRAPHIC_FAMILY)
+            .or_else(|| self.name_string(TT_NAME_ID_FAMILY_NAME))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if and only if the font is monospace (fixed-width).
+    #[inline]
+    pub fn is_monospace(&self) -> bool {
+        self.face().is_monospaced()
+    }
+
+    /// Returns the values of various font properties, corresponding to those defined in CSS.
+    pub fn properties(&self) -> Properties {
+        let face = self.face();
+        let style = if face.is_oblique() {
+            Style::oblique_from_italic_angle(face.italic_angle())
+        } else if face.is_italic() {
+            Style::Italic
+        } else {
+            Style::Normal
+        };
+        let stretch = match face.width().to_number() {
+            width @ 1..=9 => Stretch(Stretch::MAPPING[width as usize - 1]),
+            _ => Stretch::NORMAL,
+        };
+        Properties {
+            style,
+            weight: Weight(face.weight().to_number() as f32),
+            stretch,
+        }
+    }
+
+    /// Returns the usual glyph ID for a Unicode character.
+    ///
+    /// Be careful with this function; typographically correct character-to-glyph mapping must be
+    /// done using a *shaper* such as HarfBuzz. This function is only useful for best-effort simple
+    /// use cases like "what does character X look like on its own".
+    ///
+    /// As with FreeType, if the character is displayed as an emoji by default and the font maps it
+    /// to both a text and an emoji glyph through a format 14 `cmap` subtable, the emoji glyph,
+    /// selected by U+FE0F, is preferred.
+    pub fn glyph_for_char(&self, character: char) -> Option<u32> {
+        let face = self.face();
+        let emoji_glyph = if loader::has_emoji_presentation(character) {
+            face.glyph_variation_index(character, EMOJI_PRESENTATION_SELECTOR)
+        } else {
+            None
+        };
+        emoji_glyph
+            .or_else(|| face.glyph_index(character))
+            .filter(|glyph_id| glyph_id.0 != 0)
+            .map(|glyph_id| glyph_id.0 as u32)
+    }
+
+    /// Returns the glyph ID for the specified glyph name.
+    ///
+    /// `.notdef`, `.null`, and `nonmarkingreturn` also resolve by convention, as
+    /// `Loader::glyph_by_name()` describes.
+    #[inline]
+    pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
+        self.face()
+            .glyph_index_by_name(name)
+            .map(|glyph_id| glyph_id.0 as u32)
+            .or_else(|| loader::conventional_glyph_by_name(self, name))
+    }
+
+    /// Returns the name of the glyph with the given ID, the reverse of `glyph_by_name()`.
+    ///
+    /// Names come from the `post` table or, for CFF fonts, the glyph charset. Returns `None` if the
+    /// font doesn't name its glyphs.
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        let face = self.face();
+        let glyph_id = self.glyph_id(face, glyph_id).ok()?;
+        face.glyph_name(glyph_id).map(|name| name.to_owned())
+    }
+
+    /// Returns the number of glyphs in the font.
+    ///
+    /// Glyph IDs range from 0 inclusive to this value exclusive.
+    #[inline]
+    pub fn glyph_count(&self) -> u32 {
+        self.face().number_of_glyphs() as u32
+    }
+
+    fn glyph_id(&self, face: &Face, glyph_id: u32) -> Result<GlyphId, GlyphLoadingError> {
+        if glyph_id < face.number_of_glyphs() as u32 {
+            Ok(GlyphId(glyph_id as u16))
+        } else {
+            Err(GlyphLoadingError::NoSuchGlyph)
+        }
+    }
+
+    /// Sends the vector path for a glyph to a path builder.
+    ///
+    /// This loader can't hint, so `hinting_mode` is ignored and the unhinted outline is always
+    /// sent. Points are in font units, with y pointing up. Glyphs without an outline, such as
+    /// spaces, send nothing.
+    pub fn outline<S>(
+        &self,
+        glyph_id: u32,
+        _: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        let face = self.face();
+        let glyph_id = self.glyph_id(face, glyph_id)?;
+        let mut adapter = OutlineSinkAdapter {
+            sink,
+            start: Vector2F::default(),
+            pending_line: None,
+        };
+        face.outline_glyph(glyph_id, &mut adapter);
+        adapter.flush_pending_line();
+        Ok(())
+    }
+
+    /// Returns the boundaries of a glyph in font units.
+    pub fn typographic_bounds(&self, glyph_id: u32) -> Result<RectF, GlyphLoadingError> {
+        let face = self.face();
+        let glyph_id = self.glyph_id(face, glyph_id)?;
+        Ok(match face.glyph_bounding_box(glyph_id) {
+            Some(rect) => RectI::from_points(
+                Vector2I::new(rect.x_min as i32, rect.y_min as i32),
+                Vector2I::new(rect.x_max as i32, rect.y_max as i32),
+            )
+            .to_f32(),
+            None => RectF::default(),
+        })
+    }
+
+    /// Returns the distance from the origin of the glyph with the given ID to the next, in font
+    /// units.
+    pub fn advance(&self, glyph_id: u32) -> Result<Vector2F, GlyphLoadingError> {
+        let face = self.face();
+        let glyph_id = self.glyph_id(face, glyph_id)?;
+        let advance = face.glyph_hor_advance(glyph_id).unwrap_or(0);
+        Ok(Vector2F::new(advance as f32, 0.0))
+    }
+
+    /// Returns the amount that the given glyph should be displaced from the origin.
+    pub fn origin(&self, _: u32) -> Result<Vector2F, GlyphLoadingError> {
+        warn!("unimplemented");
+        Ok(Vector2F::default())
+    }
+
+    /// Retrieves various metrics that apply to the entire font.
+    pub fn metrics(&self) -> Metrics {
+        let face = self.face();
+        let underline = face.underline_metrics();
+        let bounding_box = face.global_bounding_box();
+        let [caret_slope_rise, caret_slope_run, caret_offset] = self
+            .load_font_table(tables::TAG_HHEA)
+            .and_then(|hhea| tables::hhea_caret(&hhea))
+            .unwrap_or([1, 0, 0]);
+        let [typo_line_gap, win_ascent, win_descent] = self
+            .load_font_table(tables::TAG_OS_2)
+            .and_then(|os2| tables::os2_leading_metrics(&os2))
+            .unwrap_or([0.0; 3]);
+
+        let mut metrics = Metrics {
+            units_per_em: face.units_per_em() as u32,
+            ascent: face.ascender() as f32,
+            descent: face.descender() as f32,
+            line_gap: face.line_gap() as f32,
+            underline_position: underline.map_or(0.0, |underline| underline.position as f32),
+            underline_thickness: underline.map_or(0.0, |underline| underline.thickness as f32),
+            cap_height: face.capital_height().unwrap_or(0) as f32,
+            x_height: face.x_height().unwrap_or(0) as f32,
+            bounding_box: RectI::from_points(
+                Vector2I::new(bounding_box.x_min as i32, bounding_box.y_min as i32),
+                Vector2I::new(bounding_box.x_max as i32, bounding_box.y_max as i32),
+            )
+            .to_f32(),
+            caret_slope_rise: caret_slope_rise as f32,
+            caret_slope_run: caret_slope_run as f32,
+            caret_offset: caret_offset as f32,
+            typo_line_gap,
+            win_ascent,
+            win_descent,
+        };
+        loader::measure_missing_heights(self, &mut metrics);
+        metrics
+    }
+
+    /// Returns a handle to this font, if possible.
+    ///
+    /// This is useful if you want to open the font with a different loader.
+    #[inline]
+    pub fn handle(&self) -> Option<Handle> {
+        Some(Handle::from_memory(
+            self.parsed_face.font_data.clone(),
+            self.font_index,
+        ))
+    }
+
+    /// Attempts to return the raw font data (contents of the font file).
+    ///
+    /// If this font is a member of a collection, this function returns the data for the entire
+    /// collection.
+    #[inline]
+    pub fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
+        Some(self.parsed_face.font_data.clone())
+    }
+
+    /// Returns true if and only if the font loader can perform hinting in the requested way.
+    ///
+    /// This loader can't hint, so only `HintingOptions::None` is supported, and only for outlines.
+    #[inline]
+    pub fn supports_hinting_options(
+        &self,
+        hinting_options: HintingOptions,
+        for_rasterization: bool,
+    ) -> bool {
+        hinting_options == HintingOptions::None && !for_rasterization
+    }
+
+    /// Returns the pixel boundaries that the glyph would take up when rendered at the given size
+    /// and origin.
+    ///
+    /// This loader can't rasterize, so the bounds are estimated from the unhinted outline.
+    #[inline]
+    pub fn raster_bounds(
+        &self,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<RectI, GlyphLoadingError> {
+        <Self as Loader>::raster_bounds(
+            self,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Would rasterize a glyph to a canvas, but this loader can't rasterize, so this always returns
+    /// `GlyphLoadingError::NotImplemented`.
+    #[inline]
+    pub fn rasterize_glyph(
+        &self,
+        _: &mut Canvas,
+        _: u32,
+        _: f32,
+        _: Transform2F,
+        _: HintingOptions,
+        _: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        Err(GlyphLoadingError::NotImplemented)
+    }
+
+    /// Would rasterize a glyph to a canvas, but this loader can't rasterize, so this always returns
+    /// `GlyphLoadingError::NotImplemented`.
+    pub fn rasterize_glyph_clipped(
+        &self,
+        _: &mut Canvas,
+        _: u32,
+        _: f32,
+        _: Transform2F,
+        _: HintingOptions,
+        _: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        Err(GlyphLoadingError::NotImplemented)
+    }
+
+    /// Returns the raw contents of the OpenType table with the given tag.
+    ///
+    /// Tags are four-character codes. A list of tags can be found in the [OpenType specification].
+    ///
+    /// [OpenType specification]: https://docs.microsoft.com/en-us/typography/opentype/spec/
+    pub fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
+        self.face()
+            .raw_face()
+            .table(Tag(table_tag))
+            .map(|table| table.into())
+    }
+
+    /// Returns the bytes in `range` of the OpenType table with the given tag, if the table exists
+    /// and is at least `range.end` bytes long.
+    ///
+    /// Only the requested bytes are copied out of the font.
+    pub fn font_table_range(&self, table_tag: u32, range: Range<usize>) -> Option<Box<[u8]>> {
+        self.face()
+            .raw_face()
+            .table(Tag(table_tag))?
+            .get(range)
+            .map(Box::from)
+    }
+
+    /// Returns the length in bytes of the OpenType table with the given tag, if the table exists.
+    pub fn table_len(&self, table_tag: u32) -> Option<usize> {
+        self.face()
+            .raw_face()
+            .table(Tag(table_tag))
+            .map(|table| table.len())
+    }
+
+    /// Get font fallback results for the given text and locale.
+    ///
+    /// This loader has no system font database to fall back to, so the result is always empty.
+    fn get_fallbacks(&self, text: &str, _: &str) -> FallbackResult<Font> {
+        warn!("unsupported");
+        FallbackResult {
+            fonts: vec![],
+            valid_len: text.len(),
+        }
+    }
+}
+
+impl Loader for Font {
+    type NativeFont = NativeFont;
+
+    #[inline]
+    fn from_bytes(font_data: Arc<Vec<u8>>, font_index: u32) -> Result<Self, FontLoadingError> {
+        Font::from_bytes(font_data, font_index)
+    }
+
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from_file(file: &mut File, font_index: u32) -> Result<Font, FontLoadingError> {
+        Font::from_file(file, font_index)
+    }
+
+    #[inline]
+    unsafe fn from_native_font(native_font: Self::NativeFont) -> Self {
+        Font::from_native_font(native_font)
+    }
+
+    #[inline]
+    fn analyze_bytes(font_data: Arc<Vec<u8>>) -> Result<FileType, FontLoadingError> {
+        Font::analyze_bytes(font_data)
+    }
+
+    #[inline]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn analyze_file(file: &mut File) -> Result<FileType, FontLoadingError> {
+        Font::analyze_file(file)
+    }
+
+    #[inline]
+    fn native_font(&self) -> Self::NativeFont {
+        self.native_font()
+    }
+
+    #[inline]
+    fn postscript_name(&self) -> Option<String> {
+        self.postscript_name()
+    }
+
+    #[inline]
+    fn full_name(&self) -> String {
+        self.full_name()
+    }
+
+    #[inline]
+    fn family_name(&self) -> String {
+        self.family_name()
+    }
+
+    #[inline]
+    fn is_monospace(&self) -> bool {
+        self.is_monospace()
+    }
+
+    #[inline]
+    fn properties(&self) -> Properties {
+        self.properties()
+    }
+
+    #[inline]
+    fn glyph_for_char(&self, character: char) -> Option<u32> {
+        self.glyph_for_char(character)
+    }
+
+    #[inline]
+    fn glyph_by_name(&self, name: &str) -> Option<u32> {
+        self.glyph_by_name(name)
+    }
+
+    #[inline]
+    fn glyph_name(&self, glyph_id: u32) -> Option<String> {
+        self.glyph_name(glyph_id)
+    }
+
+    #[inline]
+    fn glyph_count(&self) -> u32 {
+        self.glyph_count()
+    }
+
+    #[inline]
+    fn outline<S>(
+        &self,
+        glyph_id: u32,
+        hinting_mode: HintingOptions,
+        sink: &mut S,
+    ) -> Result<(), GlyphLoadingError>
+    where
+        S: OutlineSink,
+    {
+        self.outline(glyph_id, hinting_mode, sink)
+    }
+
+    #[inline]
+    fn typographic_bounds(&self, glyph_id: u32) -> Result<RectF, GlyphLoadingError> {
+        self.typographic_bounds(glyph_id)
+    }
+
+    #[inline]
+    fn advance(&self, glyph_id: u32) -> Result<Vector2F, GlyphLoadingError> {
+        self.advance(glyph_id)
+    }
+
+    #[inline]
+    fn origin(&self, origin: u32) -> Result<Vector2F, GlyphLoadingError> {
+        self.origin(origin)
+    }
+
+    #[inline]
+    fn metrics(&self) -> Metrics {
+        self.metrics()
+    }
+
+    #[inline]
+    fn handle(&self) -> Option<Handle> {
+        self.handle()
+    }
+
+    #[inline]
+    fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
+        self.copy_font_data()
+    }
+
+    #[inline]
+    fn supports_hinting_options(
+        &self,
+        hinting_options: HintingOptions,
+        for_rasterization: bool,
+    ) -> bool {
+        self.supports_hinting_options(hinting_options, for_rasterization)
+    }
+
+    #[inline]
+    fn rasterize_glyph(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    #[inline]
+    fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_clipped(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    #[inline]
+    fn get_fallbacks(&self, text: &str, locale: &str) -> FallbackResult<Self> {
+        self.get_fallbacks(text, locale)
+    }
+
+    #[inline]
+    fn load_font_table(&self, table_tag: u32) -> Option<Box<[u8]>> {
+        self.load_font_table(table_tag)
+    }
+
+    #[inline]
+    fn font_table_range(&self, table_tag: u32, range: Range<usize>) -> Option<Box<[u8]>> {
+        self.font_table_range(table_tag, range)
+    }
+
+    #[inline]
+    fn table_len(&self, table_tag: u32) -> Option<usize> {
+        self.table_len(table_tag)
+    }
+}
+
+/// Fonts are equal if they come from the same face of the same font data. Font data is compared in
+/// full, but only once its length and start, which holds the table directory, match.
+impl PartialEq for Font {
+    fn eq(&self, other: &Font) -> bool {
+        self.font_index == other.font_index
+            && handle::font_data_matches(&self.parsed_face.font_data, &other.parsed_face.font_data)
+    }
+}
+
+impl Eq for Font {}
+
+impl Hash for Font {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        handle::hash_font_data(state, &self.parsed_face.font_data, self.font_index);
+    }
+}
+
+impl Debug for Font {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        self.family_name().fmt(fmt)
+    }
+}
+
+// Forwards `ttf-parser` outline commands to an `OutlineSink`.
+//
+// `ttf-parser` closes contours that end in a straight line with an explicit line back to the
+// start point. The other loaders leave that line implied, so the adapter holds each line back
+// until it knows whether the contour closes right after it.
+struct OutlineSinkAdapter<'a, S>
+where
+    S: OutlineSink,
+{
+    sink: &'a mut S,
+    start: Vector2F,
+    pending_line: Option<Vector2F>,
+}
+
+impl<S> OutlineSinkAdapter<'_, S>
+where
+    S: OutlineSink,
+{
+    fn flush_pending_line(&mut self) {
+        if let Some(to) = self.pending_line.take() {
+            self.sink.line_to(to);
+        }
+    }
+}
+
+impl<S> ttf_parser::OutlineBuilder for OutlineSinkAdapter<'_, S>
+where
+    S: OutlineSink,
+{
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.flush_pending_line();
+        self.start = Vector2F::new(x, y);
+        self.sink.move_to(self.start);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.flush_pending_line();
+        self.pending_line = Some(Vector2F::new(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.flush_pending_line();
+        self.sink
+            .quadratic_curve_to(Vector2F::new(x1, y1), Vector2F::new(x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.flush_pending_line();
+        self.sink.cubic_curve_to(
+            LineSegment2F::new(Vector2F::new(x1, y1), Vector2F::new(x2, y2)),
+            Vector2F::new(x, y),
+        );
+    }
+
+    fn close(&mut self) {
+        if self.pending_line != Some(self.start) {
+            self.flush_pending_line();
+        }
+        self.pending_line = None;
+        self.sink.close();
+    }
+}
@@ -24,6 +24,7 @@ use pathfinder_simd::default::F32x4;
 use std::f32;
 use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::io::{Seek, SeekFrom};
 use std::iter;
 use std::mem;
@@ -35,9 +36,9 @@ use std::sync::Arc;
 use crate::canvas::{Canvas, Format, RasterizationOptions};
 use crate::error::{FontLoadingError, GlyphLoadingError};
 use crate::file_type::FileType;
-use crate::handle::Handle;
+use crate::handle::{self, Handle};
 use crate::hinting::HintingOptions;
-use crate::loader::{FallbackResult, Loader};
+use crate::loader::{self, FallbackResult, Loader};
 use crate::metrics::Metrics;
 use crate::outline::OutlineSink;
 use crate::properties::{Properties, Stretch, Style, Weight};
@@ -235,9 +236,12 @@ impl Font {
     }
 
     /// Returns the glyph ID for the specified glyph name.
+    ///
+    /// `.notdef`, `.null`, and `nonmarkingreturn` also resolve by convention, as
+    /// `Loader::glyph_by_name()` describes.
     #[inline]
     pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
-        None
+        loader::conventional_glyph_by_name(self, name)
     }
 
     /// Returns the number of glyphs in the font.
@@ -341,6 +345,11 @@ impl Font {
     /// loader.
     ///
     /// If `hinting_options` is not None, the requested grid fitting is performed.
+    ///
+    /// If the glyph's raster bounds don't fit inside the canvas, this returns
+    /// `GlyphLoadingError::CanvasTooSmall`. Use `rasterize_glyph_clipped()` to draw the visible
+    /// part of the glyph instead.
+    #[inline]
     pub fn rasterize_glyph(
         &self,
         canvas: &mut Canvas,
@@ -349,6 +358,31 @@ impl Font {
         transform: Transform2F,
         hinting_options: HintingOptions,
         rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        loader::rasterize_glyph(
+            self,
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
+    /// Rasterizes a glyph to a canvas with the given size and origin, clipping it to the bounds of
+    /// the canvas.
+    ///
+    /// This behaves like `rasterize_glyph()`, except that parts of the glyph that fall outside
+    /// the canvas are silently discarded.
+    pub fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
     ) -> Result<(), GlyphLoadingError> {
         // TODO(pcwalton): This is woefully incomplete. See WebRender's code for a more complete
         // implementation.
@@ -375,8 +409,12 @@ impl Font {
     /// Attempts to return the raw font data (contents of the font file).
     ///
     /// If this font is a member of a collection, this function returns the data for the entire
-    /// collection.
+    /// collection. Fonts created with `from_native_font()` don't retain their data and return
+    /// `None`.
     pub fn copy_font_data(&self) -> Option<Arc<Vec<u8>>> {
+        if self.font_data.is_empty() {
+            return None;
+        }
         Some(self.font_data.clone())
     }
 
@@ -430,6 +468,28 @@ impl Debug for Font {
     }
 }
 
+/// Fonts compare equal if they were loaded from identical font data.
+///
+/// This compares the source of the font, not its rendered output. In particular, two
+/// independently loaded copies of the same file compare equal. Font data is compared in full, but
+/// only once its length and start, which holds the table directory, match.
+impl PartialEq for Font {
+    fn eq(&self, other: &Font) -> bool {
+        handle::font_data_matches(&self.font_data, &other.font_data)
+    }
+}
+
+impl Eq for Font {}
+
+impl Hash for Font {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        handle::hash_font_data(state, &self.font_data, 0);
+    }
+}
+
 impl Loader for Font {
     type NativeFont = NativeFont;
 
@@ -571,6 +631,26 @@ impl Loader for Font {
         )
     }
 
+    #[inline]
+    fn rasterize_glyph_clipped(
+        &self,
+        canvas: &mut Canvas,
+        glyph_id: u32,
+        point_size: f32,
+        transform: Transform2F,
+        hinting_options: HintingOptions,
+        rasterization_options: RasterizationOptions,
+    ) -> Result<(), GlyphLoadingError> {
+        self.rasterize_glyph_clipped(
+            canvas,
+            glyph_id,
+            point_size,
+            transform,
+            hinting_options,
+            rasterization_options,
+        )
+    }
+
     #[inline]
     fn get_fallbacks(&self, text: &str, locale: &str) -> FallbackResult<Self> {
         self.get_fallbacks(text, locale)
@@ -11,6 +11,7 @@
 //! Determines the closest font matching a description per the CSS Fonts Level 3 specification.
 
 use float_ord::FloatOrd;
+use std::mem;
 
 use crate::error::SelectionError;
 use crate::properties::{Properties, Stretch, Style, Weight};
@@ -22,6 +23,15 @@ pub fn find_best_match(
     candidates: &[Properties],
     query: &Properties,
 ) -> Result<usize, SelectionError> {
+    // A near-exact match wins outright. Without this, a regular face that reports a weight of 399
+    // would lose to a 500 face when asking for 400.
+    if let Some(index) = (0..candidates.len())
+        .filter(|&index| candidates[index].approximately_eq(query, EXACT_WEIGHT_TOLERANCE))
+        .min_by_key(|&index| FloatOrd((candidates[index].weight.0 - query.weight.0).abs()))
+    {
+        return Ok(index);
+    }
+
     // Step 4.
     let mut matching_set: Vec<usize> = (0..candidates.len()).collect();
     if matching_set.is_empty() {
@@ -71,20 +81,23 @@ pub fn find_best_match(
     matching_set.retain(|&index| candidates[index].stretch == matching_stretch);
 
     // Step 4b (`font-style`).
+    //
+    // Oblique faces are matched regardless of their angle.
+    let oblique = Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE);
     let style_preference = match query.style {
-        Style::Italic => [Style::Italic, Style::Oblique, Style::Normal],
-        Style::Oblique => [Style::Oblique, Style::Italic, Style::Normal],
-        Style::Normal => [Style::Normal, Style::Oblique, Style::Italic],
+        Style::Italic => [Style::Italic, oblique, Style::Normal],
+        Style::Oblique(_) => [oblique, Style::Italic, Style::Normal],
+        Style::Normal => [Style::Normal, oblique, Style::Italic],
     };
     let matching_style = *style_preference
         .iter()
         .find(|&query_style| {
             matching_set
                 .iter()
-                .any(|&index| candidates[index].style == *query_style)
+                .any(|&index| same_style_kind(candidates[index].style, *query_style))
         })
         .unwrap();
-    matching_set.retain(|&index| candidates[index].style == matching_style);
+    matching_set.retain(|&index| same_style_kind(candidates[index].style, matching_style));
 
     // Step 4c (`font-weight`).
     //
@@ -154,3 +167,63 @@ pub fn find_best_match(
         .next()
         .ok_or(SelectionError::NotFound)
 }
+
+/// Returns true if `candidate` matches `query` closely enough to be returned from a multi-font
+/// query such as `Source::select_fonts()`.
+///
+/// Styles must be of the same kind (any oblique angle matches any other), weights must be within
+/// 50 of each other, and stretches within one sixteenth.
+pub fn properties_match(candidate: &Properties, query: &Properties) -> bool {
+    same_style_kind(candidate.style, query.style)
+        && (candidate.weight.0 - query.weight.0).abs() <= WEIGHT_TOLERANCE
+        && (candidate.stretch.0 - query.stretch.0).abs() <= STRETCH_TOLERANCE
+}
+
+const EXACT_WEIGHT_TOLERANCE: f32 = 1.0;
+const WEIGHT_TOLERANCE: f32 = 50.0;
+const STRETCH_TOLERANCE: f32 = 0.0625;
+
+#[inline]
+fn same_style_kind(a: Style, b: Style) -> bool {
+    mem::discriminant(&a) == mem::discriminant(&b)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::properties::{Properties, Stretch, Style, Weight};
+
+    use super::find_best_match;
+
+    #[test]
+    fn approximately_eq_at_weight_tolerance() {
+        let regular = *Properties::new().weight(Weight(400.0));
+        assert!(regular.approximately_eq(Properties::new().weight(Weight(399.0)), 1.0));
+        assert!(regular.approximately_eq(Properties::new().weight(Weight(401.0)), 1.0));
+        assert!(!regular.approximately_eq(Properties::new().weight(Weight(398.5)), 1.0));
+        assert!(regular.approximately_eq(Properties::new().weight(Weight(398.5)), 1.5));
+        assert!(!regular.approximately_eq(Properties::new().style(Style::Italic), 1.0));
+        assert!(!regular.approximately_eq(Properties::new().stretch(Stretch::CONDENSED), 1.0));
+    }
+
+    #[test]
+    fn find_best_match_accepts_near_exact_weight() {
+        let candidates = [
+            *Properties::new().weight(Weight::MEDIUM),
+            *Properties::new().weight(Weight(399.0)),
+        ];
+        assert_eq!(
+            find_best_match(&candidates, Properties::new().weight(Weight::NORMAL)),
+            Ok(1)
+        );
+
+        // Beyond the tolerance, CSS matching prefers 500 for a query of 400.
+        let candidates = [
+            *Properties::new().weight(Weight::MEDIUM),
+            *Properties::new().weight(Weight(398.0)),
+        ];
+        assert_eq!(
+            find_best_match(&candidates, Properties::new().weight(Weight::NORMAL)),
+            Ok(0)
+        );
+    }
+}
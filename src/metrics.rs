@@ -17,7 +17,12 @@ use pathfinder_geometry::rect::RectF;
 /// Various metrics that apply to the entire font.
 ///
 /// For OpenType fonts, these mostly come from the `OS/2` table.
+///
+/// More metrics may be added in the future, so this can't be built with a struct literal outside
+/// of font-kit. This is a breaking change from earlier versions, which listed every field; to
+/// adjust a value, copy the metrics a loader returns and assign to the field instead.
 #[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
 pub struct Metrics {
     /// The number of font units per em.
     ///
@@ -45,16 +50,100 @@ pub struct Metrics {
     pub underline_thickness: f32,
 
     /// The approximate amount that uppercase letters rise above the baseline, in font units.
+    ///
+    /// If the `OS/2` table doesn't give it, as in version 0 and 1 tables or broken fonts that
+    /// report zero, the FreeType and pure-Rust loaders measure the top of the `H` glyph instead.
     pub cap_height: f32,
 
     /// The approximate amount that non-ascending lowercase letters rise above the baseline, in
     /// font units.
+    ///
+    /// This falls back to the top of the `x` glyph in the same way as `cap_height`.
     pub x_height: f32,
 
     /// A rectangle that surrounds all bounding boxes of all glyphs, in font units.
     ///
     /// This corresponds to the `xMin`/`xMax`/`yMin`/`yMax` values in the OpenType `head` table.
     pub bounding_box: RectF,
+
+    /// The rise of the caret slope, in font units.
+    ///
+    /// Together with `caret_slope_run`, this gives the angle of the text cursor: a rise of 1 and a
+    /// run of 0 is a vertical caret, as for upright fonts. This comes from the OpenType `hhea`
+    /// table.
+    pub caret_slope_rise: f32,
+
+    /// The run of the caret slope, in font units.
+    ///
+    /// This is nonzero for italic and oblique fonts, whose caret leans with the glyphs.
+    pub caret_slope_run: f32,
+
+    /// The amount by which a slanted highlight on a glyph should be shifted horizontally to
+    /// produce the best appearance, in font units. This is 0 for upright fonts.
+    pub caret_offset: f32,
+
+    /// The typographic line gap from the `OS/2` table (`sTypoLineGap`), in font units. This is 0
+    /// if the font has no `OS/2` table.
+    pub typo_line_gap: f32,
+
+    /// The top of the Windows clipping region from the `OS/2` table (`usWinAscent`), in font
+    /// units. This is 0 if the font has no `OS/2` table.
+    pub win_ascent: f32,
+
+    /// The bottom of the Windows clipping region from the `OS/2` table (`usWinDescent`), in font
+    /// units.
+    ///
+    /// Like `descent`, this is negative for a region that extends below the baseline, so the sign
+    /// is reversed from the value stored in the table. This is 0 if the font has no `OS/2` table.
+    pub win_descent: f32,
+}
+
+impl Metrics {
+    /// Returns the line gap to use under the given convention, in font units.
+    ///
+    /// This is `line_gap` if it's nonzero. Many fonts leave it at zero while still intending some
+    /// leading, so otherwise a gap is derived from the other vertical metrics: the typographic
+    /// line gap for `Mac`, the extra height of the Windows clipping region over the ascent and
+    /// descent for `Windows`, and the first of those that's nonzero for `Css`. The result is never
+    /// negative. The `line_gap` field itself is left untouched.
+    pub fn effective_line_gap(&self, convention: LineHeightConvention) -> f32 {
+        if self.line_gap != 0.0 {
+            return self.line_gap;
+        }
+        let win_gap =
+            ((self.win_ascent - self.win_descent) - (self.ascent - self.descent)).max(0.0);
+        let typo_gap = self.typo_line_gap.max(0.0);
+        match convention {
+            LineHeightConvention::Css if typo_gap > 0.0 => typo_gap,
+            LineHeightConvention::Css | LineHeightConvention::Windows => win_gap,
+            LineHeightConvention::Mac => typo_gap,
+        }
+    }
+
+    /// Returns `cap_height` as a fraction of the em, or 0.0 if `units_per_em` is 0.
+    #[inline]
+    pub fn cap_height_ratio(&self) -> f32 {
+        self.per_em(self.cap_height)
+    }
+
+    /// Returns `x_height` as a fraction of the em, or 0.0 if `units_per_em` is 0.
+    #[inline]
+    pub fn x_height_ratio(&self) -> f32 {
+        self.per_em(self.x_height)
+    }
+
+    /// Returns `ascent` as a fraction of the em, or 0.0 if `units_per_em` is 0.
+    #[inline]
+    pub fn ascent_ratio(&self) -> f32 {
+        self.per_em(self.ascent)
+    }
+
+    fn per_em(&self, value: f32) -> f32 {
+        if self.units_per_em == 0 {
+            return 0.0;
+        }
+        value / self.units_per_em as f32
+    }
 }
 
 impl Default for Metrics {
@@ -69,6 +158,29 @@ impl Default for Metrics {
             cap_height: 700.0,
             x_height: 500.0,
             bounding_box: RectF::default(),
+            caret_slope_rise: 1.0,
+            caret_slope_run: 0.0,
+            caret_offset: 0.0,
+            typo_line_gap: 0.0,
+            win_ascent: 800.0,
+            win_descent: -200.0,
         }
     }
-}
\ No newline at end of file
+}
+
+/// A platform convention for deriving the default distance between baselines from a font's
+/// vertical metrics.
+///
+/// Fonts carry three sets of vertical metrics, and platforms disagree about which one to use, so
+/// the same font can have different line heights on different systems.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineHeightConvention {
+    /// The CSS `line-height: normal` computation used by browsers: the `OS/2` typographic
+    /// ascender, descender, and line gap if the font sets the `USE_TYPO_METRICS` flag, and the
+    /// `hhea` ascender, descender, and line gap otherwise.
+    Css,
+    /// The GDI convention: `OS/2` `usWinAscent` plus `usWinDescent`, with no line gap.
+    Windows,
+    /// The Core Text convention: the `hhea` ascender, descender, and line gap.
+    Mac,
+}
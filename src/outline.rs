@@ -11,9 +11,25 @@
 //! Bézier paths.
 
 use pathfinder_geometry::line_segment::LineSegment2F;
+use pathfinder_geometry::rect::RectF;
+use pathfinder_geometry::transform2d::Transform2F;
 use pathfinder_geometry::vector::Vector2F;
 use std::mem;
 
+#[cfg(any(
+    all(
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "windows",
+            target_arch = "wasm32"
+        )),
+        not(feature = "pure-rust")
+    ),
+    feature = "loader-freetype"
+))]
+use crate::canvas::{Canvas, Format};
+
 /// Receives Bézier path rendering commands.
 pub trait OutlineSink {
     /// Moves the pen to a point.
@@ -28,6 +44,36 @@ pub trait OutlineSink {
     fn close(&mut self);
 }
 
+impl<S> OutlineSink for &mut S
+where
+    S: OutlineSink + ?Sized,
+{
+    #[inline]
+    fn move_to(&mut self, to: Vector2F) {
+        (**self).move_to(to)
+    }
+
+    #[inline]
+    fn line_to(&mut self, to: Vector2F) {
+        (**self).line_to(to)
+    }
+
+    #[inline]
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        (**self).quadratic_curve_to(ctrl, to)
+    }
+
+    #[inline]
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        (**self).cubic_curve_to(ctrl, to)
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        (**self).close()
+    }
+}
+
 /// A glyph vector outline or path.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Outline {
@@ -64,6 +110,46 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Axes to mirror a glyph across, about the center of its bounding box.
+    ///
+    /// This is a purely geometric reflection, as for reflection effects or rendering targets that
+    /// need flipped glyphs. It isn't Unicode bidi mirroring, which substitutes a different
+    /// character (such as `)` for `(`) in right-to-left text and is the job of a shaper.
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct Mirror: u8 {
+        /// Reflects left and right, about a vertical line through the center.
+        const HORIZONTAL = 0x01;
+        /// Reflects top and bottom, about a horizontal line through the center.
+        const VERTICAL = 0x02;
+    }
+}
+
+impl Mirror {
+    /// Returns the transform that mirrors points about the center of `bounds`.
+    ///
+    /// `bounds` is unchanged by the transform. Mirroring across one axis reverses the direction
+    /// of contours.
+    pub fn transform(self, bounds: RectF) -> Transform2F {
+        let scale = Vector2F::new(
+            if self.contains(Mirror::HORIZONTAL) {
+                -1.0
+            } else {
+                1.0
+            },
+            if self.contains(Mirror::VERTICAL) {
+                -1.0
+            } else {
+                1.0
+            },
+        );
+        let center = bounds.center();
+        Transform2F::from_translation(center)
+            * Transform2F::from_scale(scale)
+            * Transform2F::from_translation(-center)
+    }
+}
+
 /// Accumulates Bézier path rendering commands into an `Outline` structure.
 #[derive(Clone, Debug)]
 pub struct OutlineBuilder {
@@ -215,3 +301,577 @@ impl OutlineSink for OutlineBuilder {
             .push(mem::replace(&mut self.current_contour, Contour::new()));
     }
 }
+
+/// Applies an affine transform to Bézier path rendering commands before forwarding them to
+/// another sink.
+#[derive(Clone, Debug)]
+pub struct TransformingSink<S> {
+    sink: S,
+    transform: Transform2F,
+}
+
+impl<S> TransformingSink<S>
+where
+    S: OutlineSink,
+{
+    /// Creates a sink that transforms every point by `transform` before sending it to `sink`.
+    #[inline]
+    pub fn new(sink: S, transform: Transform2F) -> TransformingSink<S> {
+        TransformingSink { sink, transform }
+    }
+
+    /// Creates a sink that divides every coordinate by `units_per_em`, converting outlines in font
+    /// units to em-normalized coordinates (roughly in the range [0, 1]).
+    #[inline]
+    pub fn em_normalized(sink: S, units_per_em: u32) -> TransformingSink<S> {
+        TransformingSink::new(sink, Transform2F::from_scale(1.0 / units_per_em as f32))
+    }
+
+    /// Returns the transform applied to each point.
+    #[inline]
+    pub fn transform(&self) -> Transform2F {
+        self.transform
+    }
+
+    /// Consumes this sink and returns the wrapped one.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S> OutlineSink for TransformingSink<S>
+where
+    S: OutlineSink,
+{
+    #[inline]
+    fn move_to(&mut self, to: Vector2F) {
+        self.sink.move_to(self.transform * to)
+    }
+
+    #[inline]
+    fn line_to(&mut self, to: Vector2F) {
+        self.sink.line_to(self.transform * to)
+    }
+
+    #[inline]
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        self.sink
+            .quadratic_curve_to(self.transform * ctrl, self.transform * to)
+    }
+
+    #[inline]
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        self.sink
+            .cubic_curve_to(self.transform * ctrl, self.transform * to)
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.sink.close()
+    }
+}
+
+/// Measures the signed area of each contour of an outline, for fill-rule decisions.
+///
+/// Areas are computed exactly for lines and Bézier curves, in the units of the incoming points.
+/// Glyph outlines have y pointing up, so counterclockwise contours have positive area. Within a
+/// glyph, holes wind the opposite way from the solid contours around them.
+#[derive(Clone, Debug, Default)]
+pub struct AnalysisSink {
+    contours: Vec<ContourAnalysis>,
+    first_point: Vector2F,
+    current_point: Vector2F,
+    current_area: f32,
+    contour_open: bool,
+}
+
+/// The signed area and winding direction of a single contour, as measured by `AnalysisSink`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContourAnalysis {
+    signed_area: f32,
+}
+
+impl AnalysisSink {
+    /// Creates a new sink that has seen no contours.
+    #[inline]
+    pub fn new() -> AnalysisSink {
+        AnalysisSink::default()
+    }
+
+    /// Returns the analyses of the contours seen so far, in order.
+    ///
+    /// A contour is recorded when it is closed, or when a new one is started without closing it.
+    #[inline]
+    pub fn contours(&self) -> &[ContourAnalysis] {
+        &self.contours
+    }
+
+    /// Returns the sum of the signed areas of all contours.
+    pub fn signed_area(&self) -> f32 {
+        self.contours
+            .iter()
+            .map(|contour| contour.signed_area)
+            .sum()
+    }
+
+    /// Returns true if the outline as a whole winds clockwise, i.e. if its solid contours do.
+    #[inline]
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area() < 0.0
+    }
+
+    fn finish_contour(&mut self) {
+        if !self.contour_open {
+            return;
+        }
+        self.current_area += self.current_point.det(self.first_point) * 0.5;
+        self.contours.push(ContourAnalysis {
+            signed_area: self.current_area,
+        });
+        self.current_area = 0.0;
+        self.current_point = self.first_point;
+        self.contour_open = false;
+    }
+}
+
+impl ContourAnalysis {
+    /// Returns the signed area enclosed by the contour. This is positive for counterclockwise
+    /// contours in a y-up coordinate system.
+    #[inline]
+    pub fn signed_area(&self) -> f32 {
+        self.signed_area
+    }
+
+    /// Returns true if the contour winds clockwise in a y-up coordinate system.
+    #[inline]
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area < 0.0
+    }
+}
+
+impl OutlineSink for AnalysisSink {
+    fn move_to(&mut self, to: Vector2F) {
+        self.finish_contour();
+        self.first_point = to;
+        self.current_point = to;
+        self.contour_open = true;
+    }
+
+    fn line_to(&mut self, to: Vector2F) {
+        self.current_area += self.current_point.det(to) * 0.5;
+        self.current_point = to;
+    }
+
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        // Green's theorem, integrated exactly over the curve.
+        let from = self.current_point;
+        self.current_area +=
+            (2.0 * from.det(ctrl) + 2.0 * ctrl.det(to) + from.det(to)) * (1.0 / 6.0);
+        self.current_point = to;
+    }
+
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        let (from, ctrl0, ctrl1) = (self.current_point, ctrl.from(), ctrl.to());
+        self.current_area += (6.0 * from.det(ctrl0)
+            + 3.0 * from.det(ctrl1)
+            + from.det(to)
+            + 3.0 * ctrl0.det(ctrl1)
+            + 3.0 * ctrl0.det(to)
+            + 6.0 * ctrl1.det(to))
+            * (1.0 / 20.0);
+        self.current_point = to;
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.finish_contour()
+    }
+}
+
+/// Accumulates the control box of an outline: the smallest rectangle containing all of its points.
+///
+/// Off-curve control points are included, so the box always contains the outline, though it can
+/// be a little larger than the outline's tight bounds where a curve bulges less than its control
+/// points.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BoundsSink {
+    bounds: Option<RectF>,
+}
+
+impl BoundsSink {
+    /// Creates a new sink that has seen no points.
+    #[inline]
+    pub fn new() -> BoundsSink {
+        BoundsSink::default()
+    }
+
+    /// Returns the control box of the points seen so far, or `None` if there were none.
+    #[inline]
+    pub fn bounds(&self) -> Option<RectF> {
+        self.bounds
+    }
+
+    fn add_point(&mut self, point: Vector2F) {
+        self.bounds = Some(match self.bounds {
+            None => RectF::new(point, Vector2F::zero()),
+            Some(bounds) => bounds.union_point(point),
+        });
+    }
+}
+
+impl OutlineSink for BoundsSink {
+    #[inline]
+    fn move_to(&mut self, to: Vector2F) {
+        self.add_point(to)
+    }
+
+    #[inline]
+    fn line_to(&mut self, to: Vector2F) {
+        self.add_point(to)
+    }
+
+    #[inline]
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        self.add_point(ctrl);
+        self.add_point(to);
+    }
+
+    #[inline]
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        self.add_point(ctrl.from());
+        self.add_point(ctrl.to());
+        self.add_point(to);
+    }
+
+    #[inline]
+    fn close(&mut self) {}
+}
+
+/// The most line segments that `SimplifyingSink` flattens a single curve into.
+const MAX_CURVE_SEGMENTS: usize = 64;
+
+/// Simplifies Bézier paths into polygons with fewer points before forwarding them to another
+/// sink.
+///
+/// Curves are flattened into line segments, and each contour is then reduced with the
+/// Douglas-Peucker algorithm: points are dropped as long as the resulting polygon stays within
+/// `tolerance` of the original, in the units of the incoming points. Only `move_to`, `line_to`,
+/// and `close` calls reach the wrapped sink. A contour is forwarded once it is closed, or when the
+/// next one starts; call `into_inner()` to flush a trailing unclosed contour.
+#[derive(Clone, Debug)]
+pub struct SimplifyingSink<S> {
+    sink: S,
+    tolerance: f32,
+    points: Vec<Vector2F>,
+}
+
+impl<S> SimplifyingSink<S>
+where
+    S: OutlineSink,
+{
+    /// Creates a sink that simplifies contours to within `tolerance` before sending them to
+    /// `sink`.
+    #[inline]
+    pub fn new(sink: S, tolerance: f32) -> SimplifyingSink<S> {
+        SimplifyingSink {
+            sink,
+            tolerance,
+            points: vec![],
+        }
+    }
+
+    /// Returns the distance that simplified contours may deviate from the original ones.
+    #[inline]
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    /// Forwards any unclosed contour, then consumes this sink and returns the wrapped one.
+    pub fn into_inner(mut self) -> S {
+        self.flush(false);
+        self.sink
+    }
+
+    fn current_point(&self) -> Vector2F {
+        self.points.last().cloned().unwrap_or_default()
+    }
+
+    fn push_flattened<F>(&mut self, deviation: f32, point_at: F)
+    where
+        F: Fn(f32) -> Vector2F,
+    {
+        // The flattening error of a curve falls with the square of the segment count.
+        let segment_count =
+            ((deviation / self.tolerance).sqrt().ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS);
+        for index in 1..=segment_count {
+            let point = point_at(index as f32 / segment_count as f32);
+            self.points.push(point);
+        }
+    }
+
+    fn flush(&mut self, close: bool) {
+        if self.points.is_empty() {
+            return;
+        }
+        let mut points = mem::take(&mut self.points);
+        points.dedup();
+        if close && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        // Closed contours are simplified as a path that returns to its first point.
+        let mut keep = vec![false; points.len()];
+        keep[0] = true;
+        let last = if close {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        if !close {
+            keep[last] = true;
+        }
+        let point = |index: usize| points[index % points.len()];
+        let mut stack = vec![(0, last)];
+        while let Some((start, end)) = stack.pop() {
+            let (from, to) = (point(start), point(end));
+            let farthest = ((start + 1)..end)
+                .map(|index| (index, distance_to_segment(point(index), from, to)))
+                .fold(
+                    None,
+                    |farthest: Option<(usize, f32)>, candidate| match farthest {
+                        Some(farthest) if farthest.1 >= candidate.1 => Some(farthest),
+                        _ => Some(candidate),
+                    },
+                );
+            if let Some((index, distance)) = farthest {
+                if distance > self.tolerance {
+                    keep[index] = true;
+                    stack.push((start, index));
+                    stack.push((index, end));
+                }
+            }
+        }
+
+        let mut kept = points
+            .iter()
+            .zip(keep.iter())
+            .filter(|&(_, &keep)| keep)
+            .map(|(&point, _)| point);
+        if let Some(first) = kept.next() {
+            self.sink.move_to(first);
+        }
+        for point in kept {
+            self.sink.line_to(point);
+        }
+        if close {
+            self.sink.close();
+        }
+    }
+}
+
+impl<S> OutlineSink for SimplifyingSink<S>
+where
+    S: OutlineSink,
+{
+    fn move_to(&mut self, to: Vector2F) {
+        self.flush(false);
+        self.points.push(to);
+    }
+
+    #[inline]
+    fn line_to(&mut self, to: Vector2F) {
+        self.points.push(to);
+    }
+
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        let from = self.current_point();
+        let deviation = (from - ctrl * 2.0 + to).length() * 0.25;
+        self.push_flattened(deviation, |t| {
+            let u = 1.0 - t;
+            from * (u * u) + ctrl * (2.0 * u * t) + to * (t * t)
+        });
+    }
+
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        let (from, ctrl0, ctrl1) = (self.current_point(), ctrl.from(), ctrl.to());
+        let deviation = f32::max(
+            (from - ctrl0 * 2.0 + ctrl1).length(),
+            (ctrl0 - ctrl1 * 2.0 + to).length(),
+        ) * 0.75;
+        self.push_flattened(deviation, |t| {
+            let u = 1.0 - t;
+            from * (u * u * u)
+                + ctrl0 * (3.0 * u * u * t)
+                + ctrl1 * (3.0 * u * t * t)
+                + to * (t * t * t)
+        });
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.flush(true)
+    }
+}
+
+/// Writes Bézier path rendering commands as a PostScript path, for PDF and PostScript export.
+///
+/// Each command becomes one line of `moveto`, `lineto`, `curveto`, or `closepath` operators with
+/// the incoming coordinates unchanged. Those are y-up for glyph outlines, as PostScript expects.
+/// Quadratic curves are raised to cubic ones, since PostScript only has the latter. Coordinates
+/// are rounded to three decimal places, with trailing zeros dropped.
+#[derive(Clone, Debug, Default)]
+pub struct PostScriptPathSink {
+    path: String,
+    current_point: Vector2F,
+}
+
+impl PostScriptPathSink {
+    /// Creates a new sink with an empty path.
+    #[inline]
+    pub fn new() -> PostScriptPathSink {
+        PostScriptPathSink::default()
+    }
+
+    /// Returns the path written so far.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Consumes this sink and returns the path.
+    #[inline]
+    pub fn into_path(self) -> String {
+        self.path
+    }
+
+    fn push_operator(&mut self, points: &[Vector2F], operator: &str) {
+        for point in points {
+            push_postscript_number(&mut self.path, point.x());
+            self.path.push(' ');
+            push_postscript_number(&mut self.path, point.y());
+            self.path.push(' ');
+        }
+        self.path.push_str(operator);
+        self.path.push('\n');
+    }
+}
+
+impl OutlineSink for PostScriptPathSink {
+    fn move_to(&mut self, to: Vector2F) {
+        self.push_operator(&[to], "moveto");
+        self.current_point = to;
+    }
+
+    fn line_to(&mut self, to: Vector2F) {
+        self.push_operator(&[to], "lineto");
+        self.current_point = to;
+    }
+
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        let from = self.current_point;
+        let ctrl0 = from + (ctrl - from) * (2.0 / 3.0);
+        let ctrl1 = to + (ctrl - to) * (2.0 / 3.0);
+        self.push_operator(&[ctrl0, ctrl1, to], "curveto");
+        self.current_point = to;
+    }
+
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        self.push_operator(&[ctrl.from(), ctrl.to(), to], "curveto");
+        self.current_point = to;
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.path.push_str("closepath\n");
+    }
+}
+
+/// Appends `value` in PostScript syntax, to three decimal places without trailing zeros.
+fn push_postscript_number(path: &mut String, value: f32) {
+    let value = (value * 1000.0).round() / 1000.0;
+    // Avoid writing `-0`.
+    let value = if value == 0.0 { 0.0 } else { value };
+    let number = format!("{:.3}", value);
+    path.push_str(number.trim_end_matches('0').trim_end_matches('.'));
+}
+
+/// Returns the distance from `point` to the line segment from `from` to `to`.
+fn distance_to_segment(point: Vector2F, from: Vector2F, to: Vector2F) -> f32 {
+    let vector = to - from;
+    let square_length = vector.square_length();
+    if square_length == 0.0 {
+        return (point - from).length();
+    }
+    let t = ((point - from).dot(vector) / square_length).clamp(0.0, 1.0);
+    (point - (from + vector * t)).length()
+}
+
+/// Traces the set pixels of an `A8` canvas as filled rectangles, one contour each.
+///
+/// A pixel is set if its coverage is at least one half. Horizontal runs of set pixels become
+/// rectangles, and runs with the same extent in consecutive rows are merged. `origin` is the
+/// position of the top left corner of the canvas and `pixel_size` the side of one pixel, both in
+/// the y-up coordinate space of the sink.
+#[cfg(any(
+    all(
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "windows",
+            target_arch = "wasm32"
+        )),
+        not(feature = "pure-rust")
+    ),
+    feature = "loader-freetype"
+))]
+pub(crate) fn trace_bitmap_rectangles<S>(
+    canvas: &Canvas,
+    origin: Vector2F,
+    pixel_size: f32,
+    sink: &mut S,
+) where
+    S: OutlineSink + ?Sized,
+{
+    debug_assert_eq!(canvas.format, Format::A8);
+
+    let mut emit = |left: usize, right: usize, top: usize, bottom: usize| {
+        let (left, right) = (left as f32 * pixel_size, right as f32 * pixel_size);
+        let (top, bottom) = (-(top as f32) * pixel_size, -(bottom as f32) * pixel_size);
+        sink.move_to(origin + Vector2F::new(left, top));
+        sink.line_to(origin + Vector2F::new(right, top));
+        sink.line_to(origin + Vector2F::new(right, bottom));
+        sink.line_to(origin + Vector2F::new(left, bottom));
+        sink.close();
+    };
+
+    // Rectangles that reach the previous row, as (left, right, top).
+    let mut open: Vec<(usize, usize, usize)> = vec![];
+    let width = canvas.size.x() as usize;
+    for y in 0..(canvas.size.y() as usize) {
+        let row = &canvas.pixels[(y * canvas.stride)..(y * canvas.stride + width)];
+        let mut next_open = vec![];
+        let mut x = 0;
+        while x < width {
+            if row[x] < 0x80 {
+                x += 1;
+                continue;
+            }
+            let left = x;
+            while x < width && row[x] >= 0x80 {
+                x += 1;
+            }
+            let top = match open.iter().position(|&(l, r, _)| (l, r) == (left, x)) {
+                Some(index) => open.swap_remove(index).2,
+                None => y,
+            };
+            next_open.push((left, x, top));
+        }
+        for (left, right, top) in mem::replace(&mut open, next_open) {
+            emit(left, right, top, y);
+        }
+    }
+    for (left, right, top) in open {
+        emit(left, right, top, canvas.size.y() as usize);
+    }
+}
@@ -13,7 +13,14 @@
 //! Much of the documentation in this modules comes from the CSS 3 Fonts specification:
 //! <https://drafts.csswg.org/css-fonts-3/>
 
+use pathfinder_geometry::transform2d::Transform2F;
+#[cfg(feature = "serde")]
+use serde::de::{self, Deserializer, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem;
 
 /// Properties that specify which font in a family to use: e.g. style, weight, and stretchiness.
 ///
@@ -22,6 +29,7 @@ use std::fmt::{self, Debug, Display, Formatter};
 ///     # use font_kit::properties::{Properties, Style};
 ///     println!("{:?}", Properties::new().style(Style::Italic));
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Properties {
     /// The font style, as defined in CSS.
     pub style: Style,
@@ -59,29 +67,180 @@ impl Properties {
         self.stretch = stretch;
         self
     }
+
+    /// Returns true if both property sets have the same style and stretch, and weights that differ
+    /// by at most `weight_tolerance`.
+    ///
+    /// Fonts often report weights slightly off the standard values, such as 399 for a regular
+    /// face, which exact comparison would reject.
+    #[inline]
+    pub fn approximately_eq(&self, other: &Properties, weight_tolerance: f32) -> bool {
+        self.style == other.style
+            && self.stretch == other.stretch
+            && (self.weight.0 - other.weight.0).abs() <= weight_tolerance
+    }
+
+    /// Returns a human-readable label for this property set, such as "Bold Condensed Italic".
+    ///
+    /// The weight, stretch, and style are joined in that order, leaving out the ones with default
+    /// values. A property set with all default values is labeled "Regular".
+    pub fn to_style_string(&self) -> String {
+        let mut labels = vec![];
+        if self.weight != Weight::NORMAL {
+            labels.push(self.weight.to_string());
+        }
+        if self.stretch != Stretch::NORMAL {
+            labels.push(self.stretch.to_string());
+        }
+        if self.style != Style::Normal {
+            labels.push(self.style.to_string());
+        }
+        if labels.is_empty() {
+            return "Regular".to_owned();
+        }
+        labels.join(" ")
+    }
 }
 
 /// Allows italic or oblique faces to be selected.
-#[derive(Clone, Copy, PartialEq, Debug, Hash, Default)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub enum Style {
     /// A face that is neither italic not obliqued.
     #[default]
     Normal,
     /// A form that is generally cursive in nature.
     Italic,
-    /// A typically-sloped version of the regular face.
-    Oblique,
+    /// A typically-sloped version of the regular face, slanted by the given angle in degrees.
+    ///
+    /// Positive angles slant the glyphs forward (clockwise), as in CSS `font-style: oblique 14deg`.
+    /// Plain `oblique` with no angle corresponds to `Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE)`.
+    ///
+    /// This is a breaking change from earlier versions, where `Oblique` was a unit variant; match
+    /// it as `Style::Oblique(_)` and build it as `Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE)`.
+    Oblique(f32),
+}
+
+impl Style {
+    /// The slant angle, in degrees, that CSS uses for `font-style: oblique` with no angle.
+    pub const DEFAULT_OBLIQUE_ANGLE: f32 = 14.0;
+
+    /// Returns the slant angle in degrees if this is an oblique style.
+    #[inline]
+    pub fn oblique_angle(&self) -> Option<f32> {
+        match *self {
+            Style::Oblique(angle) => Some(angle),
+            Style::Normal | Style::Italic => None,
+        }
+    }
+
+    /// Builds an oblique style from a `post.italicAngle` value, falling back to the default
+    /// angle for fonts that claim to be oblique but don't record a slant.
+    pub(crate) fn oblique_from_italic_angle(italic_angle: f32) -> Style {
+        if italic_angle == 0.0 {
+            Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE)
+        } else {
+            Style::Oblique(-italic_angle)
+        }
+    }
+
+    /// Returns the shear transform that synthesizes this style from an upright face ("faux
+    /// oblique").
+    ///
+    /// The transform is expressed in the "origin is top left" pixel coordinate space used for
+    /// rasterization, so it can be composed with the transform passed to
+    /// `Loader::rasterize_glyph()`. Styles other than `Oblique` return the identity transform.
+    pub fn faux_oblique_transform(&self) -> Transform2F {
+        match *self {
+            Style::Oblique(angle) => {
+                Transform2F::row_major(1.0, -angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0)
+            }
+            Style::Normal | Style::Italic => Transform2F::default(),
+        }
+    }
 }
 
+// Consistent with the derived `PartialEq`, which treats angles of 0.0 and -0.0 as equal.
+impl Hash for Style {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        if let Style::Oblique(angle) = *self {
+            let angle = if angle == 0.0 { 0.0 } else { angle };
+            angle.to_bits().hash(state);
+        }
+    }
+}
+
+// Oblique styles with a nonstandard angle include it, as in "Oblique 10deg".
 impl Display for Style {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Debug::fmt(self, f)
+        match *self {
+            Style::Normal => f.write_str("Normal"),
+            Style::Italic => f.write_str("Italic"),
+            Style::Oblique(angle) if angle == Style::DEFAULT_OBLIQUE_ANGLE => {
+                f.write_str("Oblique")
+            }
+            Style::Oblique(angle) => write!(f, "Oblique {}deg", angle),
+        }
+    }
+}
+
+// Styles serialize as CSS `font-style` values: `normal`, `italic`, or `oblique <angle>deg`.
+#[cfg(feature = "serde")]
+impl Serialize for Style {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Style::Normal => serializer.serialize_str("normal"),
+            Style::Italic => serializer.serialize_str("italic"),
+            Style::Oblique(angle) => serializer.collect_str(&format_args!("oblique {}deg", angle)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Style {
+    fn deserialize<D>(deserializer: D) -> Result<Style, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StyleVisitor;
+
+        impl<'de> Visitor<'de> for StyleVisitor {
+            type Value = Style;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("`normal`, `italic`, `oblique`, or `oblique <angle>deg`")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Style, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "normal" => return Ok(Style::Normal),
+                    "italic" => return Ok(Style::Italic),
+                    "oblique" => return Ok(Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE)),
+                    _ => {}
+                }
+                value
+                    .strip_prefix("oblique ")
+                    .and_then(|angle| angle.trim().strip_suffix("deg"))
+                    .and_then(|angle| angle.parse().ok())
+                    .map(Style::Oblique)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(StyleVisitor)
     }
 }
 
 /// The degree of blackness or stroke thickness of a font. This value ranges from 100.0 to 900.0,
 /// with 400.0 as normal.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Weight(pub f32);
 
 impl Default for Weight {
@@ -112,10 +271,32 @@ impl Weight {
     pub const BLACK: Weight = Weight(900.0);
 }
 
+// Standard weights display by name, as in "Semibold"; others display as a number.
+impl Display for Weight {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        const NAMES: [(Weight, &str); 9] = [
+            (Weight::THIN, "Thin"),
+            (Weight::EXTRA_LIGHT, "Extra Light"),
+            (Weight::LIGHT, "Light"),
+            (Weight::NORMAL, "Normal"),
+            (Weight::MEDIUM, "Medium"),
+            (Weight::SEMIBOLD, "Semibold"),
+            (Weight::BOLD, "Bold"),
+            (Weight::EXTRA_BOLD, "Extra Bold"),
+            (Weight::BLACK, "Black"),
+        ];
+        match NAMES.iter().find(|(value, _)| value == self) {
+            Some((_, name)) => f.write_str(name),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
 /// The width of a font as an approximate fraction of the normal width.
 ///
 /// Widths range from 0.5 to 2.0 inclusive, with 1.0 as the normal width.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Stretch(pub f32);
 
 impl Default for Stretch {
@@ -158,3 +339,24 @@ impl Stretch {
         Stretch::ULTRA_EXPANDED.0,
     ];
 }
+
+// Standard widths display by name, as in "Semi Condensed"; others display as a percentage.
+impl Display for Stretch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        const NAMES: [(Stretch, &str); 9] = [
+            (Stretch::ULTRA_CONDENSED, "Ultra Condensed"),
+            (Stretch::EXTRA_CONDENSED, "Extra Condensed"),
+            (Stretch::CONDENSED, "Condensed"),
+            (Stretch::SEMI_CONDENSED, "Semi Condensed"),
+            (Stretch::NORMAL, "Normal"),
+            (Stretch::SEMI_EXPANDED, "Semi Expanded"),
+            (Stretch::EXPANDED, "Expanded"),
+            (Stretch::EXTRA_EXPANDED, "Extra Expanded"),
+            (Stretch::ULTRA_EXPANDED, "Ultra Expanded"),
+        ];
+        match NAMES.iter().find(|(value, _)| value == self) {
+            Some((_, name)) => f.write_str(name),
+            None => write!(f, "{}%", self.0 * 100.0),
+        }
+    }
+}
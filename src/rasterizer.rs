@@ -0,0 +1,148 @@
+// font-kit/src/rasterizer.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// `Accumulator::draw_line()` and the accumulation in `rasterize()` are adapted from `Raster` in
+// font-rs (https://github.com/raphlinus/font-rs), which carries this notice:
+//
+//   Copyright 2015 Google Inc. All rights reserved.
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+//
+// Changes from font-rs: the code takes pathfinder_geometry vectors, sizes the buffer to the bounds
+// of the segments, and returns floating-point coverage instead of converting it to bytes. Those
+// portions remain under the Apache License, Version 2.0 only.
+
+//! A small analytic-coverage rasterizer producing floating-point coverage.
+//!
+//! This is the signed-area accumulation of `font-rs`, adapted from its code (see the notice at the
+//! top of this file): each line segment deposits the exact area it sweeps into an accumulation
+//! buffer, and a running sum along each row yields coverage. The platform rasterizers quantize to
+//! 8 bits; this one doesn't.
+
+use pathfinder_geometry::line_segment::LineSegment2F;
+use pathfinder_geometry::rect::RectI;
+use pathfinder_geometry::vector::Vector2F;
+
+use crate::sdf::segment_bounds;
+
+/// Rasterizes closed polygons, given as line segments in pixel coordinates with y pointing down,
+/// into coverage values from 0.0 to 1.0.
+///
+/// Returns the pixel rectangle covering the segments, and one value per pixel in row-major order
+/// with no row padding. Overlapping contours with the same orientation saturate at 1.0.
+pub(crate) fn rasterize(segments: &[LineSegment2F]) -> (RectI, Vec<f32>) {
+    let bounds = match segments.split_first() {
+        None => return (RectI::default(), vec![]),
+        Some((first, rest)) => rest.iter().fold(segment_bounds(first), |bounds, segment| {
+            bounds.union_rect(segment_bounds(segment))
+        }),
+    };
+    let bounds = bounds.round_out().to_i32();
+    let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+    if width == 0 || height == 0 {
+        return (RectI::new(bounds.origin(), Default::default()), vec![]);
+    }
+
+    // Segments can deposit area one cell past the end of a row; that spills into the start of the
+    // next row, which the running sum carries over correctly. Pad for the last row.
+    let mut accumulator = Accumulator {
+        area: vec![0.0; width * height + 4],
+        width,
+        height,
+    };
+    let origin = bounds.origin().to_f32();
+    for segment in segments {
+        accumulator.draw_line(segment.from() - origin, segment.to() - origin);
+    }
+
+    let mut sum = 0.0;
+    let coverage = accumulator.area[0..(width * height)]
+        .iter()
+        .map(|&area| {
+            sum += area;
+            f32::min(sum.abs(), 1.0)
+        })
+        .collect();
+    (bounds, coverage)
+}
+
+struct Accumulator {
+    area: Vec<f32>,
+    width: usize,
+    height: usize,
+}
+
+impl Accumulator {
+    fn draw_line(&mut self, p0: Vector2F, p1: Vector2F) {
+        if (p0.y() - p1.y()).abs() <= f32::EPSILON {
+            return;
+        }
+        let (direction, p0, p1) = if p0.y() < p1.y() {
+            (1.0, p0, p1)
+        } else {
+            (-1.0, p1, p0)
+        };
+        let dxdy = (p1.x() - p0.x()) / (p1.y() - p0.y());
+        let mut x = p0.x();
+        if p0.y() < 0.0 {
+            x -= p0.y() * dxdy;
+        }
+
+        let y_end = usize::min(self.height, p1.y().ceil() as usize);
+        for y in (p0.y().max(0.0) as usize)..y_end {
+            let row_start = y * self.width;
+            let dy = f32::min((y + 1) as f32, p1.y()) - f32::max(y as f32, p0.y());
+            let x_next = x + dxdy * dy;
+            let d = dy * direction;
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let x0_floor = x0.floor();
+            let x0_index = x0_floor.max(0.0) as usize;
+            let x1_ceil = x1.ceil();
+            let x1_index = x1_ceil.max(0.0) as usize;
+
+            if x1_index <= x0_index + 1 {
+                // The segment stays within one pixel column in this row.
+                let x_mid = 0.5 * (x + x_next) - x0_floor;
+                self.area[row_start + x0_index] += d - d * x_mid;
+                self.area[row_start + x0_index + 1] += d * x_mid;
+            } else {
+                let s = (x1 - x0).recip();
+                let x0_fract = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0_fract) * (1.0 - x0_fract);
+                let x1_fract = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1_fract * x1_fract;
+                self.area[row_start + x0_index] += d * a0;
+                if x1_index == x0_index + 2 {
+                    self.area[row_start + x0_index + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0_fract);
+                    self.area[row_start + x0_index + 1] += d * (a1 - a0);
+                    for x_index in (x0_index + 2)..(x1_index - 1) {
+                        self.area[row_start + x_index] += d * s;
+                    }
+                    let a2 = a1 + (x1_index - x0_index - 3) as f32 * s;
+                    self.area[row_start + x1_index - 1] += d * (1.0 - a2 - am);
+                }
+                self.area[row_start + x1_index] += d * am;
+            }
+            x = x_next;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+// font-kit/src/sdf.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Signed distance field generation from glyph outlines.
+//!
+//! Outlines are flattened into line segments, and then each pixel center is tested against every
+//! segment. This is quadratic, but glyphs are small enough that it doesn't matter in practice.
+
+use pathfinder_geometry::line_segment::LineSegment2F;
+use pathfinder_geometry::rect::{RectF, RectI};
+use pathfinder_geometry::vector::{Vector2F, Vector2I};
+
+use crate::outline::OutlineSink;
+
+// The number of line segments each Bézier curve is flattened into.
+const CURVE_SUBDIVISIONS: u32 = 8;
+
+/// Accumulates an outline as a list of closed polygons.
+#[derive(Default)]
+pub(crate) struct Flattener {
+    segments: Vec<LineSegment2F>,
+    first_point: Vector2F,
+    current_point: Vector2F,
+}
+
+impl Flattener {
+    #[inline]
+    pub(crate) fn new() -> Flattener {
+        Flattener::default()
+    }
+
+    #[inline]
+    pub(crate) fn segments(&self) -> &[LineSegment2F] {
+        &self.segments
+    }
+
+    fn push_line(&mut self, to: Vector2F) {
+        if to != self.current_point {
+            self.segments
+                .push(LineSegment2F::new(self.current_point, to));
+        }
+        self.current_point = to;
+    }
+}
+
+impl OutlineSink for Flattener {
+    fn move_to(&mut self, to: Vector2F) {
+        self.first_point = to;
+        self.current_point = to;
+    }
+
+    fn line_to(&mut self, to: Vector2F) {
+        self.push_line(to);
+    }
+
+    fn quadratic_curve_to(&mut self, ctrl: Vector2F, to: Vector2F) {
+        let from = self.current_point;
+        for step in 1..=CURVE_SUBDIVISIONS {
+            let t = step as f32 / CURVE_SUBDIVISIONS as f32;
+            let point = from.lerp(ctrl, t).lerp(ctrl.lerp(to, t), t);
+            self.push_line(point);
+        }
+    }
+
+    fn cubic_curve_to(&mut self, ctrl: LineSegment2F, to: Vector2F) {
+        let from = self.current_point;
+        for step in 1..=CURVE_SUBDIVISIONS {
+            let t = step as f32 / CURVE_SUBDIVISIONS as f32;
+            let (p01, p12, p23) = (
+                from.lerp(ctrl.from(), t),
+                ctrl.from().lerp(ctrl.to(), t),
+                ctrl.to().lerp(to, t),
+            );
+            let point = p01.lerp(p12, t).lerp(p12.lerp(p23, t), t);
+            self.push_line(point);
+        }
+    }
+
+    fn close(&mut self) {
+        let first_point = self.first_point;
+        self.push_line(first_point);
+    }
+}
+
+/// Computes an 8-bit signed distance field over the pixel rectangle covering `segments`, padded
+/// by `spread` pixels on each side.
+///
+/// The segments must be in pixel coordinates. A value of 128 (0.5) is the edge; values grow
+/// towards 255 inside the glyph and shrink towards 0 outside it, reaching the extremes `spread`
+/// pixels from the edge.
+pub(crate) fn generate(segments: &[LineSegment2F], spread: f32) -> (RectI, Vec<u8>) {
+    let bounds = match segments.split_first() {
+        None => return (RectI::default(), vec![]),
+        Some((first, rest)) => rest.iter().fold(segment_bounds(first), |bounds, segment| {
+            bounds.union_rect(segment_bounds(segment))
+        }),
+    };
+    let bounds = bounds.dilate(spread).round_out().to_i32();
+
+    let mut field = Vec::with_capacity((bounds.width() * bounds.height()) as usize);
+    for y in bounds.min_y()..bounds.max_y() {
+        for x in bounds.min_x()..bounds.max_x() {
+            let point = Vector2I::new(x, y).to_f32() + Vector2F::splat(0.5);
+            let distance = distance_to_outline(segments, point);
+            let signed_distance = if winding_number(segments, point) != 0 {
+                distance
+            } else {
+                -distance
+            };
+            let value = (0.5 + signed_distance / (2.0 * spread)).clamp(0.0, 1.0);
+            field.push((value * 255.0).round() as u8);
+        }
+    }
+
+    (bounds, field)
+}
+
+pub(crate) fn segment_bounds(segment: &LineSegment2F) -> RectF {
+    RectF::from_points(
+        segment.from().min(segment.to()),
+        segment.from().max(segment.to()),
+    )
+}
+
+fn distance_to_outline(segments: &[LineSegment2F], point: Vector2F) -> f32 {
+    segments
+        .iter()
+        .map(|segment| {
+            let vector = segment.vector();
+            let length_squared = vector.square_length();
+            let t = if length_squared == 0.0 {
+                0.0
+            } else {
+                ((point - segment.from()).dot(vector) / length_squared).clamp(0.0, 1.0)
+            };
+            (segment.from() + vector * t - point).length()
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+// Nonzero winding, using a horizontal ray towards +x.
+fn winding_number(segments: &[LineSegment2F], point: Vector2F) -> i32 {
+    let mut winding = 0;
+    for segment in segments {
+        let (from, to) = (segment.from(), segment.to());
+        if (from.y() <= point.y()) == (to.y() <= point.y()) {
+            continue;
+        }
+        let t = (point.y() - from.y()) / (to.y() - from.y());
+        if from.x() + (to.x() - from.x()) * t > point.x() {
+            winding += if to.y() > from.y() { 1 } else { -1 };
+        }
+    }
+    winding
+}
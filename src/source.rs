@@ -16,9 +16,16 @@ use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
 use crate::font::Font;
 use crate::handle::Handle;
+use crate::loader::Loader;
 use crate::matching;
-use crate::properties::Properties;
+use crate::properties::{Properties, Stretch, Style, Weight};
 use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+#[cfg(feature = "observer")]
+use std::time::{Duration, Instant};
 
 #[cfg(all(
     any(target_os = "macos", target_os = "ios"),
@@ -97,28 +104,167 @@ pub trait Source: Any {
     fn all_families(&self) -> Result<Vec<String>, SelectionError>;
 
     /// Looks up a font family by name and returns the handles of all the fonts in that family.
+    ///
+    /// The name may be any of the family's localized names, such as "メイリオ" for Meiryo, where
+    /// the source knows them: `MemSource` and `FsSource` read them from the fonts' `name` tables,
+    /// and the platform sources rely on the platform.
     fn select_family_by_name(&self, family_name: &str) -> Result<FamilyHandle, SelectionError>;
 
+    /// Looks up a font family by name like `select_family_by_name()`, substituting the family
+    /// whose name is closest if none has that name, as fontconfig does.
+    ///
+    /// Returns the family and whether it was substituted. Names are compared by their edit
+    /// distance, ignoring case, and the first of equally close families wins. A family is only
+    /// substituted if at most half of the characters of `family_name` need changing to reach its
+    /// name, so a misspelling such as "Helvetca" finds Helvetica but an unrelated name still
+    /// returns `SelectionError::NotFound`.
+    fn select_family_by_name_or_closest(
+        &self,
+        family_name: &str,
+    ) -> Result<(FamilyHandle, bool), SelectionError> {
+        match self.select_family_by_name(family_name) {
+            Err(SelectionError::NotFound) => {}
+            result => return result.map(|family| (family, false)),
+        }
+        let closest_name = closest_family_name(family_name, &self.all_families()?)
+            .ok_or(SelectionError::NotFound)?;
+        self.select_family_by_name(&closest_name)
+            .map(|family| (family, true))
+    }
+
     /// Selects a font by PostScript name, which should be a unique identifier.
     ///
+    /// Names from PDF files often carry a subset tag, six uppercase letters and a plus sign as in
+    /// `ABCDEF+Arial-BoldMT`; it's removed before matching. If no font has the name exactly, the
+    /// name is split at its first hyphen into a family and a style, such as `Arial` and `BoldMT`,
+    /// and the best match for the style's weight, slant, and width in that family is returned.
+    ///
     /// The default implementation, which is used by the DirectWrite and the filesystem backends,
     /// does a brute-force search of installed fonts to find the one that matches.
     fn select_by_postscript_name(&self, postscript_name: &str) -> Result<Handle, SelectionError> {
-        // TODO(pcwalton): Optimize this by searching for families with similar names first.
-        for family_name in self.all_families()? {
-            if let Ok(family_handle) = self.select_family_by_name(&family_name) {
-                if let Ok(family) = Family::<Font>::from_handle(&family_handle) {
-                    for (handle, font) in family_handle.fonts().iter().zip(family.fonts().iter()) {
-                        if let Some(font_postscript_name) = font.postscript_name() {
-                            if font_postscript_name == postscript_name {
-                                return Ok((*handle).clone());
+        select_by_postscript_name(self, postscript_name, |postscript_name| {
+            // TODO(pcwalton): Optimize this by searching for families with similar names first.
+            for family_name in self.all_families()? {
+                if let Ok(family_handle) = self.select_family_by_name(&family_name) {
+                    if let Ok(family) = Family::<Font>::from_handle(&family_handle) {
+                        for (handle, font) in
+                            family_handle.fonts().iter().zip(family.fonts().iter())
+                        {
+                            if let Some(font_postscript_name) = font.postscript_name() {
+                                if font_postscript_name == postscript_name {
+                                    return Ok((*handle).clone());
+                                }
                             }
                         }
                     }
                 }
             }
+            Err(SelectionError::NotFound)
+        })
+    }
+
+    /// Returns the handle of an installed font that lives at the given path, if any.
+    ///
+    /// Paths are compared after canonicalization, so relative paths and symbolic links to an
+    /// installed font file are found. If the path refers to a collection, the handle of the first
+    /// font in it that this source knows about is returned.
+    fn find_handle_for_path(&self, path: &Path) -> Option<Handle> {
+        let canonical_path = canonicalize_path(path);
+        self.all_fonts()
+            .ok()?
+            .into_iter()
+            .find(|handle| match *handle {
+                Handle::Path {
+                    path: ref handle_path,
+                    ..
+                } => canonicalize_path(handle_path) == canonical_path,
+                Handle::Memory { .. } => false,
+            })
+    }
+
+    /// Returns the family name and properties that this source associates with `handle`, or
+    /// `None` if the handle doesn't refer to one of its fonts.
+    ///
+    /// Path handles match by canonicalized path and font index, and memory handles by their data
+    /// and font index. The default implementation loads the font to read its properties; sources
+    /// that already know them, such as `MemSource`, answer without loading anything.
+    fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
+        let handle = self
+            .all_fonts()
+            .ok()?
+            .into_iter()
+            .find(|candidate| candidate.refers_to_same_font(handle))?;
+        let font = Font::from_handle(&handle).ok()?;
+        Some((font.family_name(), font.properties()))
+    }
+
+    /// Returns the handles of all fonts, across all families, whose properties match `properties`.
+    ///
+    /// Matching is looser than in `select_best_match()`: the style must be of the same kind (any
+    /// oblique angle matches any other), the weight must be within 50 of the requested weight, and
+    /// the stretch within 0.0625 of the requested stretch. An empty list is returned if nothing
+    /// matches.
+    ///
+    /// The default implementation loads every font to read its properties; sources that can ask
+    /// the platform, such as the fontconfig backend, override it.
+    fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        Ok(self
+            .all_fonts()?
+            .into_iter()
+            .filter(|handle| match Font::from_handle(handle) {
+                Ok(font) => matching::properties_match(&font.properties(), properties),
+                Err(e) => {
+                    log::warn!("Error loading font from handle: {:?}", e);
+                    false
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the handle of a font that can display `c` when the font behind `base` can't, or
+    /// `None` if no font covers it.
+    ///
+    /// If `base` covers `c`, it is returned as is. Otherwise the fonts that the platform falls back
+    /// to from `base` for `locale` are tried first, in order, and then the rest of the fonts in
+    /// this source. The `locale` argument is a language tag such as `"en-US"` or `"zh-Hans-CN"`.
+    ///
+    /// This complements `Loader::get_fallbacks()`, which only knows about the base font. The
+    /// default implementation loads fonts until it finds one that covers `c`; sources that can ask
+    /// the platform for its fallback order, such as the fontconfig backend, override it.
+    fn fallback_for(&self, base: &Handle, c: char, locale: &str) -> Option<Handle> {
+        if let Some(handle) = platform_fallback_for(base, c, locale) {
+            return Some(handle);
         }
-        Err(SelectionError::NotFound)
+        self.all_fonts()
+            .ok()?
+            .into_iter()
+            .filter(|handle| !handle.refers_to_same_font(base))
+            .find(|handle| handle_covers_char(handle, c))
+    }
+
+    /// Returns the handles of all fonts that map every character of `text` to a glyph, for
+    /// example to list the fonts that can render a sample string.
+    ///
+    /// Control characters such as line breaks are ignored, since fonts don't map them. An empty
+    /// `text` is covered by every font.
+    ///
+    /// The default implementation loads each font once and looks up each distinct character in its
+    /// `cmap`. The `OS/2` Unicode range bits aren't used to rule fonts out beforehand, because
+    /// fonts set them too unreliably. Sources that can ask the platform, such as the fontconfig
+    /// backend, override it.
+    fn fonts_covering(&self, text: &str) -> Result<Vec<Handle>, SelectionError> {
+        let chars = coverage_chars(text);
+        Ok(self
+            .all_fonts()?
+            .into_iter()
+            .filter(|handle| match Font::from_handle(handle) {
+                Ok(font) => chars.iter().all(|&c| font.glyph_for_char(c).is_some()),
+                Err(e) => {
+                    log::warn!("Error loading font from handle: {:?}", e);
+                    false
+                }
+            })
+            .collect())
     }
 
     // FIXME(pcwalton): This only returns one family instead of multiple families for the generic
@@ -146,17 +292,66 @@ pub trait Source: Any {
         family_names: &[FamilyName],
         properties: &Properties,
     ) -> Result<Handle, SelectionError> {
-        for family_name in family_names {
-            if let Ok(family_handle) = self.select_family_by_generic_name(family_name) {
-                let candidates = self.select_descriptions_in_family(&family_handle)?;
-                if let Ok(index) = matching::find_best_match(&candidates, properties) {
-                    return Ok(family_handle.fonts[index].clone());
+        #[cfg(feature = "observer")]
+        let start = Instant::now();
+        let select = || {
+            for family_name in family_names {
+                if let Ok(family_handle) = self.select_family_by_generic_name(family_name) {
+                    let candidates = self.select_descriptions_in_family(&family_handle)?;
+                    if let Ok(index) = matching::find_best_match(&candidates, properties) {
+                        return Ok(family_handle.fonts[index].clone());
+                    }
                 }
             }
-        }
-        Err(SelectionError::NotFound)
+            Err(SelectionError::NotFound)
+        };
+        let result = select();
+        #[cfg(feature = "observer")]
+        self.notify(SourceEvent::SelectionTook(start.elapsed()));
+        result
+    }
+
+    /// Selects the font in the family named `family_name` that best matches `properties`, as
+    /// `select_best_match()` does, and loads it.
+    ///
+    /// Loaded fonts are shared: as long as any `Rc` returned for a font is alive, later calls on
+    /// the same thread that select the same font, from this source or any other, return that same
+    /// `Rc` instead of loading it again. Fonts are shared per thread, and returned as `Rc` rather
+    /// than `Arc`, since loaders such as FreeType can't move faces between threads.
+    fn load_family_best(
+        &self,
+        family_name: &str,
+        properties: &Properties,
+    ) -> Result<Rc<Font>, SelectionError> {
+        let handle =
+            self.select_best_match(&[FamilyName::Title(family_name.to_owned())], properties)?;
+        load_shared_font(&handle)
+    }
+
+    /// Returns the fonts that the platform uses by default for sans-serif, serif, and monospace
+    /// text, as for `FamilyName::SansSerif`, `FamilyName::Serif`, and `FamilyName::Monospace`.
+    ///
+    /// Each is the best match for normal properties in the platform's default family, so apps
+    /// such as terminals can resolve all three at startup in one call. The system sources cache
+    /// the result, so later calls are cheap; the default implementation resolves them every time.
+    fn system_defaults(&self) -> SystemDefaults {
+        SystemDefaults::resolve(self)
+    }
+
+    /// Sets a function that is called with events describing the work this source does, such as
+    /// the directories it scans and how long selection takes, to diagnose slow font enumeration.
+    ///
+    /// Replaces any previous observer. Sources that don't report events, which is the default,
+    /// ignore the observer.
+    #[cfg(feature = "observer")]
+    fn set_observer(&mut self, observer: SourceObserver) {
+        drop(observer)
     }
 
+    #[doc(hidden)]
+    #[cfg(feature = "observer")]
+    fn notify(&self, _event: SourceEvent) {}
+
     #[doc(hidden)]
     fn select_descriptions_in_family(
         &self,
@@ -180,3 +375,263 @@ pub trait Source: Any {
     /// trait object.
     fn as_mut_any(&mut self) -> &mut dyn Any;
 }
+
+/// The platform's default fonts for the most common generic families, as returned by
+/// `Source::system_defaults()`.
+///
+/// A family that the source can't resolve is `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SystemDefaults {
+    /// The default sans-serif font.
+    pub sans_serif: Option<Handle>,
+    /// The default serif font.
+    pub serif: Option<Handle>,
+    /// The default monospace font.
+    pub monospace: Option<Handle>,
+}
+
+impl SystemDefaults {
+    /// Selects the default fonts from `source`.
+    pub(crate) fn resolve<S>(source: &S) -> SystemDefaults
+    where
+        S: Source + ?Sized,
+    {
+        let select = |family_name| {
+            source
+                .select_best_match(&[family_name], &Properties::new())
+                .map_err(|e| log::warn!("Error selecting default font: {:?}", e))
+                .ok()
+        };
+        SystemDefaults {
+            sans_serif: select(FamilyName::SansSerif),
+            serif: select(FamilyName::Serif),
+            monospace: select(FamilyName::Monospace),
+        }
+    }
+}
+
+/// An event reported to the observer set with `Source::set_observer()`.
+#[cfg(feature = "observer")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SourceEvent {
+    /// The source started scanning a directory for fonts.
+    ScanningDirectory(PathBuf),
+    /// The source finished loading or listing fonts, and found this many.
+    ParsedFonts(usize),
+    /// A call to `Source::select_best_match()` finished after this long.
+    SelectionTook(Duration),
+}
+
+/// A function that receives `SourceEvent`s.
+///
+/// Observers must be `Send` and `Sync`, so that sources that have one can still be shared between
+/// threads.
+#[cfg(feature = "observer")]
+pub type SourceObserver = Box<dyn Fn(SourceEvent) + Send + Sync>;
+
+/// Returns true if the family should be left out of font lists by default.
+///
+/// Apple platforms mark fonts private to the system UI (e.g. `.SF NS`) with a leading period, and
+/// that convention is honored everywhere so that such fonts don't leak into family lists built
+/// from non-native sources.
+pub(crate) fn is_hidden_family_name(family_name: &str) -> bool {
+    family_name.starts_with('.')
+}
+
+/// Returns the name in `family_names` closest to `family_name`, as
+/// `Source::select_family_by_name_or_closest()` describes.
+fn closest_family_name(family_name: &str, family_names: &[String]) -> Option<String> {
+    let family_name: Vec<char> = family_name.to_lowercase().chars().collect();
+    let max_distance = family_name.len() / 2;
+    family_names
+        .iter()
+        .map(|candidate| {
+            let candidate_chars: Vec<char> = candidate.to_lowercase().chars().collect();
+            (edit_distance(&family_name, &candidate_chars), candidate)
+        })
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// Returns the Levenshtein distance between `a` and `b`: the fewest characters that must be
+/// inserted, deleted, or replaced to turn one into the other.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+    for (i, &a_char) in a.iter().enumerate() {
+        row[0] = i + 1;
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + (a_char != b_char) as usize;
+            row[j + 1] = substitution.min(previous_row[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut previous_row, &mut row);
+    }
+    previous_row[b.len()]
+}
+
+/// Selects a font by PostScript name as `Source::select_by_postscript_name()` describes, using
+/// `find_exact` to look up the name without its subset tag.
+pub(crate) fn select_by_postscript_name<S, F>(
+    source: &S,
+    postscript_name: &str,
+    find_exact: F,
+) -> Result<Handle, SelectionError>
+where
+    S: Source + ?Sized,
+    F: FnOnce(&str) -> Result<Handle, SelectionError>,
+{
+    let postscript_name = strip_subset_tag(postscript_name);
+    match find_exact(postscript_name) {
+        Err(SelectionError::NotFound) => {}
+        result => return result,
+    }
+    let (family_names, properties) = parse_postscript_name(postscript_name);
+    source.select_best_match(&family_names, &properties)
+}
+
+/// Removes a PDF subset tag, such as the `ABCDEF+` in `ABCDEF+Arial-BoldMT`.
+fn strip_subset_tag(postscript_name: &str) -> &str {
+    match postscript_name.as_bytes().get(..7) {
+        Some([tag @ .., b'+']) if tag.iter().all(u8::is_ascii_uppercase) => &postscript_name[7..],
+        _ => postscript_name,
+    }
+}
+
+/// Guesses the family and properties of a font from a PostScript name such as
+/// `TimesNewRoman-BoldItalic`.
+///
+/// The family candidates are the part before the first hyphen, as written and split into words
+/// (`Times New Roman`). The properties come from keywords in the part after it.
+fn parse_postscript_name(postscript_name: &str) -> (Vec<FamilyName>, Properties) {
+    let (family, style) = match postscript_name.find('-') {
+        Some(index) => (&postscript_name[..index], &postscript_name[(index + 1)..]),
+        None => (postscript_name, ""),
+    };
+
+    let mut family_names = vec![FamilyName::Title(family.to_owned())];
+    let mut words = String::new();
+    let chars: Vec<char> = family.chars().collect();
+    for (index, &c) in chars.iter().enumerate() {
+        // A word starts at an uppercase letter after a lowercase one, or at the last capital of
+        // an acronym that's followed by a lowercase letter, as in `EBGaramond`.
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1);
+        if c.is_uppercase()
+            && (previous.is_some_and(char::is_lowercase)
+                || (previous.is_some_and(char::is_uppercase)
+                    && next.is_some_and(|next| next.is_lowercase())))
+        {
+            words.push(' ');
+        }
+        words.push(c);
+    }
+    if words != family {
+        family_names.push(FamilyName::Title(words));
+    }
+
+    // Longer keywords come first, so that `SemiBold` isn't taken for `Bold`.
+    const WEIGHTS: [(&str, Weight); 14] = [
+        ("extralight", Weight::EXTRA_LIGHT),
+        ("ultralight", Weight::EXTRA_LIGHT),
+        ("extrabold", Weight::EXTRA_BOLD),
+        ("ultrabold", Weight::EXTRA_BOLD),
+        ("semibold", Weight::SEMIBOLD),
+        ("demibold", Weight::SEMIBOLD),
+        ("hairline", Weight::THIN),
+        ("medium", Weight::MEDIUM),
+        ("light", Weight::LIGHT),
+        ("heavy", Weight::BLACK),
+        ("black", Weight::BLACK),
+        ("thin", Weight::THIN),
+        ("bold", Weight::BOLD),
+        ("demi", Weight::SEMIBOLD),
+    ];
+    const STRETCHES: [(&str, Stretch); 5] = [
+        ("condensed", Stretch::CONDENSED),
+        ("narrow", Stretch::CONDENSED),
+        ("cond", Stretch::CONDENSED),
+        ("expanded", Stretch::EXPANDED),
+        ("extended", Stretch::EXPANDED),
+    ];
+
+    let style = style.to_lowercase();
+    let mut properties = Properties::new();
+    if let Some(&(_, weight)) = WEIGHTS.iter().find(|(keyword, _)| style.contains(keyword)) {
+        properties.weight = weight;
+    }
+    if let Some(&(_, stretch)) = STRETCHES
+        .iter()
+        .find(|(keyword, _)| style.contains(keyword))
+    {
+        properties.stretch = stretch;
+    }
+    // Adobe abbreviates italic to `It`, as in `MinionPro-BoldIt`.
+    if style.contains("italic") || style.ends_with("it") {
+        properties.style = Style::Italic;
+    } else if style.contains("oblique") {
+        properties.style = Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE);
+    }
+    (family_names, properties)
+}
+
+/// Returns `base` if it covers `c`, or else the first of the fonts that the loader falls back to
+/// from it that does.
+pub(crate) fn platform_fallback_for(base: &Handle, c: char, locale: &str) -> Option<Handle> {
+    let base_font = Font::from_handle(base).ok()?;
+    if base_font.glyph_for_char(c).is_some() {
+        return Some(base.clone());
+    }
+    let mut text = [0; 4];
+    Loader::get_fallbacks(&base_font, c.encode_utf8(&mut text), locale)
+        .fonts
+        .into_iter()
+        .filter(|fallback| fallback.font.glyph_for_char(c).is_some())
+        .find_map(|fallback| fallback.font.handle())
+}
+
+/// Returns true if the font behind `handle` loads and maps `c` to a glyph.
+pub(crate) fn handle_covers_char(handle: &Handle, c: char) -> bool {
+    match Font::from_handle(handle) {
+        Ok(font) => font.glyph_for_char(c).is_some(),
+        Err(e) => {
+            log::warn!("Error loading font from handle: {:?}", e);
+            false
+        }
+    }
+}
+
+thread_local! {
+    // The fonts loaded by `Source::load_family_best()` that are still in use.
+    static SHARED_FONTS: RefCell<HashMap<Handle, Weak<Font>>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the shared font for `handle`, loading it if no `Rc` for it is alive on this thread.
+fn load_shared_font(handle: &Handle) -> Result<Rc<Font>, SelectionError> {
+    SHARED_FONTS.with(|shared_fonts| {
+        let mut shared_fonts = shared_fonts.borrow_mut();
+        if let Some(font) = shared_fonts.get(handle).and_then(Weak::upgrade) {
+            return Ok(font);
+        }
+        let font = Font::from_handle(handle).map_err(|e| SelectionError::CannotAccessSource {
+            reason: Some(format!("failed to load font: {}", e).into()),
+        })?;
+        let font = Rc::new(font);
+        shared_fonts.retain(|_, font| font.strong_count() > 0);
+        shared_fonts.insert(handle.clone(), Rc::downgrade(&font));
+        Ok(font)
+    })
+}
+
+/// Returns the distinct characters of `text` that `Source::fonts_covering()` checks, leaving out
+/// control characters.
+pub(crate) fn coverage_chars(text: &str) -> Vec<char> {
+    let mut chars: Vec<char> = text.chars().filter(|c| !c.is_control()).collect();
+    chars.sort_unstable();
+    chars.dedup();
+    chars
+}
+
+fn canonicalize_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
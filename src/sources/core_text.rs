@@ -15,14 +15,14 @@ use core_foundation::base::{CFType, TCFType};
 use core_foundation::dictionary::CFDictionary;
 use core_foundation::string::CFString;
 use core_text::font_collection::{self, CTFontCollection};
-use core_text::font_descriptor::{self, CTFontDescriptor};
+use core_text::font_descriptor::{self, CTFontDescriptor, SymbolicTraitAccessors, TraitAccessors};
 use core_text::font_manager;
 use std::any::Any;
 use std::collections::HashMap;
 use std::f32;
 use std::fs::File;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
@@ -31,14 +31,17 @@ use crate::file_type::FileType;
 use crate::font::Font;
 use crate::handle::Handle;
 use crate::loaders::core_text::{self as core_text_loader, FONT_WEIGHT_MAPPING};
-use crate::properties::{Properties, Stretch, Weight};
-use crate::source::Source;
+use crate::properties::{Properties, Stretch, Style, Weight};
+use crate::source::{self, Source, SystemDefaults};
 use crate::utils;
 
 /// A source that contains the installed fonts on macOS.
 #[allow(missing_debug_implementations)]
 #[allow(missing_copy_implementations)]
-pub struct CoreTextSource;
+pub struct CoreTextSource {
+    include_hidden_fonts: bool,
+    system_defaults: OnceLock<SystemDefaults>,
+}
 
 impl CoreTextSource {
     /// Opens a new connection to the system font source.
@@ -47,22 +50,54 @@ impl CoreTextSource {
     /// lazily on demand by the Core Text/Core Graphics API.)
     #[inline]
     pub fn new() -> CoreTextSource {
-        CoreTextSource
+        CoreTextSource {
+            include_hidden_fonts: false,
+            system_defaults: OnceLock::new(),
+        }
+    }
+
+    /// Sets whether `all_families()` lists hidden families, such as the system UI fonts whose
+    /// names start with a period.
+    ///
+    /// Core Text decides which families are hidden: the visible ones are those it offers for font
+    /// menus. Hidden families are excluded by default. They can always be selected by name.
+    #[inline]
+    pub fn set_include_hidden_fonts(&mut self, include_hidden_fonts: bool) {
+        self.include_hidden_fonts = include_hidden_fonts;
     }
 
     /// Returns paths of all fonts installed on the system.
     pub fn all_fonts(&self) -> Result<Vec<Handle>, SelectionError> {
         let collection = font_collection::create_for_all_families();
-        create_handles_from_core_text_collection(collection)
+        let handles = create_handles_from_core_text_collection(collection)?;
+        Ok(handles.into_iter().map(|(handle, _)| handle).collect())
     }
 
     /// Returns the names of all families installed on the system.
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
-        let core_text_family_names = font_manager::copy_available_font_family_names();
-        let mut families = Vec::with_capacity(core_text_family_names.len() as usize);
-        for core_text_family_name in core_text_family_names.iter() {
-            families.push(core_text_family_name.to_string())
+        if !self.include_hidden_fonts {
+            // Core Text leaves hidden families out of this list.
+            let core_text_family_names = font_manager::copy_available_font_family_names();
+            let mut families = Vec::with_capacity(core_text_family_names.len() as usize);
+            for core_text_family_name in core_text_family_names.iter() {
+                let family_name = core_text_family_name.to_string();
+                if !source::is_hidden_family_name(&family_name) {
+                    families.push(family_name)
+                }
+            }
+            return Ok(families);
         }
+
+        // Hidden families only show up in the family name attributes of the fonts themselves.
+        let collection = font_collection::create_for_all_families();
+        let mut families = vec![];
+        if let Some(descriptors) = collection.get_descriptors() {
+            for descriptor in descriptors.iter() {
+                families.push(descriptor.family_name());
+            }
+        }
+        families.sort();
+        families.dedup();
         Ok(families)
     }
 
@@ -76,8 +111,19 @@ impl CoreTextSource {
         let descriptor = font_descriptor::new_from_attributes(&attributes);
         let descriptors = CFArray::from_CFTypes(&[descriptor]);
         let collection = font_collection::new_from_descriptors(&descriptors);
-        let handles = create_handles_from_core_text_collection(collection)?;
-        Ok(FamilyHandle::from_font_handles(handles.into_iter()))
+        let (handles, descriptors): (Vec<_>, Vec<_>) =
+            create_handles_from_core_text_collection(collection)?
+                .into_iter()
+                .unzip();
+        let mut family = FamilyHandle::from_font_handles(handles.into_iter());
+        family.set_family_name(family_name.to_owned());
+        family.sort_by_properties(
+            descriptors
+                .iter()
+                .map(|descriptor| Some(properties_from_descriptor(descriptor)))
+                .collect(),
+        );
+        Ok(family)
     }
 
     /// Selects a font by PostScript name, which should be a unique identifier.
@@ -85,18 +131,20 @@ impl CoreTextSource {
         &self,
         postscript_name: &str,
     ) -> Result<Handle, SelectionError> {
-        let attributes: CFDictionary<CFString, CFType> = CFDictionary::from_CFType_pairs(&[(
-            CFString::new("NSFontNameAttribute"),
-            CFString::new(postscript_name).as_CFType(),
-        )]);
-
-        let descriptor = font_descriptor::new_from_attributes(&attributes);
-        let descriptors = CFArray::from_CFTypes(&[descriptor]);
-        let collection = font_collection::new_from_descriptors(&descriptors);
-        match collection.get_descriptors() {
-            None => Err(SelectionError::NotFound),
-            Some(descriptors) => create_handle_from_descriptor(&*descriptors.get(0).unwrap()),
-        }
+        source::select_by_postscript_name(self, postscript_name, |postscript_name| {
+            let attributes: CFDictionary<CFString, CFType> = CFDictionary::from_CFType_pairs(&[(
+                CFString::new("NSFontNameAttribute"),
+                CFString::new(postscript_name).as_CFType(),
+            )]);
+
+            let descriptor = font_descriptor::new_from_attributes(&attributes);
+            let descriptors = CFArray::from_CFTypes(&[descriptor]);
+            let collection = font_collection::new_from_descriptors(&descriptors);
+            match collection.get_descriptors() {
+                None => Err(SelectionError::NotFound),
+                Some(descriptors) => create_handle_from_descriptor(&*descriptors.get(0).unwrap()),
+            }
+        })
     }
 
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
@@ -109,6 +157,16 @@ impl CoreTextSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Returns the fonts that the platform uses by default for sans-serif, serif, and monospace
+    /// text.
+    ///
+    /// They are selected on the first call and cached. See `Source::system_defaults()`.
+    pub fn system_defaults(&self) -> SystemDefaults {
+        self.system_defaults
+            .get_or_init(|| SystemDefaults::resolve(self))
+            .clone()
+    }
 }
 
 impl Source for CoreTextSource {
@@ -128,6 +186,10 @@ impl Source for CoreTextSource {
         self.select_by_postscript_name(postscript_name)
     }
 
+    fn system_defaults(&self) -> SystemDefaults {
+        self.system_defaults()
+    }
+
     #[inline]
     fn as_any(&self) -> &dyn Any {
         self
@@ -159,9 +221,10 @@ struct FontDataInfo {
     file_type: FileType,
 }
 
+// Returns the handles of the fonts in `collection`, each with the descriptor it was created from.
 fn create_handles_from_core_text_collection(
     collection: CTFontCollection,
-) -> Result<Vec<Handle>, SelectionError> {
+) -> Result<Vec<(Handle, CTFontDescriptor)>, SelectionError> {
     let mut fonts = vec![];
     if let Some(descriptors) = collection.get_descriptors() {
         let mut font_data_info_cache: HashMap<PathBuf, FontDataInfo> = HashMap::new();
@@ -204,15 +267,16 @@ fn create_handles_from_core_text_collection(
                         {
                             if let Some(font_postscript_name) = font.postscript_name() {
                                 if postscript_name == font_postscript_name {
-                                    fonts.push(Handle::from_memory(data_info.data, font_index));
+                                    let handle = Handle::from_memory(data_info.data, font_index);
+                                    fonts.push((handle, descriptor.clone()));
                                     continue 'outer;
                                 }
                             }
                         }
                     }
                 }
-                FileType::Single => {
-                    fonts.push(Handle::from_memory(data_info.data, 0));
+                FileType::Single | FileType::Type1 => {
+                    fonts.push((Handle::from_memory(data_info.data, 0), descriptor.clone()));
                 }
             }
         }
@@ -224,6 +288,26 @@ fn create_handles_from_core_text_collection(
     }
 }
 
+// Reads the properties of a font from the traits Core Text lists for it, without loading the font.
+// Descriptors carry no italic angle, so oblique fonts get the default one.
+fn properties_from_descriptor(descriptor: &CTFontDescriptor) -> Properties {
+    let traits = descriptor.traits();
+    let style = if traits.symbolic_traits().is_italic() {
+        Style::Italic
+    } else if traits.normalized_slant() > 0.0 {
+        Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE)
+    } else {
+        Style::Normal
+    };
+    Properties {
+        style,
+        weight: core_text_loader::core_text_to_css_font_weight(traits.normalized_weight() as f32),
+        stretch: core_text_loader::core_text_width_to_css_stretchiness(
+            traits.normalized_width() as f32
+        ),
+    }
+}
+
 fn create_handle_from_descriptor(descriptor: &CTFontDescriptor) -> Result<Handle, SelectionError> {
     let font_path = descriptor.font_path().unwrap();
 
@@ -254,7 +338,7 @@ fn create_handle_from_descriptor(descriptor: &CTFontDescriptor) -> Result<Handle
 
             Err(SelectionError::NotFound)
         }
-        Ok(FileType::Single) => Ok(Handle::from_memory(font_data, 0)),
+        Ok(FileType::Single) | Ok(FileType::Type1) => Ok(Handle::from_memory(font_data, 0)),
         Err(e) => Err(SelectionError::CannotAccessSource {
             reason: Some(format!("{:?} error on path {:?}", e, font_path).into()),
         }),
@@ -13,18 +13,22 @@
 use dwrote::Font as DWriteFont;
 use dwrote::FontCollection as DWriteFontCollection;
 use std::any::Any;
+use std::sync::OnceLock;
 
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
 use crate::handle::Handle;
-use crate::properties::Properties;
-use crate::source::Source;
+use crate::loaders::directwrite as directwrite_loader;
+use crate::properties::{Properties, Stretch, Weight};
+use crate::source::{self, Source, SystemDefaults};
 
 /// A source that contains the installed fonts on Windows.
 #[allow(missing_debug_implementations)]
 pub struct DirectWriteSource {
     system_font_collection: DWriteFontCollection,
+    include_hidden_fonts: bool,
+    system_defaults: OnceLock<SystemDefaults>,
 }
 
 impl DirectWriteSource {
@@ -32,9 +36,20 @@ impl DirectWriteSource {
     pub fn new() -> DirectWriteSource {
         DirectWriteSource {
             system_font_collection: DWriteFontCollection::system(),
+            include_hidden_fonts: false,
+            system_defaults: OnceLock::new(),
         }
     }
 
+    /// Sets whether `all_families()` lists hidden families.
+    ///
+    /// Families made up entirely of symbol fonts (e.g. Marlett, which Windows draws its window
+    /// controls with) are hidden, as are families whose names start with a period. Hidden
+    /// families are excluded by default. They can always be selected by name.
+    pub fn set_include_hidden_fonts(&mut self, include_hidden_fonts: bool) {
+        self.include_hidden_fonts = include_hidden_fonts;
+    }
+
     /// Returns paths of all fonts installed on the system.
     pub fn all_fonts(&self) -> Result<Vec<Handle>, SelectionError> {
         let mut handles = Vec::new();
@@ -54,6 +69,12 @@ impl DirectWriteSource {
         Ok(self
             .system_font_collection
             .families_iter()
+            .filter(|dwrite_family| {
+                self.include_hidden_fonts
+                    || !((0..dwrite_family.get_font_count())
+                        .all(|font_index| dwrite_family.get_font(font_index).is_symbol())
+                        || source::is_hidden_family_name(&dwrite_family.name()))
+            })
             .map(|dwrite_family| dwrite_family.name())
             .collect())
     }
@@ -70,10 +91,14 @@ impl DirectWriteSource {
             Some(dwrite_family) => dwrite_family,
             None => return Err(SelectionError::NotFound),
         };
+        let mut properties = vec![];
         for font_index in 0..dwrite_family.get_font_count() {
             let dwrite_font = dwrite_family.get_font(font_index);
+            properties.push(Some(properties_for_dwrite_font(&dwrite_font)));
             family.push(self.create_handle_from_dwrite_font(dwrite_font))
         }
+        family.set_family_name(dwrite_family.name());
+        family.sort_by_properties(properties);
         Ok(family)
     }
 
@@ -99,6 +124,16 @@ impl DirectWriteSource {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
 
+    /// Returns the fonts that the platform uses by default for sans-serif, serif, and monospace
+    /// text.
+    ///
+    /// They are selected on the first call and cached. See `Source::system_defaults()`.
+    pub fn system_defaults(&self) -> SystemDefaults {
+        self.system_defaults
+            .get_or_init(|| SystemDefaults::resolve(self))
+            .clone()
+    }
+
     fn create_handle_from_dwrite_font(&self, dwrite_font: DWriteFont) -> Handle {
         let dwrite_font_face = dwrite_font.create_font_face();
         let dwrite_font_files = dwrite_font_face.get_files();
@@ -125,6 +160,11 @@ impl Source for DirectWriteSource {
         self.select_family_by_name(family_name)
     }
 
+    #[inline]
+    fn system_defaults(&self) -> SystemDefaults {
+        self.system_defaults()
+    }
+
     #[inline]
     fn as_any(&self) -> &dyn Any {
         self
@@ -135,3 +175,13 @@ impl Source for DirectWriteSource {
         self
     }
 }
+
+// Reads the properties DirectWrite lists for a font, without creating its font face. Oblique fonts
+// get the default angle.
+fn properties_for_dwrite_font(dwrite_font: &DWriteFont) -> Properties {
+    Properties {
+        style: directwrite_loader::style_for_dwrite_style(dwrite_font.style()),
+        stretch: Stretch(Stretch::MAPPING[(dwrite_font.stretch() as usize) - 1]),
+        weight: Weight(dwrite_font.weight().to_u32() as f32),
+    }
+}
@@ -18,10 +18,16 @@
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
+use crate::font::Font;
 use crate::handle::Handle;
-use crate::properties::Properties;
-use crate::source::Source;
+use crate::matching;
+use crate::properties::{Properties, Stretch, Weight};
+use crate::source::{self, Source, SystemDefaults};
+#[cfg(feature = "observer")]
+use crate::source::{SourceEvent, SourceObserver};
 use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
 
 /// A source that contains the fonts installed on the system, as reported by the Fontconfig
 /// library.
@@ -32,6 +38,10 @@ use std::any::Any;
 #[allow(missing_debug_implementations)]
 pub struct FontconfigSource {
     config: fc::Config,
+    include_hidden_fonts: bool,
+    system_defaults: OnceLock<SystemDefaults>,
+    #[cfg(feature = "observer")]
+    observer: Option<SourceObserver>,
 }
 
 impl Default for FontconfigSource {
@@ -45,9 +55,31 @@ impl FontconfigSource {
     pub fn new() -> FontconfigSource {
         FontconfigSource {
             config: fc::Config::new(),
+            include_hidden_fonts: false,
+            system_defaults: OnceLock::new(),
+            #[cfg(feature = "observer")]
+            observer: None,
         }
     }
 
+    /// Sets whether `all_families()` lists hidden families.
+    ///
+    /// Families made up entirely of fonts that Fontconfig marks as symbol fonts (e.g. Wingdings)
+    /// are hidden, as are families whose names start with a period. Hidden families are excluded
+    /// by default. They can always be selected by name.
+    pub fn set_include_hidden_fonts(&mut self, include_hidden_fonts: bool) {
+        self.include_hidden_fonts = include_hidden_fonts;
+    }
+
+    /// Sets a function that is called with events describing the work this source does.
+    ///
+    /// Fontconfig sources report the number of fonts that `all_fonts()` lists and how long
+    /// `select_best_match()` takes.
+    #[cfg(feature = "observer")]
+    pub fn set_observer(&mut self, observer: SourceObserver) {
+        self.observer = Some(observer);
+    }
+
     /// Returns paths of all fonts installed on the system.
     pub fn all_fonts(&self) -> Result<Vec<Handle>, SelectionError> {
         let pattern = fc::Pattern::new();
@@ -79,6 +111,8 @@ impl FontconfigSource {
             });
         }
 
+        #[cfg(feature = "observer")]
+        self.notify(SourceEvent::ParsedFonts(handles.len()));
         if !handles.is_empty() {
             Ok(handles)
         } else {
@@ -90,23 +124,32 @@ impl FontconfigSource {
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         let pattern = fc::Pattern::new();
 
-        // We want the family name.
+        // We want the family name, and whether its fonts are symbol fonts.
         let mut object_set = fc::ObjectSet::new();
         object_set.push_string(fc::Object::Family);
+        object_set.push_string(fc::Object::Symbol);
 
         let patterns = pattern
             .list(&self.config, object_set)
             .map_err(|_| SelectionError::NotFound)?;
 
-        let mut result_families = vec![];
+        // Note whether each family has any font that isn't a symbol font.
+        let mut families = BTreeMap::new();
         for patt in patterns {
             if let Some(family) = patt.get_string(fc::Object::Family) {
-                result_families.push(family);
+                let symbol = patt.get_bool(fc::Object::Symbol).unwrap_or(false);
+                *families.entry(family).or_insert(false) |= !symbol;
             }
         }
 
-        result_families.sort();
-        result_families.dedup();
+        let result_families: Vec<String> = families
+            .into_iter()
+            .filter(|(family, has_text_fonts)| {
+                self.include_hidden_fonts
+                    || (*has_text_fonts && !source::is_hidden_family_name(family))
+            })
+            .map(|(family, _)| family)
+            .collect();
 
         if !result_families.is_empty() {
             Ok(result_families)
@@ -116,6 +159,10 @@ impl FontconfigSource {
     }
 
     /// Looks up a font family by name and returns the handles of all the fonts in that family.
+    ///
+    /// Fontconfig matches the name against the family names of fonts in every language, so a
+    /// localized name such as "メイリオ" finds Meiryo. The family is then named by its English
+    /// name, if the fonts have one.
     pub fn select_family_by_name(&self, family_name: &str) -> Result<FamilyHandle, SelectionError> {
         use std::borrow::Cow;
 
@@ -129,23 +176,37 @@ impl FontconfigSource {
         let pattern = fc::Pattern::from_name(family_name.as_ref());
 
         let mut object_set = fc::ObjectSet::new();
+        object_set.push_string(fc::Object::Family);
+        object_set.push_string(fc::Object::FamilyLang);
         object_set.push_string(fc::Object::File);
         object_set.push_string(fc::Object::Index);
+        object_set.push_string(fc::Object::Weight);
+        object_set.push_string(fc::Object::Slant);
+        object_set.push_string(fc::Object::Width);
 
         let patterns = pattern
             .list(&self.config, object_set)
             .map_err(|_| SelectionError::NotFound)?;
 
         let mut handles = vec![];
+        let mut properties = vec![];
+        let mut matched_family_name = None;
         for patt in patterns {
             let font_path = patt.get_string(fc::Object::File).unwrap();
             let font_index = patt.get_integer(fc::Object::Index).unwrap() as u32;
             let handle = Handle::from_path(std::path::PathBuf::from(font_path), font_index);
             handles.push(handle);
+            properties.push(pattern_properties(&patt));
+            if matched_family_name.is_none() {
+                matched_family_name = english_family_name(&patt);
+            }
         }
 
         if !handles.is_empty() {
-            Ok(FamilyHandle::from_font_handles(handles.into_iter()))
+            let mut family = FamilyHandle::from_font_handles(handles.into_iter());
+            family.set_family_name(matched_family_name.unwrap_or_else(|| family_name.into_owned()));
+            family.sort_by_properties(properties);
+            Ok(family)
         } else {
             Err(SelectionError::NotFound)
         }
@@ -180,10 +241,85 @@ impl FontconfigSource {
         &self,
         postscript_name: &str,
     ) -> Result<Handle, SelectionError> {
+        source::select_by_postscript_name(self, postscript_name, |postscript_name| {
+            let mut pattern = fc::Pattern::new();
+            pattern.push_string(fc::Object::PostScriptName, postscript_name.to_owned());
+
+            // We want the file path and the font index.
+            let mut object_set = fc::ObjectSet::new();
+            object_set.push_string(fc::Object::File);
+            object_set.push_string(fc::Object::Index);
+
+            let patterns = pattern
+                .list(&self.config, object_set)
+                .map_err(|_| SelectionError::NotFound)?;
+
+            if let Some(patt) = patterns.into_iter().next() {
+                let font_path = patt.get_string(fc::Object::File).unwrap();
+                let font_index = patt.get_integer(fc::Object::Index).unwrap() as u32;
+                let handle = Handle::from_path(std::path::PathBuf::from(font_path), font_index);
+                Ok(handle)
+            } else {
+                Err(SelectionError::NotFound)
+            }
+        })
+    }
+
+    /// Returns the handles of all fonts whose properties match `properties`.
+    ///
+    /// This is a single Fontconfig list query; the fonts aren't loaded. Variable fonts are
+    /// represented by their named instances, since the default face reports a range of weights and
+    /// widths rather than a single value.
+    pub fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        let pattern = fc::Pattern::new();
+
+        let mut object_set = fc::ObjectSet::new();
+        object_set.push_string(fc::Object::File);
+        object_set.push_string(fc::Object::Index);
+        object_set.push_string(fc::Object::Weight);
+        object_set.push_string(fc::Object::Slant);
+        object_set.push_string(fc::Object::Width);
+
+        let patterns = pattern
+            .list(&self.config, object_set)
+            .map_err(|_| SelectionError::NotFound)?;
+
+        let mut handles = vec![];
+        for patt in patterns {
+            let (path, index) = match (
+                patt.get_string(fc::Object::File),
+                patt.get_integer(fc::Object::Index),
+            ) {
+                (Some(path), Some(index)) => (path, index),
+                _ => continue,
+            };
+            let candidate = match pattern_properties(&patt) {
+                Some(candidate) => candidate,
+                None => continue,
+            };
+            if matching::properties_match(&candidate, properties) {
+                handles.push(Handle::Path {
+                    path: path.into(),
+                    font_index: index as u32,
+                });
+            }
+        }
+
+        Ok(handles)
+    }
+
+    /// Returns the handles of all fonts that map every character of `text` to a glyph.
+    ///
+    /// This is a single Fontconfig list query against the character sets in its cache; the fonts
+    /// aren't loaded. Control characters are ignored.
+    pub fn fonts_covering(&self, text: &str) -> Result<Vec<Handle>, SelectionError> {
+        let mut char_set = fc::CharSet::new();
+        for c in source::coverage_chars(text) {
+            char_set.push_char(c);
+        }
         let mut pattern = fc::Pattern::new();
-        pattern.push_string(fc::Object::PostScriptName, postscript_name.to_owned());
+        pattern.push_char_set(fc::Object::CharSet, &char_set);
 
-        // We want the file path and the font index.
         let mut object_set = fc::ObjectSet::new();
         object_set.push_string(fc::Object::File);
         object_set.push_string(fc::Object::Index);
@@ -192,14 +328,57 @@ impl FontconfigSource {
             .list(&self.config, object_set)
             .map_err(|_| SelectionError::NotFound)?;
 
-        if let Some(patt) = patterns.into_iter().next() {
-            let font_path = patt.get_string(fc::Object::File).unwrap();
-            let font_index = patt.get_integer(fc::Object::Index).unwrap() as u32;
-            let handle = Handle::from_path(std::path::PathBuf::from(font_path), font_index);
-            Ok(handle)
-        } else {
-            Err(SelectionError::NotFound)
+        Ok(patterns
+            .into_iter()
+            .filter_map(|patt| {
+                match (
+                    patt.get_string(fc::Object::File),
+                    patt.get_integer(fc::Object::Index),
+                ) {
+                    (Some(path), Some(index)) => Some(Handle::Path {
+                        path: path.into(),
+                        font_index: index as u32,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the handle of a font that can display `c` when the font behind `base` can't, or
+    /// `None` if no font covers it.
+    ///
+    /// If `base` covers `c`, it is returned as is. Otherwise the fonts are tried in the order that
+    /// Fontconfig sorts them for the base font's family and `locale`, which is the order that
+    /// other Fontconfig clients fall back in.
+    pub fn fallback_for(&self, base: &Handle, c: char, locale: &str) -> Option<Handle> {
+        if let Some(handle) = source::platform_fallback_for(base, c, locale) {
+            return Some(handle);
         }
+
+        let family = Font::from_handle(base).ok()?.family_name();
+        let mut pattern = fc::Pattern::from_name(&family);
+        pattern.push_string(fc::Object::Lang, locale.to_owned());
+        pattern.config_substitute(fc::MatchKind::Pattern);
+        pattern.default_substitute();
+
+        let patterns = pattern.sorted(&self.config).ok()?;
+        patterns
+            .into_iter()
+            .filter_map(|patt| {
+                match (
+                    patt.get_string(fc::Object::File),
+                    patt.get_integer(fc::Object::Index),
+                ) {
+                    (Some(path), Some(index)) => Some(Handle::Path {
+                        path: path.into(),
+                        font_index: index as u32,
+                    }),
+                    _ => None,
+                }
+            })
+            .filter(|handle| !handle.refers_to_same_font(base))
+            .find(|handle| source::handle_covers_char(handle, c))
     }
 
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
@@ -212,6 +391,16 @@ impl FontconfigSource {
     ) -> Result<Handle, SelectionError> {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
+
+    /// Returns the fonts that the platform uses by default for sans-serif, serif, and monospace
+    /// text.
+    ///
+    /// They are selected on the first call and cached. See `Source::system_defaults()`.
+    pub fn system_defaults(&self) -> SystemDefaults {
+        self.system_defaults
+            .get_or_init(|| SystemDefaults::resolve(self))
+            .clone()
+    }
 }
 
 impl Source for FontconfigSource {
@@ -235,6 +424,38 @@ impl Source for FontconfigSource {
         self.select_by_postscript_name(postscript_name)
     }
 
+    #[inline]
+    fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        self.select_fonts(properties)
+    }
+
+    #[inline]
+    fn fonts_covering(&self, text: &str) -> Result<Vec<Handle>, SelectionError> {
+        self.fonts_covering(text)
+    }
+
+    #[inline]
+    fn fallback_for(&self, base: &Handle, c: char, locale: &str) -> Option<Handle> {
+        self.fallback_for(base, c, locale)
+    }
+
+    #[cfg(feature = "observer")]
+    fn set_observer(&mut self, observer: SourceObserver) {
+        self.set_observer(observer)
+    }
+
+    #[cfg(feature = "observer")]
+    fn notify(&self, event: SourceEvent) {
+        if let Some(ref observer) = self.observer {
+            observer(event)
+        }
+    }
+
+    #[inline]
+    fn system_defaults(&self) -> SystemDefaults {
+        self.system_defaults()
+    }
+
     #[inline]
     fn as_any(&self) -> &dyn Any {
         self
@@ -246,11 +467,43 @@ impl Source for FontconfigSource {
     }
 }
 
+// Returns the family name of a listed font whose language is English, or its first family name if
+// none is. Fonts list a family name for each language in their `name` table, with the matching
+// language at the same index of `familylang`.
+fn english_family_name(pattern: &fc::PatternRef) -> Option<String> {
+    let mut index = 0;
+    while let Some(family_name) = pattern.get_string_at(fc::Object::Family, index) {
+        if pattern
+            .get_string_at(fc::Object::FamilyLang, index)
+            .as_deref()
+            == Some("en")
+        {
+            return Some(family_name);
+        }
+        index += 1;
+    }
+    pattern.get_string(fc::Object::Family)
+}
+
+// Returns the properties Fontconfig lists for a font, if it lists single values for them. Variable
+// fonts list ranges instead.
+fn pattern_properties(pattern: &fc::PatternRef) -> Option<Properties> {
+    let weight = pattern.get_double(fc::Object::Weight)?;
+    let slant = pattern.get_integer(fc::Object::Slant)?;
+    let width = pattern.get_double(fc::Object::Width)?;
+    Some(Properties {
+        style: fc::style_from_slant(slant),
+        weight: Weight(fc::weight_to_opentype(weight)),
+        stretch: Stretch(width as f32 / 100.0),
+    })
+}
+
 // A minimal fontconfig wrapper.
 mod fc {
     #![allow(dead_code)]
 
     use fontconfig_sys as ffi;
+    use fontconfig_sys::constants::{FC_SLANT_ITALIC, FC_SLANT_OBLIQUE};
     use fontconfig_sys::ffi_dispatch;
 
     #[cfg(feature = "source-fontconfig-dlopen")]
@@ -262,6 +515,8 @@ mod fc {
     use std::os::raw::{c_char, c_uchar};
     use std::ptr;
 
+    use crate::properties::Style;
+
     #[derive(Clone, Copy)]
     pub enum Error {
         NoMatch,
@@ -291,18 +546,32 @@ mod fc {
     #[derive(Clone, Copy)]
     pub enum Object {
         Family,
+        FamilyLang,
         File,
         Index,
         PostScriptName,
+        Lang,
+        Weight,
+        Slant,
+        Width,
+        CharSet,
+        Symbol,
     }
 
     impl Object {
         fn as_bytes(&self) -> &[u8] {
             match self {
                 Object::Family => b"family\0",
+                Object::FamilyLang => b"familylang\0",
                 Object::File => b"file\0",
                 Object::Index => b"index\0",
                 Object::PostScriptName => b"postscriptname\0",
+                Object::Lang => b"lang\0",
+                Object::Weight => b"weight\0",
+                Object::Slant => b"slant\0",
+                Object::Width => b"width\0",
+                Object::CharSet => b"charset\0",
+                Object::Symbol => b"symbol\0",
             }
         }
 
@@ -398,6 +667,21 @@ mod fc {
             }
         }
 
+        // FcPatternAddCharSet
+        pub fn push_char_set(&mut self, object: Object, char_set: &CharSet) {
+            unsafe {
+                // The pattern takes its own reference to the character set.
+                ffi_dispatch!(
+                    feature = "source-fontconfig-dlopen",
+                    LIB,
+                    FcPatternAddCharSet,
+                    self.d,
+                    object.as_ptr(),
+                    char_set.d
+                );
+            }
+        }
+
         // FcConfigSubstitute
         pub fn config_substitute(&mut self, match_kind: MatchKind) {
             unsafe {
@@ -491,6 +775,11 @@ mod fc {
     impl PatternRef {
         // FcPatternGetString
         pub fn get_string(&self, object: Object) -> Option<String> {
+            self.get_string_at(object, 0)
+        }
+
+        // FcPatternGetString, for objects with several values
+        pub fn get_string_at(&self, object: Object, index: i32) -> Option<String> {
             unsafe {
                 let mut string = ptr::null_mut();
                 let res = ffi_dispatch!(
@@ -499,7 +788,7 @@ mod fc {
                     FcPatternGetString,
                     self.d,
                     object.as_ptr(),
-                    0,
+                    index,
                     &mut string
                 );
                 if res != ffi::FcResultMatch {
@@ -537,6 +826,51 @@ mod fc {
                 Some(integer)
             }
         }
+
+        // FcPatternGetBool
+        pub fn get_bool(&self, object: Object) -> Option<bool> {
+            unsafe {
+                let mut boolean = 0;
+                let res = ffi_dispatch!(
+                    feature = "source-fontconfig-dlopen",
+                    LIB,
+                    FcPatternGetBool,
+                    self.d,
+                    object.as_ptr(),
+                    0,
+                    &mut boolean
+                );
+                if res != ffi::FcResultMatch {
+                    return None;
+                }
+
+                Some(boolean != 0)
+            }
+        }
+
+        // FcPatternGetDouble
+        //
+        // Fontconfig converts integer values, so this works for any numeric property. Ranges, which
+        // variable fonts use, don't match.
+        pub fn get_double(&self, object: Object) -> Option<f64> {
+            unsafe {
+                let mut double = 0.0;
+                let res = ffi_dispatch!(
+                    feature = "source-fontconfig-dlopen",
+                    LIB,
+                    FcPatternGetDouble,
+                    self.d,
+                    object.as_ptr(),
+                    0,
+                    &mut double
+                );
+                if res != ffi::FcResultMatch {
+                    return None;
+                }
+
+                Some(double)
+            }
+        }
     }
 
     pub struct FontSet {
@@ -587,6 +921,47 @@ mod fc {
         }
     }
 
+    pub struct CharSet {
+        d: *mut ffi::FcCharSet,
+    }
+
+    impl CharSet {
+        // FcCharSetCreate
+        pub fn new() -> Self {
+            unsafe {
+                CharSet {
+                    d: ffi_dispatch!(feature = "source-fontconfig-dlopen", LIB, FcCharSetCreate,),
+                }
+            }
+        }
+
+        // FcCharSetAddChar
+        pub fn push_char(&mut self, c: char) {
+            unsafe {
+                ffi_dispatch!(
+                    feature = "source-fontconfig-dlopen",
+                    LIB,
+                    FcCharSetAddChar,
+                    self.d,
+                    c as u32
+                );
+            }
+        }
+    }
+
+    impl Drop for CharSet {
+        fn drop(&mut self) {
+            unsafe {
+                ffi_dispatch!(
+                    feature = "source-fontconfig-dlopen",
+                    LIB,
+                    FcCharSetDestroy,
+                    self.d
+                )
+            }
+        }
+    }
+
     pub struct ObjectSet {
         d: *mut ffi::FcObjectSet,
     }
@@ -632,4 +1007,43 @@ mod fc {
             }
         }
     }
+
+    // Pairs of Fontconfig and OpenType weights, as in `FcWeightToOpenTypeDouble()`, which not all
+    // versions of the bindings expose.
+    const WEIGHT_MAP: [(f64, f64); 12] = [
+        (0.0, 100.0),
+        (40.0, 200.0),
+        (50.0, 300.0),
+        (55.0, 350.0),
+        (75.0, 380.0),
+        (80.0, 400.0),
+        (100.0, 500.0),
+        (180.0, 600.0),
+        (200.0, 700.0),
+        (205.0, 800.0),
+        (210.0, 900.0),
+        (215.0, 1000.0),
+    ];
+
+    /// Converts a Fontconfig weight to an OpenType one, interpolating linearly between the
+    /// standard weights.
+    pub fn weight_to_opentype(weight: f64) -> f32 {
+        let weight = weight.max(0.0).min(WEIGHT_MAP[WEIGHT_MAP.len() - 1].0);
+        let upper = WEIGHT_MAP
+            .iter()
+            .position(|&(fc_weight, _)| fc_weight >= weight)
+            .unwrap_or(WEIGHT_MAP.len() - 1)
+            .max(1);
+        let ((fc_lo, ot_lo), (fc_hi, ot_hi)) = (WEIGHT_MAP[upper - 1], WEIGHT_MAP[upper]);
+        (ot_lo + (weight - fc_lo) * (ot_hi - ot_lo) / (fc_hi - fc_lo)) as f32
+    }
+
+    /// Converts a Fontconfig slant to a style.
+    pub fn style_from_slant(slant: i32) -> Style {
+        match slant {
+            slant if slant >= FC_SLANT_OBLIQUE => Style::Oblique(Style::DEFAULT_OBLIQUE_ANGLE),
+            slant if slant >= FC_SLANT_ITALIC => Style::Italic,
+            _ => Style::Normal,
+        }
+    }
 }
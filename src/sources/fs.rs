@@ -13,12 +13,28 @@
 //! This source uses the WalkDir abstraction from the `walkdir` crate to locate fonts.
 //!
 //! This is the native source on Android and OpenHarmony.
+//!
+//! With the `cache` feature, the fonts that were found can be saved to disk with
+//! `FsSource::save_cache()` and reloaded with `FsSource::load_cached()`, which only loads fonts
+//! in files and directories that changed since.
 
 use std::any::Any;
 use std::fs::File;
 use std::path::{Path, PathBuf};
+#[cfg(not(feature = "cache"))]
 use walkdir::WalkDir;
 
+#[cfg(feature = "cache")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "cache")]
+use std::collections::HashMap;
+#[cfg(feature = "cache")]
+use std::fs;
+#[cfg(feature = "cache")]
+use std::io::{self, BufReader, BufWriter, Write};
+#[cfg(feature = "cache")]
+use std::time::SystemTime;
+
 #[cfg(not(any(target_os = "android", target_family = "windows", target_env = "ohos")))]
 use dirs;
 #[cfg(target_family = "windows")]
@@ -36,8 +52,14 @@ use crate::family_name::FamilyName;
 use crate::file_type::FileType;
 use crate::font::Font;
 use crate::handle::Handle;
+#[cfg(feature = "cache")]
+use crate::loader::Loader;
 use crate::properties::Properties;
 use crate::source::Source;
+#[cfg(feature = "observer")]
+use crate::source::{SourceEvent, SourceObserver};
+#[cfg(feature = "cache")]
+use crate::sources::mem::FamilyEntry;
 use crate::sources::mem::MemSource;
 
 /// A source that loads fonts from a directory or directories on disk.
@@ -48,6 +70,8 @@ use crate::sources::mem::MemSource;
 #[allow(missing_debug_implementations)]
 pub struct FsSource {
     mem_source: MemSource,
+    #[cfg(feature = "cache")]
+    snapshot: Snapshot,
 }
 
 impl Default for FsSource {
@@ -63,17 +87,29 @@ impl FsSource {
     /// effort to locate fonts in the typical platform directories, but it is too simple to pick up
     /// fonts that are stored in unusual locations but nevertheless properly installed.
     pub fn new() -> FsSource {
+        Self::from_directories(default_font_directories(), &|_| {})
+    }
+
+    // `on_directory` is called with each directory as it is scanned.
+    #[cfg(not(feature = "cache"))]
+    fn from_directories(directories: Vec<PathBuf>, on_directory: &dyn Fn(&Path)) -> FsSource {
         let mut fonts = vec![];
-        for font_directory in default_font_directories() {
-            fonts.extend(Self::discover_fonts(&font_directory));
+        for directory in &directories {
+            fonts.extend(Self::discover_fonts(directory, on_directory));
         }
 
         FsSource {
-            mem_source: MemSource::from_fonts(fonts.into_iter()).unwrap(),
+            mem_source: MemSource::from_fonts(fonts).unwrap(),
         }
     }
 
-    fn discover_fonts(path: &Path) -> Vec<Handle> {
+    #[cfg(feature = "cache")]
+    fn from_directories(directories: Vec<PathBuf>, on_directory: &dyn Fn(&Path)) -> FsSource {
+        Self::from_snapshot(Snapshot::scan(directories, None, on_directory))
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn discover_fonts(path: &Path, on_directory: &dyn Fn(&Path)) -> Vec<Handle> {
         let mut fonts = vec![];
         for directory_entry in WalkDir::new(path).into_iter() {
             let directory_entry = match directory_entry {
@@ -81,13 +117,19 @@ impl FsSource {
                 Err(_) => continue,
             };
             let path = directory_entry.path();
+            if directory_entry.file_type().is_dir() {
+                on_directory(path);
+                continue;
+            }
             let mut file = match File::open(path) {
                 Err(_) => continue,
                 Ok(file) => file,
             };
             match Font::analyze_file(&mut file) {
                 Err(_) => continue,
-                Ok(FileType::Single) => fonts.push(Handle::from_path(path.to_owned(), 0)),
+                Ok(FileType::Single) | Ok(FileType::Type1) => {
+                    fonts.push(Handle::from_path(path.to_owned(), 0))
+                }
                 Ok(FileType::Collection(font_count)) => {
                     for font_index in 0..font_count {
                         fonts.push(Handle::from_path(path.to_owned(), font_index))
@@ -103,12 +145,111 @@ impl FsSource {
     where
         P: AsRef<Path>,
     {
-        let fonts = Self::discover_fonts(path.as_ref());
+        Self::from_directories(vec![path.as_ref().to_owned()], &|_| {})
+    }
+
+    /// Indexes all fonts found in `path`, reporting each directory scanned and the number of
+    /// fonts found to `observer`.
+    ///
+    /// The observer is kept afterward, as if set with `set_observer()`.
+    #[cfg(feature = "observer")]
+    pub fn in_path_with_observer<P>(path: P, observer: SourceObserver) -> FsSource
+    where
+        P: AsRef<Path>,
+    {
+        let mut source = Self::from_directories(vec![path.as_ref().to_owned()], &|directory| {
+            observer(SourceEvent::ScanningDirectory(directory.to_owned()))
+        });
+        let font_count = source.all_fonts().map_or(0, |fonts| fonts.len());
+        observer(SourceEvent::ParsedFonts(font_count));
+        source.set_observer(observer);
+        source
+    }
+
+    /// Sets a function that is called with events describing the work this source does.
+    ///
+    /// Filesystem sources scan their directories when they are created, so only selection is
+    /// reported afterward; use `in_path_with_observer()` to observe the scan too.
+    #[cfg(feature = "observer")]
+    pub fn set_observer(&mut self, observer: SourceObserver) {
+        self.mem_source.set_observer(observer)
+    }
+
+    /// Reindexes the directories recorded in a cache written by `save_cache()`.
+    ///
+    /// Directories whose modification time is unchanged are not listed again, and fonts are only
+    /// loaded from files that are new or were modified since the cache was saved, so this is much
+    /// faster than `new()` when few fonts have changed. Returns an error if the cache can't be
+    /// read or is malformed; callers will usually fall back to `new()` or `in_path()`.
+    #[cfg(feature = "cache")]
+    pub fn load_cached<P>(path: P) -> io::Result<FsSource>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = BufReader::new(File::open(path)?);
+        let snapshot: Snapshot = serde_json::from_reader(reader)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if snapshot.version != Snapshot::VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unsupported font cache version",
+            ));
+        }
+        let roots = snapshot.roots.clone();
+        Ok(Self::from_snapshot(Snapshot::scan(
+            roots,
+            Some(&snapshot),
+            &|_| {},
+        )))
+    }
+
+    /// Writes the fonts in this source to `path`, along with the modification times of their files
+    /// and directories, so that `load_cached()` can restore it.
+    ///
+    /// Paths that aren't valid UTF-8 can't be stored and cause an error.
+    #[cfg(feature = "cache")]
+    pub fn save_cache<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, &self.snapshot)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        writer.flush()
+    }
+
+    #[cfg(feature = "cache")]
+    fn from_snapshot(snapshot: Snapshot) -> FsSource {
+        let mut families = vec![];
+        for file in snapshot
+            .directories
+            .iter()
+            .flat_map(|directory| &directory.files)
+        {
+            for font in &file.fonts {
+                families.push(FamilyEntry {
+                    family_name: font.family_name.clone(),
+                    localized_family_names: font.localized_family_names.clone(),
+                    postscript_name: font.postscript_name.clone(),
+                    properties: font.properties,
+                    font: Handle::from_path(file.path.clone(), font.index),
+                });
+            }
+        }
         FsSource {
-            mem_source: MemSource::from_fonts(fonts.into_iter()).unwrap(),
+            mem_source: MemSource::from_entries(families),
+            snapshot,
         }
     }
 
+    /// Sets whether `all_families()` lists hidden families, whose names start with a period.
+    ///
+    /// Hidden families are excluded by default. They can always be selected by name.
+    pub fn set_include_hidden_fonts(&mut self, include_hidden_fonts: bool) {
+        self.mem_source
+            .set_include_hidden_fonts(include_hidden_fonts)
+    }
+
     /// Returns paths of all fonts installed on the system.
     pub fn all_fonts(&self) -> Result<Vec<Handle>, SelectionError> {
         self.mem_source.all_fonts()
@@ -135,6 +276,18 @@ impl FsSource {
         self.mem_source.select_by_postscript_name(postscript_name)
     }
 
+    /// Returns the family name and properties of the font that `handle` refers to, if it's in
+    /// this source, without loading the font again.
+    pub fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
+        self.mem_source.describe_handle(handle)
+    }
+
+    /// Returns the handles of all fonts whose properties match `properties`, without loading the
+    /// fonts again.
+    pub fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        self.mem_source.select_fonts(properties)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -166,6 +319,24 @@ impl Source for FsSource {
         self.select_by_postscript_name(postscript_name)
     }
 
+    fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
+        self.describe_handle(handle)
+    }
+
+    fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        self.select_fonts(properties)
+    }
+
+    #[cfg(feature = "observer")]
+    fn set_observer(&mut self, observer: SourceObserver) {
+        self.set_observer(observer)
+    }
+
+    #[cfg(feature = "observer")]
+    fn notify(&self, event: SourceEvent) {
+        self.mem_source.notify(event)
+    }
+
     #[inline]
     fn as_any(&self) -> &dyn Any {
         self
@@ -177,6 +348,174 @@ impl Source for FsSource {
     }
 }
 
+/// The directories an `FsSource` indexed and the fonts it found in them, as saved in a cache.
+#[cfg(feature = "cache")]
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    roots: Vec<PathBuf>,
+    directories: Vec<CachedDirectory>,
+}
+
+#[cfg(feature = "cache")]
+#[derive(Serialize, Deserialize)]
+struct CachedDirectory {
+    path: PathBuf,
+    modified: SystemTime,
+    files: Vec<CachedFile>,
+    subdirectories: Vec<PathBuf>,
+}
+
+#[cfg(feature = "cache")]
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedFile {
+    path: PathBuf,
+    modified: SystemTime,
+    fonts: Vec<CachedFont>,
+}
+
+#[cfg(feature = "cache")]
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedFont {
+    index: u32,
+    family_name: String,
+    localized_family_names: Vec<String>,
+    postscript_name: String,
+    properties: Properties,
+}
+
+#[cfg(feature = "cache")]
+impl Snapshot {
+    const VERSION: u32 = 2;
+
+    /// Walks `roots`, reusing the listings of directories and the fonts of files in `previous`
+    /// whose modification times haven't changed. `on_directory` is called with each directory
+    /// visited.
+    fn scan(
+        roots: Vec<PathBuf>,
+        previous: Option<&Snapshot>,
+        on_directory: &dyn Fn(&Path),
+    ) -> Snapshot {
+        let mut previous_directories = HashMap::new();
+        let mut previous_files = HashMap::new();
+        for directory in previous.iter().flat_map(|snapshot| &snapshot.directories) {
+            previous_directories.insert(&*directory.path, directory);
+            for file in &directory.files {
+                previous_files.insert(&*file.path, file);
+            }
+        }
+
+        let mut directories = vec![];
+        let mut pending: Vec<PathBuf> = roots.iter().rev().cloned().collect();
+        while let Some(path) = pending.pop() {
+            let modified = match fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+                Ok(modified) => modified,
+                Err(_) => continue,
+            };
+            on_directory(&path);
+
+            let (file_paths, subdirectories) = match previous_directories.get(&*path) {
+                Some(directory) if directory.modified == modified => (
+                    directory
+                        .files
+                        .iter()
+                        .map(|file| file.path.clone())
+                        .collect(),
+                    directory.subdirectories.clone(),
+                ),
+                _ => match list_directory(&path) {
+                    Ok(listing) => listing,
+                    Err(_) => continue,
+                },
+            };
+
+            let mut files = vec![];
+            for file_path in file_paths {
+                let modified =
+                    match fs::metadata(&file_path).and_then(|metadata| metadata.modified()) {
+                        Ok(modified) => modified,
+                        Err(_) => continue,
+                    };
+                match previous_files.get(&*file_path) {
+                    Some(file) if file.modified == modified => files.push((*file).clone()),
+                    _ => files.push(CachedFile {
+                        fonts: describe_fonts_in_file(&file_path),
+                        path: file_path,
+                        modified,
+                    }),
+                }
+            }
+
+            pending.extend(subdirectories.iter().rev().cloned());
+            directories.push(CachedDirectory {
+                path,
+                modified,
+                files,
+                subdirectories,
+            });
+        }
+
+        Snapshot {
+            version: Snapshot::VERSION,
+            roots,
+            directories,
+        }
+    }
+}
+
+/// Returns the files and subdirectories in `path`, sorted. Like `WalkDir`, symbolic links to
+/// directories aren't followed.
+#[cfg(feature = "cache")]
+fn list_directory(path: &Path) -> io::Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let (mut files, mut subdirectories) = (vec![], vec![]);
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            subdirectories.push(entry.path());
+        } else {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    subdirectories.sort();
+    Ok((files, subdirectories))
+}
+
+/// Loads every font in the file at `path` and records its names and properties. Files that
+/// aren't fonts yield an empty list, so they aren't examined again while they stay unchanged.
+#[cfg(feature = "cache")]
+fn describe_fonts_in_file(path: &Path) -> Vec<CachedFont> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return vec![],
+    };
+    let font_count = match Font::analyze_file(&mut file) {
+        Ok(FileType::Single) | Ok(FileType::Type1) => 1,
+        Ok(FileType::Collection(font_count)) => font_count,
+        Err(_) => return vec![],
+    };
+
+    let mut fonts = vec![];
+    for index in 0..font_count {
+        let font = match Font::from_file(&mut file, index) {
+            Ok(font) => font,
+            Err(_) => continue,
+        };
+        if let Some(postscript_name) = font.postscript_name() {
+            let mut localized_family_names = font.localized_family_names();
+            let family_name = localized_family_names.remove(0);
+            fonts.push(CachedFont {
+                index,
+                family_name,
+                localized_family_names,
+                postscript_name,
+                properties: font.properties(),
+            });
+        }
+    }
+    fonts
+}
+
 #[cfg(any(target_os = "android", target_env = "ohos"))]
 fn default_font_directories() -> Vec<PathBuf> {
     vec![PathBuf::from("/system/fonts")]
@@ -11,39 +11,87 @@
 //! A source that keeps fonts in memory.
 
 use crate::error::{FontLoadingError, SelectionError};
-use crate::family_handle::FamilyHandle;
+use crate::family_handle::{self, FamilyHandle};
 use crate::family_name::FamilyName;
 use crate::font::Font;
 use crate::handle::Handle;
+use crate::loader::Loader;
+use crate::matching;
 use crate::properties::Properties;
-use crate::source::Source;
+use crate::source::{self, Source};
+#[cfg(feature = "observer")]
+use crate::source::{SourceEvent, SourceObserver};
 use std::any::Any;
 
 /// A source that keeps fonts in memory.
 #[allow(missing_debug_implementations)]
 pub struct MemSource {
     families: Vec<FamilyEntry>,
+    include_hidden_fonts: bool,
+    #[cfg(feature = "observer")]
+    observer: Option<SourceObserver>,
 }
 
 impl MemSource {
     /// Creates a new empty memory source.
     pub fn empty() -> MemSource {
-        MemSource { families: vec![] }
+        MemSource {
+            families: vec![],
+            include_hidden_fonts: false,
+            #[cfg(feature = "observer")]
+            observer: None,
+        }
     }
 
-    /// Creates a new memory source that contains the given set of font handles.
+    /// Creates a new memory source that contains the given set of font handles, grouped into
+    /// families by their family names.
     ///
-    /// The fonts referenced by the handles are eagerly loaded into memory.
+    /// The fonts referenced by the handles are eagerly loaded into memory. Together with
+    /// `Handle::from_memory()` and `include_bytes!`, this allows a fully self-contained binary to
+    /// ship its own fonts; wrap the result in a `MultiSource` to prefer them over system fonts.
     pub fn from_fonts<I>(fonts: I) -> Result<MemSource, FontLoadingError>
     where
-        I: Iterator<Item = Handle>,
+        I: IntoIterator<Item = Handle>,
     {
         let mut families = vec![];
         for handle in fonts {
             add_font(handle, &mut families)?;
         }
-        families.sort_by(|a, b| a.family_name.cmp(&b.family_name));
-        Ok(MemSource { families })
+        sort_families(&mut families);
+        Ok(MemSource {
+            families,
+            include_hidden_fonts: false,
+            #[cfg(feature = "observer")]
+            observer: None,
+        })
+    }
+
+    /// Creates a new memory source from fonts whose names and properties are already known,
+    /// without loading them.
+    #[cfg(feature = "cache")]
+    pub(crate) fn from_entries(mut families: Vec<FamilyEntry>) -> MemSource {
+        sort_families(&mut families);
+        MemSource {
+            families,
+            include_hidden_fonts: false,
+            #[cfg(feature = "observer")]
+            observer: None,
+        }
+    }
+
+    /// Sets whether `all_families()` lists hidden families, whose names start with a period.
+    ///
+    /// Hidden families are excluded by default. They can always be selected by name.
+    pub fn set_include_hidden_fonts(&mut self, include_hidden_fonts: bool) {
+        self.include_hidden_fonts = include_hidden_fonts;
+    }
+
+    /// Sets a function that is called with events describing the work this source does.
+    ///
+    /// Memory sources report how long `select_best_match()` takes.
+    #[cfg(feature = "observer")]
+    pub fn set_observer(&mut self, observer: SourceObserver) {
+        self.observer = Some(observer);
     }
 
     /// Add an existing font handle to a `MemSource`.
@@ -55,8 +103,7 @@ impl MemSource {
     /// end.
     pub fn add_font(&mut self, handle: Handle) -> Result<Font, FontLoadingError> {
         let font = add_font(handle, &mut self.families)?;
-        self.families
-            .sort_by(|a, b| a.family_name.cmp(&b.family_name));
+        sort_families(&mut self.families);
         Ok(font)
     }
 
@@ -67,13 +114,12 @@ impl MemSource {
     /// method.
     pub fn add_fonts(
         &mut self,
-        handles: impl Iterator<Item = Handle>,
+        handles: impl IntoIterator<Item = Handle>,
     ) -> Result<(), FontLoadingError> {
         for handle in handles {
             add_font(handle, &mut self.families)?;
         }
-        self.families
-            .sort_by(|a, b| a.family_name.cmp(&b.family_name));
+        sort_families(&mut self.families);
         Ok(())
     }
 
@@ -90,7 +136,10 @@ impl MemSource {
     pub fn all_families(&self) -> Result<Vec<String>, SelectionError> {
         let mut families = vec![];
         for family in &self.families {
-            if families.last() == Some(&family.family_name) {
+            if families.last() == Some(&family.family_name)
+                || (!self.include_hidden_fonts
+                    && source::is_hidden_family_name(&family.family_name))
+            {
                 continue;
             }
 
@@ -102,12 +151,28 @@ impl MemSource {
 
     /// Looks up a font family by name and returns the handles of all the fonts in that family.
     ///
+    /// The name can also be one of the family's localized names from the fonts' `name` tables,
+    /// such as "メイリオ" for Meiryo; the family is still named by its default name.
+    ///
     /// FIXME(pcwalton): Case-insensitive comparison.
     pub fn select_family_by_name(&self, family_name: &str) -> Result<FamilyHandle, SelectionError> {
-        let mut first_family_index = self
+        let mut first_family_index = match self
             .families
             .binary_search_by(|family| (*family.family_name).cmp(family_name))
-            .map_err(|_| SelectionError::NotFound)?;
+        {
+            Ok(family_index) => family_index,
+            Err(_) => {
+                return match self.families.iter().find(|family| {
+                    family
+                        .localized_family_names
+                        .iter()
+                        .any(|localized_family_name| localized_family_name == family_name)
+                }) {
+                    Some(family) => self.select_family_by_name(&family.family_name),
+                    None => Err(SelectionError::NotFound),
+                };
+            }
+        };
 
         while first_family_index > 0
             && self.families[first_family_index - 1].family_name == family_name
@@ -122,9 +187,10 @@ impl MemSource {
         }
 
         let families = &self.families[first_family_index..(last_family_index + 1)];
-        Ok(FamilyHandle::from_font_handles(
-            families.iter().map(|family| family.font.clone()),
-        ))
+        let mut family_handle =
+            FamilyHandle::from_font_handles(families.iter().map(|family| family.font.clone()));
+        family_handle.set_family_name(families[0].family_name.clone());
+        Ok(family_handle)
     }
 
     /// Selects a font by PostScript name, which should be a unique identifier.
@@ -135,12 +201,37 @@ impl MemSource {
         &self,
         postscript_name: &str,
     ) -> Result<Handle, SelectionError> {
+        source::select_by_postscript_name(self, postscript_name, |postscript_name| {
+            self.families
+                .iter()
+                .filter(|family_entry| family_entry.postscript_name == postscript_name)
+                .map(|family_entry| family_entry.font.clone())
+                .next()
+                .ok_or(SelectionError::NotFound)
+        })
+    }
+
+    /// Returns the family name and properties of the font that `handle` refers to, if it's in
+    /// this source.
+    ///
+    /// The properties were read when the font was added, so the font isn't loaded again.
+    pub fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
         self.families
             .iter()
-            .filter(|family_entry| family_entry.postscript_name == postscript_name)
+            .find(|family_entry| family_entry.font.refers_to_same_font(handle))
+            .map(|family_entry| (family_entry.family_name.clone(), family_entry.properties))
+    }
+
+    /// Returns the handles of all fonts whose properties match `properties`.
+    ///
+    /// The properties were read when the fonts were added, so nothing is loaded again.
+    pub fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        Ok(self
+            .families
+            .iter()
+            .filter(|family_entry| matching::properties_match(&family_entry.properties, properties))
             .map(|family_entry| family_entry.font.clone())
-            .next()
-            .ok_or(SelectionError::NotFound)
+            .collect())
     }
 
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
@@ -174,6 +265,26 @@ impl Source for MemSource {
         self.select_by_postscript_name(postscript_name)
     }
 
+    fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
+        self.describe_handle(handle)
+    }
+
+    fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        self.select_fonts(properties)
+    }
+
+    #[cfg(feature = "observer")]
+    fn set_observer(&mut self, observer: SourceObserver) {
+        self.set_observer(observer)
+    }
+
+    #[cfg(feature = "observer")]
+    fn notify(&self, event: SourceEvent) {
+        if let Some(ref observer) = self.observer {
+            observer(event)
+        }
+    }
+
     #[inline]
     fn as_any(&self) -> &dyn Any {
         self
@@ -189,17 +300,33 @@ impl Source for MemSource {
 fn add_font(handle: Handle, families: &mut Vec<FamilyEntry>) -> Result<Font, FontLoadingError> {
     let font = Font::from_handle(&handle)?;
     if let Some(postscript_name) = font.postscript_name() {
+        let mut localized_family_names = font.localized_family_names();
+        let family_name = localized_family_names.remove(0);
         families.push(FamilyEntry {
-            family_name: font.family_name(),
+            family_name,
+            localized_family_names,
             postscript_name,
+            properties: font.properties(),
             font: handle,
         })
     }
     Ok(font)
 }
 
-struct FamilyEntry {
-    family_name: String,
-    postscript_name: String,
-    font: Handle,
+/// Sorts fonts by family name, and the fonts within each family by their properties.
+fn sort_families(families: &mut [FamilyEntry]) {
+    families.sort_by(|a, b| {
+        a.family_name
+            .cmp(&b.family_name)
+            .then_with(|| family_handle::compare_properties(&a.properties, &b.properties))
+    });
+}
+
+pub(crate) struct FamilyEntry {
+    pub(crate) family_name: String,
+    // The other names of the family, in any language.
+    pub(crate) localized_family_names: Vec<String>,
+    pub(crate) postscript_name: String,
+    pub(crate) properties: Properties,
+    pub(crate) font: Handle,
 }
@@ -16,9 +16,13 @@
 use crate::error::SelectionError;
 use crate::family_handle::FamilyHandle;
 use crate::family_name::FamilyName;
-use crate::handle::Handle;
+use crate::handle::{self, Handle};
 use crate::properties::Properties;
 use crate::source::Source;
+#[cfg(feature = "observer")]
+use crate::source::{SourceEvent, SourceObserver};
+#[cfg(feature = "observer")]
+use std::sync::Arc;
 use std::{
     any::Any,
     fmt,
@@ -33,12 +37,18 @@ use std::{
 #[allow(missing_debug_implementations)]
 pub struct MultiSource {
     subsources: Vec<Box<dyn Source>>,
+    #[cfg(feature = "observer")]
+    observer: Option<Arc<dyn Fn(SourceEvent) + Send + Sync>>,
 }
 
 impl MultiSource {
     /// Creates a new source that contains all the fonts in the supplied sources.
     pub fn from_sources(subsources: Vec<Box<dyn Source>>) -> MultiSource {
-        MultiSource { subsources }
+        MultiSource {
+            subsources,
+            #[cfg(feature = "observer")]
+            observer: None,
+        }
     }
 
     /// Returns paths of all fonts installed on the system.
@@ -60,15 +70,39 @@ impl MultiSource {
     }
 
     /// Looks up a font family by name and returns the handles of all the fonts in that family.
+    ///
+    /// Unlike the other lookups, this doesn't stop at the first subsource that has the family:
+    /// families of that name in several subsources are merged, under the name recorded by the
+    /// first, with the first subsource's fonts listed first. A font that appears in more than one
+    /// of them, even by path in one and in memory in another, is listed once, with the handle from
+    /// the first. Once the family has been found, errors from later subsources are logged and
+    /// skipped.
     pub fn select_family_by_name(&self, family_name: &str) -> Result<FamilyHandle, SelectionError> {
+        let mut merged_family: Option<FamilyHandle> = None;
+        let mut merged = false;
         for subsource in &self.subsources {
-            match subsource.select_family_by_name(family_name) {
-                Ok(family) => return Ok(family),
-                Err(SelectionError::NotFound) => {}
-                Err(err) => return Err(err),
+            match (
+                subsource.select_family_by_name(family_name),
+                &mut merged_family,
+            ) {
+                (Ok(family), Some(merged_family)) => {
+                    merged_family.fonts.extend(family.fonts);
+                    merged = true;
+                }
+                (Ok(family), None) => merged_family = Some(family),
+                (Err(SelectionError::NotFound), _) => {}
+                (Err(err), Some(_)) => {
+                    log::warn!("Error selecting family from subsource: {:?}", err)
+                }
+                (Err(err), None) => return Err(err),
             }
         }
-        Err(SelectionError::NotFound)
+        let mut family = merged_family.ok_or(SelectionError::NotFound)?;
+        // A family from a single subsource needs no deduplication, so don't touch its files.
+        if merged {
+            handle::dedup_handles(&mut family.fonts);
+        }
+        Ok(family)
     }
 
     /// Selects a font by PostScript name, which should be a unique identifier.
@@ -86,6 +120,33 @@ impl MultiSource {
         Err(SelectionError::NotFound)
     }
 
+    /// Returns the family name and properties that the first subsource containing `handle`
+    /// associates with it.
+    pub fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
+        self.subsources
+            .iter()
+            .find_map(|subsource| subsource.describe_handle(handle))
+    }
+
+    /// Returns the handles of all fonts in all subsources whose properties match `properties`.
+    pub fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        let mut handles = vec![];
+        for subsource in &self.subsources {
+            handles.extend(subsource.select_fonts(properties)?)
+        }
+        Ok(handles)
+    }
+
+    /// Returns the handles of all fonts in all subsources that map every character of `text` to a
+    /// glyph.
+    pub fn fonts_covering(&self, text: &str) -> Result<Vec<Handle>, SelectionError> {
+        let mut handles = vec![];
+        for subsource in &self.subsources {
+            handles.extend(subsource.fonts_covering(text)?)
+        }
+        Ok(handles)
+    }
+
     /// Performs font matching according to the CSS Fonts Level 3 specification and returns the
     /// handle.
     #[inline]
@@ -97,6 +158,18 @@ impl MultiSource {
         <Self as Source>::select_best_match(self, family_names, properties)
     }
 
+    /// Sets a function that is called with events describing the work this source and all of its
+    /// subsources do.
+    #[cfg(feature = "observer")]
+    pub fn set_observer(&mut self, observer: SourceObserver) {
+        let observer: Arc<dyn Fn(SourceEvent) + Send + Sync> = Arc::from(observer);
+        for subsource in &mut self.subsources {
+            let observer = observer.clone();
+            subsource.set_observer(Box::new(move |event| observer(event)));
+        }
+        self.observer = Some(observer);
+    }
+
     /// Returns an iterator over the contained sources.
     #[inline]
     pub fn iter(&self) -> MultiIter<'_> {
@@ -145,6 +218,33 @@ impl Source for MultiSource {
         self.select_by_postscript_name(postscript_name)
     }
 
+    #[inline]
+    fn describe_handle(&self, handle: &Handle) -> Option<(String, Properties)> {
+        self.describe_handle(handle)
+    }
+
+    #[inline]
+    fn select_fonts(&self, properties: &Properties) -> Result<Vec<Handle>, SelectionError> {
+        self.select_fonts(properties)
+    }
+
+    #[inline]
+    fn fonts_covering(&self, text: &str) -> Result<Vec<Handle>, SelectionError> {
+        self.fonts_covering(text)
+    }
+
+    #[cfg(feature = "observer")]
+    fn set_observer(&mut self, observer: SourceObserver) {
+        self.set_observer(observer)
+    }
+
+    #[cfg(feature = "observer")]
+    fn notify(&self, event: SourceEvent) {
+        if let Some(ref observer) = self.observer {
+            observer(event)
+        }
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
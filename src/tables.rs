@@ -0,0 +1,849 @@
+// font-kit/src/tables.rs
+//
+// Copyright © 2026 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Minimal readers for OpenType tables whose contents the native APIs don't expose uniformly.
+//!
+//! All functions take the raw table bytes as returned by `Loader::load_font_table()` and return
+//! `None` if the table is truncated.
+
+use byteorder::{BigEndian, ByteOrder};
+use std::convert::TryFrom;
+use std::ops::Range;
+
+use crate::loader::{GlyphMapping, GlyphStats, VariationAxis};
+
+/// The `BASE` table tag.
+pub(crate) const TAG_BASE: u32 = 0x42415345;
+/// The `CBDT` table tag.
+pub(crate) const TAG_CBDT: u32 = 0x43424454;
+/// The `CBLC` table tag.
+pub(crate) const TAG_CBLC: u32 = 0x43424c43;
+/// The `CFF ` table tag.
+pub(crate) const TAG_CFF: u32 = 0x43464620;
+/// The `CFF2` table tag.
+pub(crate) const TAG_CFF2: u32 = 0x43464632;
+/// The `cmap` table tag.
+pub(crate) const TAG_CMAP: u32 = 0x636d6170;
+/// The `fvar` table tag.
+pub(crate) const TAG_FVAR: u32 = 0x66766172;
+/// The `GDEF` table tag.
+pub(crate) const TAG_GDEF: u32 = 0x47444546;
+/// The `glyf` table tag.
+pub(crate) const TAG_GLYF: u32 = 0x676c7966;
+/// The `head` table tag.
+pub(crate) const TAG_HEAD: u32 = 0x68656164;
+/// The `hdmx` table tag.
+pub(crate) const TAG_HDMX: u32 = 0x68646d78;
+/// The `hhea` table tag.
+pub(crate) const TAG_HHEA: u32 = 0x68686561;
+/// The `loca` table tag.
+pub(crate) const TAG_LOCA: u32 = 0x6c6f6361;
+/// The `LTSH` table tag.
+pub(crate) const TAG_LTSH: u32 = 0x4c545348;
+/// The `meta` table tag.
+pub(crate) const TAG_META: u32 = 0x6d657461;
+/// The `morx` table tag.
+pub(crate) const TAG_MORX: u32 = 0x6d6f7278;
+/// The `name` table tag.
+pub(crate) const TAG_NAME: u32 = 0x6e616d65;
+/// The `OS/2` table tag.
+pub(crate) const TAG_OS_2: u32 = 0x4f532f32;
+/// The `post` table tag.
+pub(crate) const TAG_POST: u32 = 0x706f7374;
+/// The `sbix` table tag.
+pub(crate) const TAG_SBIX: u32 = 0x73626978;
+
+/// The `meta` tag for design languages.
+pub(crate) const META_TAG_DLNG: u32 = 0x646c6e67;
+/// The `meta` tag for supported languages.
+pub(crate) const META_TAG_SLNG: u32 = 0x736c6e67;
+
+/// The tag of the default script in OpenType layout tables.
+const TAG_DFLT: u32 = 0x44464c54;
+
+/// The number of glyph names in the standard Macintosh ordering used by `post` formats 1 and 2.
+const MAC_GLYPH_NAME_COUNT: usize = 258;
+
+/// The standard Macintosh glyph ordering.
+#[rustfmt::skip]
+static MAC_GLYPH_NAMES: [&str; MAC_GLYPH_NAME_COUNT] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+    "percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+    "hyphen", "period", "slash", "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B",
+    "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum",
+    "underscore", "grave", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright",
+    "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis",
+    "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute",
+    "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde",
+    "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex",
+    "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph",
+    "germandbls", "registered", "copyright", "trademark", "acute", "dieresis", "notequal", "AE",
+    "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu", "partialdiff",
+    "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega", "ae",
+    "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal",
+    "Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace", "Agrave", "Atilde",
+    "Otilde", "OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency",
+    "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl", "periodcentered", "quotesinglbase",
+    "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute", "Edieresis", "Egrave",
+    "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex", "apple", "Ograve",
+    "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde", "macron", "breve",
+    "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron", "Lslash", "lslash",
+    "Scaron", "scaron", "Zcaron", "zcaron", "brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn",
+    "thorn", "minus", "multiply", "onesuperior", "twosuperior", "threesuperior", "onehalf",
+    "onequarter", "threequarters", "franc", "Gbreve", "gbreve", "Idotaccent", "Scedilla",
+    "scedilla", "Cacute", "cacute", "Ccaron", "ccaron", "dcroat",
+];
+
+/// The `name` record ID of the family name.
+pub(crate) const NAME_ID_FAMILY: u16 = 1;
+/// The `name` record ID of the version string.
+pub(crate) const NAME_ID_VERSION: u16 = 5;
+/// The `name` record ID of the typographic family name, which groups more styles than name ID 1.
+pub(crate) const NAME_ID_TYPOGRAPHIC_FAMILY: u16 = 16;
+
+#[inline]
+pub(crate) fn read_u16(table: &[u8], offset: usize) -> Option<u16> {
+    table.get(offset..(offset + 2)).map(BigEndian::read_u16)
+}
+
+#[inline]
+pub(crate) fn read_i16(table: &[u8], offset: usize) -> Option<i16> {
+    table.get(offset..(offset + 2)).map(BigEndian::read_i16)
+}
+
+#[inline]
+pub(crate) fn read_u32(table: &[u8], offset: usize) -> Option<u32> {
+    table.get(offset..(offset + 4)).map(BigEndian::read_u32)
+}
+
+#[inline]
+pub(crate) fn read_i32(table: &[u8], offset: usize) -> Option<i32> {
+    table.get(offset..(offset + 4)).map(BigEndian::read_i32)
+}
+
+/// Reads a 16.16 fixed-point number.
+#[inline]
+pub(crate) fn read_fixed(table: &[u8], offset: usize) -> Option<f32> {
+    read_i32(table, offset).map(|value| value as f32 / 65536.0)
+}
+
+/// Returns `OS/2.fsType`.
+pub(crate) fn os2_fs_type(os2: &[u8]) -> Option<u16> {
+    read_u16(os2, 8)
+}
+
+/// Returns `OS/2.ulUnicodeRange1`..`ulUnicodeRange4`.
+pub(crate) fn os2_unicode_ranges(os2: &[u8]) -> Option<[u32; 4]> {
+    Some([
+        read_u32(os2, 42)?,
+        read_u32(os2, 46)?,
+        read_u32(os2, 50)?,
+        read_u32(os2, 54)?,
+    ])
+}
+
+/// Returns `OS/2.ulCodePageRange1` and `ulCodePageRange2`, or `None` if the table predates
+/// version 1.
+pub(crate) fn os2_codepage_ranges(os2: &[u8]) -> Option<[u32; 2]> {
+    if read_u16(os2, 0)? < 1 {
+        return None;
+    }
+    Some([read_u32(os2, 78)?, read_u32(os2, 82)?])
+}
+
+/// Looks up a Unicode code point in the best Unicode subtable of `cmap`.
+///
+/// Full-repertoire subtables are preferred over BMP-only ones. Returns `None` if there is no
+/// Unicode subtable in a supported format (4, 6, or 12), or if the table is truncated.
+pub(crate) fn cmap_lookup(cmap: &[u8], code_point: u32) -> Option<GlyphMapping> {
+    let mut best: Option<(u32, usize)> = None;
+    for record in 0..(read_u16(cmap, 2)? as usize) {
+        let record_offset = 4 + record * 8;
+        let platform_id = read_u16(cmap, record_offset)?;
+        let encoding_id = read_u16(cmap, record_offset + 2)?;
+        let subtable_offset = read_u32(cmap, record_offset + 4)? as usize;
+        let priority = match (platform_id, encoding_id) {
+            (3, 10) => 4,
+            (0, 4) | (0, 6) => 3,
+            (3, 1) => 2,
+            (0, 0..=3) => 1,
+            _ => continue,
+        };
+        match read_u16(cmap, subtable_offset) {
+            Some(4) | Some(6) | Some(12) => {}
+            _ => continue,
+        }
+        if best.map_or(true, |(best_priority, _)| priority > best_priority) {
+            best = Some((priority, subtable_offset));
+        }
+    }
+
+    let subtable = cmap.get(best?.1..)?;
+    let glyph_id = match read_u16(subtable, 0)? {
+        4 => cmap_format_4_lookup(subtable, code_point)?,
+        6 => cmap_format_6_lookup(subtable, code_point)?,
+        _ => cmap_format_12_lookup(subtable, code_point)?,
+    };
+    Some(match glyph_id {
+        None => GlyphMapping::Unmapped,
+        Some(0) => GlyphMapping::NotDef,
+        Some(glyph_id) => GlyphMapping::Mapped(glyph_id),
+    })
+}
+
+// Segment mapping to delta values.
+fn cmap_format_4_lookup(subtable: &[u8], code_point: u32) -> Option<Option<u32>> {
+    // 0xffff only appears as the mandatory terminating segment.
+    if code_point >= 0xffff {
+        return Some(None);
+    }
+    let code_point = code_point as u16;
+    let seg_count_x2 = read_u16(subtable, 6)? as usize;
+    let (end_codes, start_codes) = (14, 16 + seg_count_x2);
+    let (id_deltas, id_range_offsets) = (16 + seg_count_x2 * 2, 16 + seg_count_x2 * 3);
+    for segment in (0..seg_count_x2).step_by(2) {
+        if read_u16(subtable, end_codes + segment)? < code_point {
+            continue;
+        }
+        let start_code = read_u16(subtable, start_codes + segment)?;
+        if start_code > code_point {
+            return Some(None);
+        }
+        let id_delta = read_u16(subtable, id_deltas + segment)?;
+        let id_range_offset_pos = id_range_offsets + segment;
+        let glyph_id = match read_u16(subtable, id_range_offset_pos)? {
+            0 => code_point.wrapping_add(id_delta),
+            id_range_offset => {
+                let glyph_id_pos = id_range_offset_pos
+                    + id_range_offset as usize
+                    + (code_point - start_code) as usize * 2;
+                match read_u16(subtable, glyph_id_pos)? {
+                    0 => 0,
+                    glyph_id => glyph_id.wrapping_add(id_delta),
+                }
+            }
+        };
+        return Some(Some(glyph_id as u32));
+    }
+    Some(None)
+}
+
+// Trimmed table mapping.
+fn cmap_format_6_lookup(subtable: &[u8], code_point: u32) -> Option<Option<u32>> {
+    let first_code = read_u16(subtable, 6)? as u32;
+    let entry_count = read_u16(subtable, 8)? as u32;
+    if code_point < first_code || code_point >= first_code + entry_count {
+        return Some(None);
+    }
+    let index = (code_point - first_code) as usize;
+    read_u16(subtable, 10 + index * 2).map(|glyph_id| Some(glyph_id as u32))
+}
+
+// Segmented coverage.
+fn cmap_format_12_lookup(subtable: &[u8], code_point: u32) -> Option<Option<u32>> {
+    let num_groups = read_u32(subtable, 12)? as usize;
+    for group in 0..num_groups {
+        let group_offset = 16 + group * 12;
+        let start_char_code = read_u32(subtable, group_offset)?;
+        let end_char_code = read_u32(subtable, group_offset + 4)?;
+        if code_point < start_char_code || code_point > end_char_code {
+            continue;
+        }
+        let start_glyph_id = read_u32(subtable, group_offset + 8)?;
+        return Some(Some(start_glyph_id + (code_point - start_char_code)));
+    }
+    Some(None)
+}
+
+/// Returns the offsets of the device records in the `hdmx` table, given its 8-byte header.
+///
+/// Each device record holds a pixel size, a maximum width, and one width per glyph, padded to
+/// `sizeDeviceRecord` bytes, so a glyph's width is `2 + glyph_id` bytes into its record. Returns
+/// `None` if the records are too short to hold a width for `glyph_id`. The caller checks that
+/// `glyph_id` is below the font's glyph count.
+pub(crate) fn hdmx_record_offsets(
+    header: &[u8],
+    glyph_id: u32,
+) -> Option<impl Iterator<Item = usize>> {
+    let record_count = read_i16(header, 2)?.max(0) as usize;
+    let record_size = read_i32(header, 4)?.max(0) as usize;
+    if glyph_id as usize + 2 >= record_size {
+        return None;
+    }
+    Some((0..record_count).map(move |index| 8 + index * record_size))
+}
+
+/// Returns the offset of a glyph's `LTSH.yPels` entry, the ppem from which its hinted advance
+/// scales linearly, given the table's 4-byte header.
+pub(crate) fn ltsh_entry_offset(header: &[u8], glyph_id: u32) -> Option<usize> {
+    if glyph_id >= u32::from(read_u16(header, 2)?) {
+        return None;
+    }
+    Some(4 + glyph_id as usize)
+}
+
+/// Returns `hhea.ascender`, `descender`, and `lineGap`.
+pub(crate) fn hhea_line_metrics(hhea: &[u8]) -> Option<[i16; 3]> {
+    Some([read_i16(hhea, 4)?, read_i16(hhea, 6)?, read_i16(hhea, 8)?])
+}
+
+/// Returns `hhea.caretSlopeRise`, `caretSlopeRun`, and `caretOffset`.
+pub(crate) fn hhea_caret(hhea: &[u8]) -> Option<[i16; 3]> {
+    Some([
+        read_i16(hhea, 18)?,
+        read_i16(hhea, 20)?,
+        read_i16(hhea, 22)?,
+    ])
+}
+
+/// Returns `OS/2.sTypoAscender`, `sTypoDescender`, and `sTypoLineGap`.
+pub(crate) fn os2_typo_line_metrics(os2: &[u8]) -> Option<[i16; 3]> {
+    Some([read_i16(os2, 68)?, read_i16(os2, 70)?, read_i16(os2, 72)?])
+}
+
+/// Returns `OS/2.usWinAscent` and `usWinDescent`.
+pub(crate) fn os2_win_metrics(os2: &[u8]) -> Option<[u16; 2]> {
+    Some([read_u16(os2, 74)?, read_u16(os2, 76)?])
+}
+
+/// Returns `OS/2.sTypoLineGap`, `usWinAscent`, and `usWinDescent`, with the descent negated to
+/// match `Metrics::descent`.
+pub(crate) fn os2_leading_metrics(os2: &[u8]) -> Option<[f32; 3]> {
+    let [_, _, typo_line_gap] = os2_typo_line_metrics(os2)?;
+    let [win_ascent, win_descent] = os2_win_metrics(os2)?;
+    Some([
+        typo_line_gap as f32,
+        win_ascent as f32,
+        -(win_descent as f32),
+    ])
+}
+
+/// Returns whether `OS/2.fsSelection` has the `USE_TYPO_METRICS` bit set.
+pub(crate) fn os2_use_typo_metrics(os2: &[u8]) -> Option<bool> {
+    read_u16(os2, 62).map(|fs_selection| (fs_selection & (1 << 7)) != 0)
+}
+
+/// Returns the index of `glyph_id` in an OpenType coverage table, or `None` if the glyph isn't
+/// covered.
+pub(crate) fn coverage_index(coverage: &[u8], glyph_id: u32) -> Option<usize> {
+    let glyph_id = u16::try_from(glyph_id).ok()?;
+    let count = read_u16(coverage, 2)? as usize;
+    match read_u16(coverage, 0)? {
+        1 => (0..count).find(|&index| read_u16(coverage, 4 + index * 2) == Some(glyph_id)),
+        2 => (0..count).find_map(|index| {
+            let record_offset = 4 + index * 6;
+            let start_glyph_id = read_u16(coverage, record_offset)?;
+            let end_glyph_id = read_u16(coverage, record_offset + 2)?;
+            let start_coverage_index = read_u16(coverage, record_offset + 4)?;
+            if glyph_id < start_glyph_id || glyph_id > end_glyph_id {
+                return None;
+            }
+            Some(start_coverage_index as usize + (glyph_id - start_glyph_id) as usize)
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the class of `glyph_id` in an OpenType class definition table. Glyphs that aren't
+/// listed are in class 0.
+pub(crate) fn class_def_lookup(class_def: &[u8], glyph_id: u32) -> Option<u16> {
+    let glyph_id = match u16::try_from(glyph_id) {
+        Ok(glyph_id) => glyph_id,
+        Err(_) => return Some(0),
+    };
+    match read_u16(class_def, 0)? {
+        1 => {
+            let start_glyph_id = read_u16(class_def, 2)?;
+            let glyph_count = read_u16(class_def, 4)?;
+            if glyph_id < start_glyph_id || glyph_id - start_glyph_id >= glyph_count {
+                return Some(0);
+            }
+            read_u16(class_def, 6 + (glyph_id - start_glyph_id) as usize * 2)
+        }
+        2 => {
+            for index in 0..(read_u16(class_def, 2)? as usize) {
+                let record_offset = 4 + index * 6;
+                let start_glyph_id = read_u16(class_def, record_offset)?;
+                let end_glyph_id = read_u16(class_def, record_offset + 2)?;
+                if glyph_id >= start_glyph_id && glyph_id <= end_glyph_id {
+                    return read_u16(class_def, record_offset + 4);
+                }
+            }
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the class of a glyph from the `GDEF` glyph class definition: 1 for base glyphs, 2 for
+/// ligatures, 3 for marks, 4 for components, and 0 for unclassified glyphs.
+pub(crate) fn gdef_glyph_class(gdef: &[u8], glyph_id: u32) -> Option<u16> {
+    match read_u16(gdef, 4)? as usize {
+        0 => Some(0),
+        class_def_offset => class_def_lookup(gdef.get(class_def_offset..)?, glyph_id),
+    }
+}
+
+/// Returns the caret positions for a ligature glyph from the `GDEF` ligature caret list, in font
+/// units.
+///
+/// Carets expressed as contour points (format 2) are skipped, since resolving them requires the
+/// hinted outline.
+pub(crate) fn gdef_ligature_carets(gdef: &[u8], glyph_id: u32) -> Option<Vec<f32>> {
+    let lig_caret_list = gdef.get((read_u16(gdef, 8)? as usize)..)?;
+    let coverage = lig_caret_list.get((read_u16(lig_caret_list, 0)? as usize)..)?;
+    let index = coverage_index(coverage, glyph_id)?;
+    if index >= read_u16(lig_caret_list, 2)? as usize {
+        return None;
+    }
+    let lig_glyph_offset = read_u16(lig_caret_list, 4 + index * 2)? as usize;
+    let lig_glyph = lig_caret_list.get(lig_glyph_offset..)?;
+
+    let mut carets = vec![];
+    for caret in 0..(read_u16(lig_glyph, 0)? as usize) {
+        let caret_value = lig_glyph.get((read_u16(lig_glyph, 2 + caret * 2)? as usize)..)?;
+        if matches!(read_u16(caret_value, 0)?, 1 | 3) {
+            carets.push(read_i16(caret_value, 2)? as f32);
+        }
+    }
+    Some(carets)
+}
+
+/// Returns the byte range of a glyph's entries in the `loca` table, given the `indexToLocFormat`
+/// field of the `head` table.
+pub(crate) fn loca_entries_range(index_to_loc_format: i16, glyph_id: u32) -> Range<usize> {
+    let entry_size = if index_to_loc_format == 0 { 2 } else { 4 };
+    let start = glyph_id as usize * entry_size;
+    start..(start + entry_size * 2)
+}
+
+/// Returns the byte range of a glyph in the `glyf` table from its two `loca` entries, as read from
+/// `loca_entries_range()`.
+pub(crate) fn glyf_range(index_to_loc_format: i16, loca_entries: &[u8]) -> Option<Range<usize>> {
+    let (start, end) = match index_to_loc_format {
+        0 => (
+            read_u16(loca_entries, 0)? as usize * 2,
+            read_u16(loca_entries, 2)? as usize * 2,
+        ),
+        _ => (
+            read_u32(loca_entries, 0)? as usize,
+            read_u32(loca_entries, 4)? as usize,
+        ),
+    };
+    if start <= end {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+/// Counts the contours and points of a simple glyph from its data in the `glyf` table, reading its
+/// header and point flags alone.
+///
+/// As in `Loader::glyph_complexity()`, the on-curve points include those implied midway between
+/// two consecutive off-curve points of a contour.
+///
+/// Returns `None` for composite glyphs, whose points come from other glyphs, and if the glyph is
+/// truncated. Glyphs with no outline have no contours or points.
+pub(crate) fn glyf_stats(glyph: &[u8]) -> Option<GlyphStats> {
+    if glyph.is_empty() {
+        return Some(GlyphStats::default());
+    }
+    let contour_count = read_i16(glyph, 0)?;
+    if contour_count < 0 {
+        return None;
+    }
+    let contour_count = contour_count as usize;
+    if contour_count == 0 {
+        return Some(GlyphStats::default());
+    }
+    let point_count = read_u16(glyph, 10 + (contour_count - 1) * 2)? as usize + 1;
+
+    // Flags follow the instructions. A flag with the repeat bit set is followed by the number of
+    // extra points that share it.
+    let instructions_length = read_u16(glyph, 10 + contour_count * 2)? as usize;
+    let mut offset = 12 + contour_count * 2 + instructions_length;
+    let mut on_curve = Vec::with_capacity(point_count);
+    while on_curve.len() < point_count {
+        let flag = *glyph.get(offset)?;
+        offset += 1;
+        let mut repeat_count = 1;
+        if flag & 0x08 != 0 {
+            repeat_count += *glyph.get(offset)? as usize;
+            offset += 1;
+        }
+        let repeat_count = repeat_count.min(point_count - on_curve.len());
+        on_curve.extend(std::iter::repeat(flag & 0x01 != 0).take(repeat_count));
+    }
+
+    // Each pair of consecutive off-curve points in a contour, wrapping around at its end, implies
+    // an on-curve point between them.
+    let mut contour_start = 0;
+    let (mut on_curve_point_count, mut off_curve_point_count) = (0, 0);
+    for contour in 0..contour_count {
+        let contour_end = read_u16(glyph, 10 + contour * 2)? as usize + 1;
+        let contour_points = on_curve.get(contour_start..contour_end)?;
+        for (index, &point_on_curve) in contour_points.iter().enumerate() {
+            if point_on_curve {
+                on_curve_point_count += 1;
+                continue;
+            }
+            off_curve_point_count += 1;
+            if !contour_points[(index + 1) % contour_points.len()] {
+                on_curve_point_count += 1;
+            }
+        }
+        contour_start = contour_end;
+    }
+
+    Some(GlyphStats {
+        contour_count: contour_count as u32,
+        point_count: (on_curve_point_count + off_curve_point_count) as u32,
+        on_curve_point_count: on_curve_point_count as u32,
+        off_curve_point_count: off_curve_point_count as u32,
+    })
+}
+
+/// Returns the ScriptLangTags in the `meta` data map with the given tag, which is a
+/// comma-separated UTF-8 list. Whitespace around the tags is ignored.
+pub(crate) fn meta_languages(meta: &[u8], tag: u32) -> Option<Vec<String>> {
+    for map in 0..(read_u32(meta, 12)? as usize) {
+        let record = 16 + map * 12;
+        if read_u32(meta, record)? != tag {
+            continue;
+        }
+        let offset = read_u32(meta, record + 4)? as usize;
+        let length = read_u32(meta, record + 8)? as usize;
+        let data = std::str::from_utf8(meta.get(offset..(offset + length))?).ok()?;
+        return Some(
+            data.split(',')
+                .map(str::trim)
+                .filter(|language| !language.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        );
+    }
+    None
+}
+
+/// Returns `head.fontRevision`.
+pub(crate) fn head_font_revision(head: &[u8]) -> Option<f32> {
+    read_fixed(head, 4)
+}
+
+/// Returns the string with the given name ID, preferring US English Windows Unicode records,
+/// then other Windows Unicode records, then Unicode platform records, then Mac Roman records.
+pub(crate) fn name_string(name: &[u8], name_id: u16) -> Option<String> {
+    let count = read_u16(name, 2)? as usize;
+    let storage = name.get(read_u16(name, 4)? as usize..)?;
+
+    let mut best: Option<(u8, &[u8], bool)> = None;
+    for record_index in 0..count {
+        let record = name.get((6 + record_index * 12)..(18 + record_index * 12))?;
+        if BigEndian::read_u16(&record[6..]) != name_id {
+            continue;
+        }
+        let (platform_id, encoding_id, language_id) = (
+            BigEndian::read_u16(&record[0..]),
+            BigEndian::read_u16(&record[2..]),
+            BigEndian::read_u16(&record[4..]),
+        );
+        let (rank, utf16) = match (platform_id, encoding_id) {
+            (3, 1) | (3, 10) if language_id == 0x0409 => (0, true),
+            (3, 1) | (3, 10) => (1, true),
+            (0, _) => (2, true),
+            (1, 0) => (3, false),
+            _ => continue,
+        };
+        if best.is_some_and(|(best_rank, _, _)| best_rank <= rank) {
+            continue;
+        }
+        let (length, offset) = (
+            BigEndian::read_u16(&record[8..]) as usize,
+            BigEndian::read_u16(&record[10..]) as usize,
+        );
+        if let Some(bytes) = storage.get(offset..(offset + length)) {
+            best = Some((rank, bytes, utf16));
+        }
+    }
+
+    let (_, bytes, utf16) = best?;
+    Some(decode_name(bytes, utf16))
+}
+
+/// Returns every distinct string with the given name ID, in all languages, in record order.
+///
+/// Only Unicode and Mac Roman records are read, as in `name_string()`.
+pub(crate) fn name_strings(name: &[u8], name_id: u16) -> Vec<String> {
+    let mut strings = vec![];
+    let (count, storage) = match (read_u16(name, 2), read_u16(name, 4)) {
+        (Some(count), Some(storage_offset)) => match name.get(storage_offset as usize..) {
+            Some(storage) => (count as usize, storage),
+            None => return strings,
+        },
+        _ => return strings,
+    };
+    for record_index in 0..count {
+        let record = match name.get((6 + record_index * 12)..(18 + record_index * 12)) {
+            Some(record) => record,
+            None => break,
+        };
+        if BigEndian::read_u16(&record[6..]) != name_id {
+            continue;
+        }
+        let utf16 = match (
+            BigEndian::read_u16(&record[0..]),
+            BigEndian::read_u16(&record[2..]),
+        ) {
+            (3, 1) | (3, 10) | (0, _) => true,
+            (1, 0) => false,
+            _ => continue,
+        };
+        let (length, offset) = (
+            BigEndian::read_u16(&record[8..]) as usize,
+            BigEndian::read_u16(&record[10..]) as usize,
+        );
+        if let Some(bytes) = storage.get(offset..(offset + length)) {
+            let string = decode_name(bytes, utf16);
+            if !strings.contains(&string) {
+                strings.push(string);
+            }
+        }
+    }
+    strings
+}
+
+fn decode_name(bytes: &[u8], utf16: bool) -> String {
+    if utf16 {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(BigEndian::read_u16).collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        // Only the ASCII subset of Mac Roman is decoded.
+        bytes
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii() {
+                    char::from(byte)
+                } else {
+                    char::REPLACEMENT_CHARACTER
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the variation axis records of an `fvar` table, in order.
+pub(crate) fn fvar_axes(fvar: &[u8]) -> Option<Vec<VariationAxis>> {
+    let axes_array_offset = read_u16(fvar, 4)? as usize;
+    let axis_count = read_u16(fvar, 8)? as usize;
+    let axis_size = read_u16(fvar, 10)? as usize;
+    (0..axis_count)
+        .map(|index| {
+            let offset = axes_array_offset + index * axis_size;
+            Some(VariationAxis {
+                tag: read_u32(fvar, offset)?,
+                min_value: read_fixed(fvar, offset + 4)?,
+                default_value: read_fixed(fvar, offset + 8)?,
+                max_value: read_fixed(fvar, offset + 12)?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the subfamily name ID and the axis coordinates of each named instance record of an
+/// `fvar` table, in order. Coordinates are listed in the order of the axes.
+pub(crate) fn fvar_instances(fvar: &[u8]) -> Option<Vec<(u16, Vec<f32>)>> {
+    let axes_array_offset = read_u16(fvar, 4)? as usize;
+    let axis_count = read_u16(fvar, 8)? as usize;
+    let axis_size = read_u16(fvar, 10)? as usize;
+    let instance_count = read_u16(fvar, 12)? as usize;
+    let instance_size = read_u16(fvar, 14)? as usize;
+    let instances_offset = axes_array_offset + axis_count * axis_size;
+    (0..instance_count)
+        .map(|index| {
+            let offset = instances_offset + index * instance_size;
+            let coordinates = (0..axis_count)
+                .map(|axis_index| read_fixed(fvar, offset + 4 + axis_index * 4))
+                .collect::<Option<_>>()?;
+            Some((read_u16(fvar, offset)?, coordinates))
+        })
+        .collect()
+}
+
+/// Returns the coordinate of `baseline_tag` for `script` on the horizontal axis of a `BASE` table.
+///
+/// Scripts without a record of their own use the `DFLT` record, if there is one.
+pub(crate) fn base_coordinate(base: &[u8], script: u32, baseline_tag: u32) -> Option<i16> {
+    let axis = subtable(base, read_u16(base, 4))?;
+    let tag_list = subtable(axis, read_u16(axis, 0))?;
+    let baseline_index = (0..read_u16(tag_list, 0)? as usize)
+        .find(|&index| read_u32(tag_list, 2 + index * 4) == Some(baseline_tag))?;
+
+    let script_list = subtable(axis, read_u16(axis, 2))?;
+    let script_count = read_u16(script_list, 0)? as usize;
+    let find_script = |script: u32| {
+        (0..script_count)
+            .map(|index| 2 + index * 6)
+            .find(|&record_offset| read_u32(script_list, record_offset) == Some(script))
+            .and_then(|record_offset| read_u16(script_list, record_offset + 4))
+    };
+    let script_table = subtable(
+        script_list,
+        find_script(script).or_else(|| find_script(TAG_DFLT)),
+    )?;
+
+    // `BaseValues`: a default baseline index and a count, then one `BaseCoord` offset per tag.
+    let base_values = subtable(script_table, read_u16(script_table, 0))?;
+    if baseline_index >= read_u16(base_values, 2)? as usize {
+        return None;
+    }
+    let base_coord = subtable(base_values, read_u16(base_values, 4 + baseline_index * 2))?;
+
+    // All three `BaseCoord` formats start with the format and the coordinate.
+    read_i16(base_coord, 2)
+}
+
+// Follows an offset to a subtable. Null offsets mean the subtable is absent.
+fn subtable(table: &[u8], offset: Option<u16>) -> Option<&[u8]> {
+    match offset? {
+        0 => None,
+        offset => table.get(offset as usize..),
+    }
+}
+
+/// Returns `post.italicAngle`, in counterclockwise degrees from the vertical.
+pub(crate) fn post_italic_angle(post: &[u8]) -> Option<f32> {
+    read_fixed(post, 4)
+}
+
+/// Returns the name of a glyph from the `post` table.
+///
+/// Format 1 fonts name their glyphs in the standard Macintosh ordering, and format 2 fonts index
+/// into that ordering or into their own Pascal strings. Other formats store no names.
+pub(crate) fn post_glyph_name(post: &[u8], glyph_id: u32) -> Option<String> {
+    let name_index = match read_u32(post, 0)? {
+        0x00010000 => {
+            return MAC_GLYPH_NAMES
+                .get(glyph_id as usize)
+                .map(|&name| name.to_owned())
+        }
+        0x00020000 => {
+            if glyph_id >= u32::from(read_u16(post, 32)?) {
+                return None;
+            }
+            read_u16(post, 34 + glyph_id as usize * 2)? as usize
+        }
+        _ => return None,
+    };
+    if name_index < MAC_GLYPH_NAME_COUNT {
+        return Some(MAC_GLYPH_NAMES[name_index].to_owned());
+    }
+
+    // The custom names follow the index array, each prefixed by its length.
+    let mut offset = 34 + read_u16(post, 32)? as usize * 2;
+    for _ in MAC_GLYPH_NAME_COUNT..name_index {
+        offset += 1 + *post.get(offset)? as usize;
+    }
+    let length = *post.get(offset)? as usize;
+    let name = post.get((offset + 1)..(offset + 1 + length))?;
+    Some(name.iter().map(|&byte| byte as char).collect())
+}
+
+/// Assembles a standalone SFNT font file from its tables, computing the table directory, the
+/// checksums, and `head.checkSumAdjustment`.
+///
+/// Only the FreeType loader, which may have to reassemble a font from its tables, uses this.
+#[cfg(any(
+    all(
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "windows",
+            target_arch = "wasm32"
+        )),
+        not(feature = "pure-rust")
+    ),
+    feature = "loader-freetype"
+))]
+pub(crate) fn build_sfnt(mut font_tables: Vec<(u32, Box<[u8]>)>) -> Vec<u8> {
+    // The SFNT versions of fonts with TrueType outlines and with CFF outlines (`OTTO`).
+    const SFNT_VERSION_TRUETYPE: u32 = 0x00010000;
+    const SFNT_VERSION_CFF: u32 = 0x4f54544f;
+
+    font_tables.sort_by_key(|&(tag, _)| tag);
+    let is_cff = font_tables
+        .iter()
+        .any(|&(tag, _)| tag == TAG_CFF || tag == TAG_CFF2);
+    let table_count = font_tables.len() as u16;
+    let entry_selector = 15 - table_count.max(1).leading_zeros() as u16;
+    let search_range = (1 << entry_selector) * 16;
+
+    let mut font_data = vec![0; 12 + font_tables.len() * 16];
+    let sfnt_version = if is_cff {
+        SFNT_VERSION_CFF
+    } else {
+        SFNT_VERSION_TRUETYPE
+    };
+    BigEndian::write_u32(&mut font_data[0..], sfnt_version);
+    BigEndian::write_u16(&mut font_data[4..], table_count);
+    BigEndian::write_u16(&mut font_data[6..], search_range);
+    BigEndian::write_u16(&mut font_data[8..], entry_selector);
+    BigEndian::write_u16(&mut font_data[10..], table_count * 16 - search_range);
+
+    let mut head_offset = None;
+    for (index, (tag, table)) in font_tables.iter().enumerate() {
+        let offset = font_data.len();
+        font_data.extend_from_slice(table);
+        // The checksum adjustment is computed over the whole file with the field zeroed.
+        if *tag == TAG_HEAD && table.len() >= 12 {
+            font_data[(offset + 8)..(offset + 12)].copy_from_slice(&[0; 4]);
+            head_offset = Some(offset);
+        }
+        let checksum = table_checksum(&font_data[offset..]);
+        font_data.resize((font_data.len() + 3) & !3, 0);
+
+        let record = &mut font_data[(12 + index * 16)..(28 + index * 16)];
+        BigEndian::write_u32(&mut record[0..], *tag);
+        BigEndian::write_u32(&mut record[4..], checksum);
+        BigEndian::write_u32(&mut record[8..], offset as u32);
+        BigEndian::write_u32(&mut record[12..], table.len() as u32);
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xb1b0afba_u32.wrapping_sub(table_checksum(&font_data));
+        BigEndian::write_u32(&mut font_data[(head_offset + 8)..], adjustment);
+    }
+    font_data
+}
+
+// Sums the data as big-endian 32-bit words, padding the end with zeroes.
+#[cfg(any(
+    all(
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_family = "windows",
+            target_arch = "wasm32"
+        )),
+        not(feature = "pure-rust")
+    ),
+    feature = "loader-freetype"
+))]
+fn table_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0, |sum: u32, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(BigEndian::read_u32(&word))
+    })
+}
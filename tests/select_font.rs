@@ -268,6 +268,27 @@ mod test {
         assert!(filenames.iter().any(|name| name == "DejaVuSans.ttf"));
     }
 
+    #[test]
+    fn select_fonts_bold() {
+        let bold = Properties {
+            style: font_kit::properties::Style::Normal,
+            weight: font_kit::properties::Weight::BOLD,
+            stretch: font_kit::properties::Stretch::NORMAL,
+        };
+        let handles = SystemSource::new().select_fonts(&bold).unwrap();
+        assert!(handles.iter().any(|handle| match *handle {
+            Handle::Path { ref path, .. } => {
+                path.file_name() == Some(OsStr::new("DejaVuSans-Bold.ttf"))
+            }
+            _ => false,
+        }));
+        for handle in &handles {
+            let properties = handle.load().unwrap().properties();
+            assert_eq!(properties.style, font_kit::properties::Style::Normal);
+            assert!((properties.weight.0 - 700.0).abs() <= 50.0);
+        }
+    }
+
     #[allow(non_snake_case)]
     #[test]
     fn select_by_postscript_name_ArialMT() {